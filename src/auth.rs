@@ -0,0 +1,287 @@
+//! Authentication strategies used by `ClientContext` to sign every request.
+
+use std::sync::{Mutex, PoisonError};
+
+use base64::engine::{Engine, general_purpose::STANDARD as BASE64_STANDARD};
+use reqwest::blocking::Client as HttpClient;
+use reqwest::header::{AUTHORIZATION, COOKIE, HeaderName, HeaderValue, LOCATION, SET_COOKIE};
+use reqwest::redirect::Policy;
+use zeroize::Zeroizing;
+
+use crate::errors::ClientError;
+use crate::middleware::ApiRequest;
+use crate::secret::Secret;
+
+/// Information about the client an `Authenticator` works for
+pub struct AuthContext<'a> {
+    /// HTTP client used for the API calls
+    pub http_client: &'a HttpClient,
+    /// API base URL, e.g. `https://online.actitime.com/acme/api/v1`
+    pub base_url: &'a str,
+}
+
+/// Adds credentials to the requests sent by a `ClientContext`
+///
+/// Implementations must be thread-safe, as one `ClientContext` can be shared by many threads.
+pub trait Authenticator: Send + Sync {
+    /// Adds the credentials to an outgoing request.
+    ///
+    /// # Arguments
+    /// * `context` - The client the request is sent through.
+    /// * `request` - The request to sign.
+    fn authenticate(
+        &self,
+        context: &AuthContext<'_>,
+        request: &mut ApiRequest,
+    ) -> Result<(), ClientError>;
+
+    /// Refreshes the credentials after the server answered `401 Unauthorized`.
+    ///
+    /// # Returns
+    /// `true` if the credentials changed and the request should be sent again.
+    fn reauthenticate(&self, _context: &AuthContext<'_>) -> Result<bool, ClientError> {
+        Ok(false)
+    }
+
+    /// Ends the authenticated session, if the strategy has one.
+    fn logout(&self, _context: &AuthContext<'_>) -> Result<(), ClientError> {
+        Ok(())
+    }
+}
+
+/// HTTP Basic authentication, sending the credentials with every request
+///
+/// The header value is kept as a `Secret` and only turned into a `HeaderValue` for each
+/// request, so it is wiped from memory with the authenticator.
+pub struct BasicAuthenticator {
+    authorization: Secret,
+}
+
+impl BasicAuthenticator {
+    /// Creates a Basic authenticator for a user.
+    pub fn new(username: &str, password: &str) -> Result<Self, ClientError> {
+        let credentials = Zeroizing::new(format!("{}:{}", username, password));
+        let token = Zeroizing::new(BASE64_STANDARD.encode(credentials.as_bytes()));
+        let authorization = Secret::new(format!("Basic {}", token.as_str()));
+        sensitive_header_value(&authorization)?;
+        Ok(BasicAuthenticator { authorization })
+    }
+}
+
+impl Authenticator for BasicAuthenticator {
+    fn authenticate(
+        &self,
+        _context: &AuthContext<'_>,
+        request: &mut ApiRequest,
+    ) -> Result<(), ClientError> {
+        request
+            .headers
+            .insert(AUTHORIZATION, sensitive_header_value(&self.authorization)?);
+        Ok(())
+    }
+}
+
+/// Custom header token, e.g. `Authorization: Bearer ...` or an API gateway key
+pub struct HeaderTokenAuthenticator {
+    name: HeaderName,
+    value: Secret,
+}
+
+impl HeaderTokenAuthenticator {
+    /// Creates an authenticator sending `name: value` with every request.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let authenticator = HeaderTokenAuthenticator::new("X-Api-Key", &api_key)?;
+    /// client_context.set_authenticator(authenticator);
+    /// ```
+    pub fn new(name: &str, value: &str) -> Result<Self, ClientError> {
+        let name =
+            HeaderName::from_bytes(name.as_bytes()).map_err(|e| ClientError::Other(Box::new(e)))?;
+        let value = Secret::from(value);
+        sensitive_header_value(&value)?;
+        Ok(HeaderTokenAuthenticator { name, value })
+    }
+
+    /// Creates an authenticator sending `Authorization: Bearer <token>`.
+    pub fn bearer(token: &str) -> Result<Self, ClientError> {
+        let value = Secret::new(format!("Bearer {}", token));
+        sensitive_header_value(&value)?;
+        Ok(HeaderTokenAuthenticator {
+            name: AUTHORIZATION,
+            value,
+        })
+    }
+}
+
+impl Authenticator for HeaderTokenAuthenticator {
+    fn authenticate(
+        &self,
+        _context: &AuthContext<'_>,
+        request: &mut ApiRequest,
+    ) -> Result<(), ClientError> {
+        request
+            .headers
+            .insert(self.name.clone(), sensitive_header_value(&self.value)?);
+        Ok(())
+    }
+}
+
+/// Session authentication: logs in once through the web login form and sends the
+/// session cookie instead of the password
+///
+/// The login happens on the first request and is checked with `GET /users/me`, so wrong
+/// credentials fail with `ClientError::Unauthorized` right away. When the session expires and
+/// the server answers `401 Unauthorized`, the authenticator logs in again and the request is
+/// retried once.
+pub struct SessionAuthenticator {
+    username: String,
+    password: Secret,
+    login_path: String,
+    logout_path: String,
+    login_client: HttpClient,
+    cookie: Mutex<Option<Secret>>,
+}
+
+impl SessionAuthenticator {
+    /// Creates a session authenticator using the actiTIME login form (`/login.do`).
+    pub fn new(username: &str, password: &str) -> Result<Self, ClientError> {
+        let login_client = HttpClient::builder()
+            .redirect(Policy::none())
+            .timeout(std::time::Duration::from_secs(10))
+            .build()
+            .map_err(ClientError::Reqwest)?;
+        Ok(SessionAuthenticator {
+            username: username.to_string(),
+            password: Secret::from(password),
+            login_path: "/login.do".to_string(),
+            logout_path: "/logout.do".to_string(),
+            login_client,
+            cookie: Mutex::new(None),
+        })
+    }
+
+    /// Sets the login and logout paths, relative to the site root (the base URL without `/api/v1`).
+    pub fn with_paths(mut self, login_path: &str, logout_path: &str) -> Self {
+        self.login_path = login_path.to_string();
+        self.logout_path = logout_path.to_string();
+        self
+    }
+
+    /// Returns `true` if a session cookie is currently held.
+    pub fn has_session(&self) -> bool {
+        self.lock_cookie().is_some()
+    }
+
+    fn login(&self, context: &AuthContext<'_>) -> Result<Secret, ClientError> {
+        let url = format!("{}{}", site_root(context.base_url), self.login_path);
+        let response = self
+            .login_client
+            .post(&url)
+            .form(&[
+                ("username", self.username.as_str()),
+                ("pwd", self.password.expose_secret()),
+            ])
+            .send()
+            .map_err(ClientError::Reqwest)?;
+
+        if !(response.status().is_success() || response.status().is_redirection()) {
+            return Err(ClientError::Unauthorized);
+        }
+        // A rejected login redirects back to the login form, still setting a cookie
+        let back_to_login = response
+            .headers()
+            .get(LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .is_some_and(|location| {
+                location
+                    .split(['?', '#'])
+                    .next()
+                    .is_some_and(|path| path.ends_with(self.login_path.as_str()))
+            });
+        if back_to_login {
+            return Err(ClientError::Unauthorized);
+        }
+        let cookies = Secret::new(
+            response
+                .headers()
+                .get_all(SET_COOKIE)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .filter_map(|value| value.split(';').next())
+                .map(str::trim)
+                .filter(|pair| !pair.is_empty())
+                .collect::<Vec<_>>()
+                .join("; "),
+        );
+        if cookies.is_empty() {
+            return Err(ClientError::Unauthorized);
+        }
+
+        // Only a session the API accepts counts as logged in
+        let check = context
+            .http_client
+            .get(format!("{}/users/me", context.base_url))
+            .header(COOKIE, sensitive_header_value(&cookies)?)
+            .send()
+            .map_err(ClientError::Reqwest)?;
+        if !check.status().is_success() {
+            return Err(ClientError::Unauthorized);
+        }
+        *self.lock_cookie() = Some(cookies.clone());
+        Ok(cookies)
+    }
+
+    fn lock_cookie(&self) -> std::sync::MutexGuard<'_, Option<Secret>> {
+        self.cookie.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Authenticator for SessionAuthenticator {
+    fn authenticate(
+        &self,
+        context: &AuthContext<'_>,
+        request: &mut ApiRequest,
+    ) -> Result<(), ClientError> {
+        let current = self.lock_cookie().clone();
+        let cookie = match current {
+            Some(cookie) => cookie,
+            None => self.login(context)?,
+        };
+        request
+            .headers
+            .insert(COOKIE, sensitive_header_value(&cookie)?);
+        Ok(())
+    }
+
+    fn reauthenticate(&self, context: &AuthContext<'_>) -> Result<bool, ClientError> {
+        self.login(context).map(|_| true)
+    }
+
+    fn logout(&self, context: &AuthContext<'_>) -> Result<(), ClientError> {
+        let Some(cookie) = self.lock_cookie().take() else {
+            return Ok(());
+        };
+        let url = format!("{}{}", site_root(context.base_url), self.logout_path);
+        self.login_client
+            .get(&url)
+            .header(COOKIE, sensitive_header_value(&cookie)?)
+            .send()
+            .map_err(ClientError::Reqwest)?;
+        Ok(())
+    }
+}
+
+/// Site root of an API base URL: `https://host/org/api/v1` becomes `https://host/org`.
+fn site_root(base_url: &str) -> &str {
+    let base_url = base_url.trim_end_matches('/');
+    base_url.strip_suffix("/api/v1").unwrap_or(base_url)
+}
+
+/// Builds the header value of a credential, marked sensitive so it is left out of `Debug`.
+fn sensitive_header_value(value: &Secret) -> Result<HeaderValue, ClientError> {
+    let mut header_value = HeaderValue::from_str(value.expose_secret())
+        .map_err(|e| ClientError::Other(Box::new(e)))?;
+    header_value.set_sensitive(true);
+    Ok(header_value)
+}
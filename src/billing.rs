@@ -0,0 +1,440 @@
+//! Billing report: tracked time priced with type-of-work rates, per customer, project, task
+//! and user over a period.
+//!
+//! Time on a billable type of work is billable and priced at its hourly rate; amounts are
+//! rounded to cents per task and user, and the totals are sums of those lines. Billable time
+//! on a type of work without a rate is reported as unrated.
+//!
+//! ```ignore
+//! let parameters = GetTimeTrackParameters::new().with_dates(month_start..=month_end);
+//! let report = BillingReport::build(&client_context, parameters)?;
+//! println!("{:.2}", report.totals.amount);
+//! report.write_csv(std::io::stdout())?;
+//! ```
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::client_context::ClientContext;
+use crate::errors::ClientError;
+use crate::payload::WorkDuration;
+use crate::routes::GetTimeTrackParameters;
+use crate::time_track_export::{ExportRow, TimeTrackExport};
+
+/// Billable and non-billable time and amount of part of a report
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct BillingTotals {
+    /// Time on billable types of work
+    pub billable_time: WorkDuration,
+    /// Time on non-billable types of work
+    pub non_billable_time: WorkDuration,
+    /// Billable time on types of work without a rate, included in `billable_time`
+    pub unrated_time: WorkDuration,
+    /// Billable time priced at the type-of-work rates
+    pub amount: f64,
+}
+
+impl BillingTotals {
+    /// Returns the billable and non-billable time together.
+    pub fn total_time(&self) -> WorkDuration {
+        self.billable_time + self.non_billable_time
+    }
+
+    fn add(&mut self, other: &BillingTotals) {
+        self.billable_time += other.billable_time;
+        self.non_billable_time += other.non_billable_time;
+        self.unrated_time += other.unrated_time;
+        self.amount = round_cents(self.amount + other.amount);
+    }
+}
+
+/// Billing of one user on one task
+#[derive(Debug, Clone, Serialize)]
+pub struct UserBilling {
+    /// Identifier of the user
+    pub user_id: i32,
+    /// Username of the user
+    pub user: String,
+    /// Totals of the user on the task
+    pub totals: BillingTotals,
+}
+
+/// Billing of one task
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskBilling {
+    /// Identifier of the task
+    pub task_id: i32,
+    /// Name of the task
+    pub task: String,
+    /// Name of the type of work of the task
+    pub type_of_work: Option<String>,
+    /// Hourly rate of the type of work
+    pub rate: Option<f64>,
+    /// Totals of the task
+    pub totals: BillingTotals,
+    /// Totals per user
+    pub users: Vec<UserBilling>,
+}
+
+/// Billing of one project
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectBilling {
+    /// Identifier of the project
+    pub project_id: Option<i32>,
+    /// Name of the project
+    pub project: String,
+    /// Totals of the project
+    pub totals: BillingTotals,
+    /// Totals per task
+    pub tasks: Vec<TaskBilling>,
+}
+
+/// Billing of one customer
+#[derive(Debug, Clone, Serialize)]
+pub struct CustomerBilling {
+    /// Identifier of the customer
+    pub customer_id: Option<i32>,
+    /// Name of the customer
+    pub customer: String,
+    /// Totals of the customer
+    pub totals: BillingTotals,
+    /// Totals per project
+    pub projects: Vec<ProjectBilling>,
+}
+
+/// One task and user of a report, as written to CSV
+#[derive(Debug, Clone, Serialize)]
+pub struct BillingLine {
+    /// Name of the customer
+    pub customer: String,
+    /// Name of the project
+    pub project: String,
+    /// Name of the task
+    pub task: String,
+    /// Username of the user
+    pub user: String,
+    /// Name of the type of work of the task
+    pub type_of_work: Option<String>,
+    /// Hourly rate of the type of work
+    pub rate: Option<f64>,
+    /// Billable time in decimal hours
+    pub billable_hours: f64,
+    /// Non-billable time in decimal hours
+    pub non_billable_hours: f64,
+    /// Billable time on a type of work without a rate, in decimal hours
+    pub unrated_hours: f64,
+    /// Billable time priced at the rate
+    pub amount: f64,
+}
+
+/// Billable hours and amounts per customer, project, task and user
+#[derive(Debug, Clone, Serialize)]
+pub struct BillingReport {
+    /// First day of the period
+    pub date_from: Option<NaiveDate>,
+    /// Last day of the period (inclusive)
+    pub date_to: Option<NaiveDate>,
+    /// Totals of the period
+    pub totals: BillingTotals,
+    /// Totals per customer, by customer name
+    pub customers: Vec<CustomerBilling>,
+}
+
+impl BillingReport {
+    /// Fetches the time-track matching `parameters` and prices it.
+    ///
+    /// # Arguments
+    /// * `client_context` - The client context used to fetch the records and names.
+    /// * `parameters` - The time-track filters; `with_dates` sets the period.
+    ///
+    /// # Returns
+    /// The report, with customers, projects, tasks and users sorted by name.
+    pub fn build(
+        client_context: &ClientContext,
+        parameters: GetTimeTrackParameters,
+    ) -> Result<Self, ClientError> {
+        let (date_from, date_to) = (parameters.date_from, parameters.date_to);
+        let rows = TimeTrackExport::new()
+            .with_sort_by(vec![])
+            .rows(client_context, parameters)?;
+        let mut report = Self::from_rows(&rows);
+        report.date_from = date_from;
+        report.date_to = date_to;
+        Ok(report)
+    }
+
+    /// Prices rows joined by `TimeTrackExport::rows`; the period is left unset.
+    pub fn from_rows(rows: &[ExportRow]) -> Self {
+        // Keyed by names first so that every level comes out sorted by name
+        let mut lines: BTreeMap<LineKey, (Option<String>, Option<f64>, BillingTotals)> =
+            BTreeMap::new();
+        for row in rows {
+            let key = LineKey::from(row);
+            let (_, _, totals) = lines
+                .entry(key)
+                .or_insert_with(|| (row.type_of_work.clone(), row.rate, BillingTotals::default()));
+            if row.billable == Some(true) {
+                totals.billable_time += row.time;
+                if row.rate.is_none() {
+                    totals.unrated_time += row.time;
+                }
+            } else {
+                totals.non_billable_time += row.time;
+            }
+        }
+
+        let mut report = BillingReport {
+            date_from: None,
+            date_to: None,
+            totals: BillingTotals::default(),
+            customers: vec![],
+        };
+        for (key, (type_of_work, rate, mut totals)) in lines {
+            totals.amount = rate
+                .map(|rate| {
+                    round_cents((totals.billable_time - totals.unrated_time).hours() * rate)
+                })
+                .unwrap_or_default();
+            report.totals.add(&totals);
+
+            if report
+                .customers
+                .last()
+                .is_none_or(|c| c.customer_id != key.customer_id)
+            {
+                report.customers.push(CustomerBilling {
+                    customer_id: key.customer_id,
+                    customer: key.customer.clone(),
+                    totals: BillingTotals::default(),
+                    projects: vec![],
+                });
+            }
+            let Some(customer) = report.customers.last_mut() else {
+                continue;
+            };
+            customer.totals.add(&totals);
+
+            if customer
+                .projects
+                .last()
+                .is_none_or(|p| p.project_id != key.project_id)
+            {
+                customer.projects.push(ProjectBilling {
+                    project_id: key.project_id,
+                    project: key.project.clone(),
+                    totals: BillingTotals::default(),
+                    tasks: vec![],
+                });
+            }
+            let Some(project) = customer.projects.last_mut() else {
+                continue;
+            };
+            project.totals.add(&totals);
+
+            if project
+                .tasks
+                .last()
+                .is_none_or(|t| t.task_id != key.task_id)
+            {
+                project.tasks.push(TaskBilling {
+                    task_id: key.task_id,
+                    task: key.task.clone(),
+                    type_of_work,
+                    rate,
+                    totals: BillingTotals::default(),
+                    users: vec![],
+                });
+            }
+            let Some(task) = project.tasks.last_mut() else {
+                continue;
+            };
+            task.totals.add(&totals);
+            task.users.push(UserBilling {
+                user_id: key.user_id,
+                user: key.user,
+                totals,
+            });
+        }
+        report
+    }
+
+    /// Flattens the report into one line per task and user.
+    pub fn lines(&self) -> Vec<BillingLine> {
+        let mut lines = vec![];
+        for customer in &self.customers {
+            for project in &customer.projects {
+                for task in &project.tasks {
+                    for user in &task.users {
+                        lines.push(BillingLine {
+                            customer: customer.customer.clone(),
+                            project: project.project.clone(),
+                            task: task.task.clone(),
+                            user: user.user.clone(),
+                            type_of_work: task.type_of_work.clone(),
+                            rate: task.rate,
+                            billable_hours: user.totals.billable_time.hours(),
+                            non_billable_hours: user.totals.non_billable_time.hours(),
+                            unrated_hours: user.totals.unrated_time.hours(),
+                            amount: user.totals.amount,
+                        });
+                    }
+                }
+            }
+        }
+        lines
+    }
+
+    /// Writes the report as JSON.
+    pub fn write_json(&self, writer: impl std::io::Write) -> Result<(), ClientError> {
+        serde_json::to_writer_pretty(writer, self).map_err(ClientError::SerdeJson)
+    }
+
+    /// Writes `lines` as CSV with a header row.
+    #[cfg(feature = "csv")]
+    pub fn write_csv(&self, writer: impl std::io::Write) -> Result<(), ClientError> {
+        let other = |e: csv::Error| ClientError::Other(Box::new(e));
+        let mut csv_writer = csv::Writer::from_writer(writer);
+        for line in self.lines() {
+            csv_writer.serialize(line).map_err(other)?;
+        }
+        csv_writer
+            .flush()
+            .map_err(|e| ClientError::Other(Box::new(e)))
+    }
+}
+
+/// Position of a line in the report, ordered by name then id at every level
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct LineKey {
+    customer_sort: String,
+    customer_id: Option<i32>,
+    project_sort: String,
+    project_id: Option<i32>,
+    task_sort: String,
+    task_id: i32,
+    user_sort: String,
+    user_id: i32,
+    customer: String,
+    project: String,
+    task: String,
+    user: String,
+}
+
+impl From<&ExportRow> for LineKey {
+    fn from(row: &ExportRow) -> Self {
+        let name = |name: &Option<String>, id: Option<i32>| match (name, id) {
+            (Some(name), _) => name.clone(),
+            (None, Some(id)) => format!("#{}", id),
+            (None, None) => String::new(),
+        };
+        let customer = name(&row.customer, row.customer_id);
+        let project = name(&row.project, row.project_id);
+        let task = name(&row.task, Some(row.task_id));
+        let user = name(&row.user, Some(row.user_id));
+        LineKey {
+            customer_sort: customer.to_lowercase(),
+            customer_id: row.customer_id,
+            project_sort: project.to_lowercase(),
+            project_id: row.project_id,
+            task_sort: task.to_lowercase(),
+            task_id: row.task_id,
+            user_sort: user.to_lowercase(),
+            user_id: row.user_id,
+            customer,
+            project,
+            task,
+            user,
+        }
+    }
+}
+
+fn round_cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACME: (i32, &str) = (1, "Acme");
+    const GLOBEX: (i32, &str) = (2, "Globex");
+    const JDOE: (i32, &str) = (1, "jdoe");
+    const BOB: (i32, &str) = (2, "bob");
+
+    fn row(
+        (customer_id, customer): (i32, &str),
+        task_id: i32,
+        (user_id, user): (i32, &str),
+        rate: Option<f64>,
+        minutes: i64,
+    ) -> ExportRow {
+        ExportRow {
+            date: NaiveDate::default(),
+            user_id,
+            user: Some(user.to_string()),
+            user_name: None,
+            customer_id: Some(customer_id),
+            customer: Some(customer.to_string()),
+            project_id: Some(1),
+            project: Some("Website".to_string()),
+            task_id,
+            task: Some(format!("Task {}", task_id)),
+            type_of_work: Some("Consulting".to_string()),
+            billable: Some(task_id != 3),
+            rate,
+            time: WorkDuration::from_minutes(minutes),
+            comment: None,
+        }
+    }
+
+    #[test]
+    fn prices_billable_time_per_line() {
+        let rows = vec![
+            row(ACME, 1, JDOE, Some(120.5), 45),
+            row(ACME, 1, JDOE, Some(120.5), 90),
+            row(ACME, 1, BOB, Some(120.5), 120),
+            row(ACME, 3, JDOE, Some(80.0), 60),
+        ];
+        let report = BillingReport::from_rows(&rows);
+
+        let lines = report.lines();
+        assert_eq!(lines.len(), 3);
+        assert_eq!((lines[0].user.as_str(), lines[0].amount), ("bob", 241.0));
+        // 2:15 at 120.50 is 271.125, rounded per line
+        assert_eq!((lines[1].user.as_str(), lines[1].amount), ("jdoe", 271.13));
+        assert_eq!((lines[2].non_billable_hours, lines[2].amount), (1.0, 0.0));
+
+        assert_eq!(report.totals.billable_time, WorkDuration::from_minutes(255));
+        assert_eq!(
+            report.totals.non_billable_time,
+            WorkDuration::from_minutes(60)
+        );
+        assert_eq!(report.totals.amount, 512.13);
+        assert_eq!(report.customers[0].projects[0].tasks.len(), 2);
+    }
+
+    #[test]
+    fn reports_unrated_billable_time() {
+        let rows = vec![
+            row(GLOBEX, 1, JDOE, None, 30),
+            row(ACME, 2, JDOE, Some(100.0), 30),
+        ];
+        let report = BillingReport::from_rows(&rows);
+
+        let customers: Vec<&str> = report
+            .customers
+            .iter()
+            .map(|customer| customer.customer.as_str())
+            .collect();
+        assert_eq!(customers, ["Acme", "Globex"]);
+        assert_eq!(
+            report.customers[1].totals.unrated_time,
+            WorkDuration::from_minutes(30)
+        );
+        assert_eq!(report.customers[1].totals.amount, 0.0);
+        assert_eq!(report.totals.amount, 50.0);
+        assert_eq!(report.totals.unrated_time, WorkDuration::from_minutes(30));
+    }
+}
@@ -0,0 +1,1131 @@
+//! `actitime` command-line client built on `ClientContext` and the crate routes.
+
+mod output;
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use chrono::{Datelike, Local, NaiveDate};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+
+use actitime_rest_client::billing::{BillingLine, BillingReport};
+use actitime_rest_client::client_context::ClientContext;
+use actitime_rest_client::config::Config;
+use actitime_rest_client::errors::ClientError;
+use actitime_rest_client::estimates::{
+    EstimateAnalysis, EstimateFlag, EstimateReport, EstimateTotals,
+};
+use actitime_rest_client::payload::{
+    LeaveType, Task, UserWithAllowedActions, WorkDuration,
+    enums::{LeaveTypeBalance, TaskStatus},
+};
+use actitime_rest_client::routes::{
+    GetLeaveTypesParameters, GetLeaveTypesSortOrder, GetTasksIncludeReferenced, GetTasksParameters,
+    GetTasksSortOrder, GetTimeTrackParameters, get_leave_types, get_tasks, get_users_me,
+    get_users_schedule,
+};
+use actitime_rest_client::structure::{
+    AppliedChange, ApplyOptions, ChangeAction, PlannedChange, StructureSpec,
+};
+use actitime_rest_client::task_import::{RowResult, RowStatus, TaskImport};
+use actitime_rest_client::time_track_export::{ExportColumn, ExportFormat, TimeTrackExport};
+use actitime_rest_client::time_track_import::{
+    ImportedDay, RecordStatus, TimeTrackImport, TimeTrackMapping, TimeTrackSource,
+};
+
+use output::{OutputFormat, Tabular, cell};
+
+/// Command-line client for the actiTIME REST API
+#[derive(Parser)]
+#[command(name = "actitime", version, about)]
+struct Cli {
+    /// Configuration file (defaults to $ACTITIME_CONFIG or ~/.config/actitime/config.toml)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Configuration profile
+    #[arg(short, long, global = true)]
+    profile: Option<String>,
+
+    /// actiTIME Online organization, overrides the profile and its base URL
+    #[arg(long, global = true)]
+    organization: Option<String>,
+
+    /// API base URL, overrides the profile
+    #[arg(long, global = true)]
+    base_url: Option<String>,
+
+    /// Username, overrides the profile
+    #[arg(long, global = true)]
+    username: Option<String>,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Table, global = true)]
+    output: OutputFormat,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show the authenticated user
+    Whoami,
+    /// Work with tasks
+    #[command(subcommand)]
+    Tasks(TasksCommand),
+    /// Work with leave types
+    #[command(subcommand)]
+    LeaveTypes(LeaveTypesCommand),
+    /// Work with work schedules
+    #[command(subcommand)]
+    Schedule(ScheduleCommand),
+    /// Manage customers, projects, tasks and types of work from a structure file
+    #[command(subcommand)]
+    Structure(StructureCommand),
+    /// Work with tracked time
+    #[command(subcommand)]
+    TimeTrack(TimeTrackCommand),
+    /// Report billable hours and amounts
+    #[command(subcommand)]
+    Billing(BillingCommand),
+}
+
+#[derive(Subcommand)]
+enum TasksCommand {
+    /// List tasks
+    List(TasksListArgs),
+    /// Create tasks from a CSV file
+    Import(TasksImportArgs),
+    /// Compare task estimates with tracked time and show tasks over estimate or past deadline
+    Overrun(TasksOverrunArgs),
+}
+
+#[derive(Subcommand)]
+enum LeaveTypesCommand {
+    /// List leave types
+    List(LeaveTypesListArgs),
+}
+
+#[derive(Subcommand)]
+enum ScheduleCommand {
+    /// Show the work schedule of a user
+    Show(ScheduleShowArgs),
+}
+
+#[derive(Subcommand)]
+enum StructureCommand {
+    /// Show the changes needed to match a structure file
+    Plan(StructurePlanArgs),
+    /// Carry out the changes needed to match a structure file
+    Apply(StructureApplyArgs),
+}
+
+#[derive(Subcommand)]
+enum TimeTrackCommand {
+    /// Import time tracked in another tool
+    Import(TimeTrackImportArgs),
+    /// Export tracked time with user, customer, project, task and type-of-work names
+    Export(TimeTrackExportArgs),
+}
+
+#[derive(Subcommand)]
+enum BillingCommand {
+    /// Show billable hours and amounts per customer, project, task and user
+    Report(BillingReportArgs),
+}
+
+#[derive(Args)]
+struct TasksImportArgs {
+    /// CSV file with customer, project, task, description, deadline, estimate,
+    /// type of work and workflow status columns
+    file: PathBuf,
+    /// Create customers and projects that do not exist
+    #[arg(long)]
+    create_missing: bool,
+    /// Validate the file without creating anything
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Args)]
+struct TasksOverrunArgs {
+    /// Day of the analysis, YYYY-MM-DD (defaults to today)
+    #[arg(long)]
+    as_of: Option<NaiveDate>,
+    /// First day of tracked time to count, YYYY-MM-DD (defaults to the earliest task creation)
+    #[arg(long)]
+    since: Option<NaiveDate>,
+    /// Customer IDs to filter by
+    #[arg(long, value_delimiter = ',')]
+    customer_ids: Option<Vec<u32>>,
+    /// Project IDs to filter by
+    #[arg(long, value_delimiter = ',')]
+    project_ids: Option<Vec<u32>>,
+    /// Status filter
+    #[arg(long, value_enum)]
+    status: Option<StatusArg>,
+    /// Level to show: tasks, or totals per project or customer
+    #[arg(long, value_enum, default_value_t = OverrunLevel::Task)]
+    by: OverrunLevel,
+    /// Show every task, project or customer, not only flagged ones
+    #[arg(long)]
+    all: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum OverrunLevel {
+    Task,
+    Project,
+    Customer,
+}
+
+#[derive(Args)]
+struct TimeTrackImportArgs {
+    /// CSV file to import
+    file: PathBuf,
+    /// Layout of the CSV file
+    #[arg(long, value_enum, default_value_t = SourceArg::Generic)]
+    source: SourceArg,
+    /// TOML or YAML file mapping source user, project and task names to actiTIME ids
+    #[arg(long)]
+    mapping: Option<PathBuf>,
+    /// Show the records per user and day without submitting them
+    #[arg(long)]
+    dry_run: bool,
+    /// Refuse days that are not working days, hold leave or exceed the schedule
+    #[arg(long)]
+    strict: bool,
+    /// Replace time already tracked on the same task and day
+    #[arg(long)]
+    overwrite: bool,
+}
+
+#[derive(Args)]
+struct TimeTrackExportArgs {
+    /// First day, YYYY-MM-DD
+    #[arg(long)]
+    from: NaiveDate,
+    /// Last day, YYYY-MM-DD
+    #[arg(long)]
+    to: NaiveDate,
+    /// User IDs to filter by
+    #[arg(long, value_delimiter = ',')]
+    user_ids: Option<Vec<i32>>,
+    /// Customer IDs to filter by
+    #[arg(long, value_delimiter = ',')]
+    customer_ids: Option<Vec<i32>>,
+    /// Project IDs to filter by
+    #[arg(long, value_delimiter = ',')]
+    project_ids: Option<Vec<i32>>,
+    /// Columns to write, in order
+    #[arg(long, value_enum, value_delimiter = ',')]
+    columns: Option<Vec<ColumnArg>>,
+    /// Columns to sort by, most significant first; grouped rows sort by grouped columns only
+    #[arg(long, value_enum, value_delimiter = ',')]
+    sort: Option<Vec<ColumnArg>>,
+    /// Columns to group by, summing the time of each group
+    #[arg(long, value_enum, value_delimiter = ',')]
+    group_by: Option<Vec<ColumnArg>>,
+    /// File format
+    #[arg(long, value_enum, default_value_t = ExportFormatArg::Csv)]
+    format: ExportFormatArg,
+    /// File to write (defaults to standard output, except for XLSX)
+    #[arg(long)]
+    file: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct BillingReportArgs {
+    /// First day, YYYY-MM-DD
+    #[arg(long)]
+    from: NaiveDate,
+    /// Last day, YYYY-MM-DD
+    #[arg(long)]
+    to: NaiveDate,
+    /// User IDs to filter by
+    #[arg(long, value_delimiter = ',')]
+    user_ids: Option<Vec<i32>>,
+    /// Customer IDs to filter by
+    #[arg(long, value_delimiter = ',')]
+    customer_ids: Option<Vec<i32>>,
+    /// Project IDs to filter by
+    #[arg(long, value_delimiter = ',')]
+    project_ids: Option<Vec<i32>>,
+}
+
+#[derive(Args)]
+struct TasksListArgs {
+    /// Index offset of the first task to return
+    #[arg(long)]
+    offset: Option<u32>,
+    /// Maximum number of tasks to return
+    #[arg(long)]
+    limit: Option<u32>,
+    /// Task IDs to filter by
+    #[arg(long, value_delimiter = ',')]
+    task_ids: Option<Vec<u32>>,
+    /// Customer IDs to filter by
+    #[arg(long, value_delimiter = ',')]
+    customer_ids: Option<Vec<u32>>,
+    /// Project IDs to filter by
+    #[arg(long, value_delimiter = ',')]
+    project_ids: Option<Vec<u32>>,
+    /// Type of work IDs to filter by
+    #[arg(long, value_delimiter = ',')]
+    type_of_work_ids: Option<Vec<u32>>,
+    /// Workflow status IDs to filter by
+    #[arg(long, value_delimiter = ',')]
+    workflow_status_ids: Option<Vec<u32>>,
+    /// Sort order
+    #[arg(long, value_enum)]
+    sort: Option<TasksSort>,
+    /// Task name filter (partial match)
+    #[arg(long)]
+    name: Option<String>,
+    /// Contains words filter (partial match)
+    #[arg(long)]
+    words: Option<String>,
+    /// Status filter
+    #[arg(long, value_enum)]
+    status: Option<StatusArg>,
+    /// Referenced entities to include in the response
+    #[arg(long, value_enum, value_delimiter = ',')]
+    include_referenced: Option<Vec<IncludeReferencedArg>>,
+    /// Skip tasks that fail to decode and report them on stderr instead of failing
+    #[arg(long)]
+    lenient: bool,
+}
+
+#[derive(Args)]
+struct LeaveTypesListArgs {
+    /// Index offset of the first leave type to return
+    #[arg(long)]
+    offset: Option<u32>,
+    /// Maximum number of leave types to return
+    #[arg(long)]
+    limit: Option<u32>,
+    /// Leave type IDs to filter by
+    #[arg(long, value_delimiter = ',')]
+    type_ids: Option<Vec<u32>>,
+    /// Leave type name filter (partial match)
+    #[arg(long)]
+    name: Option<String>,
+    /// Contains words filter (partial match)
+    #[arg(long)]
+    words: Option<String>,
+    /// Balance type filter
+    #[arg(long, value_enum)]
+    balance: Option<BalanceArg>,
+    /// Archived status filter
+    #[arg(long)]
+    archived: Option<bool>,
+    /// Sort order
+    #[arg(long, value_enum)]
+    sort: Option<NameSort>,
+}
+
+#[derive(Args)]
+struct ScheduleShowArgs {
+    /// User ID (defaults to the authenticated user)
+    #[arg(long)]
+    user: Option<i32>,
+    /// First day, YYYY-MM-DD (defaults to the start of the current week)
+    #[arg(long)]
+    from: Option<NaiveDate>,
+    /// Last day, YYYY-MM-DD (defaults to six days after `--from`)
+    #[arg(long)]
+    to: Option<NaiveDate>,
+}
+
+#[derive(Args)]
+struct StructurePlanArgs {
+    /// Structure file (.toml, .yaml or .yml)
+    file: PathBuf,
+}
+
+#[derive(Args)]
+struct StructureApplyArgs {
+    /// Structure file (.toml, .yaml or .yml)
+    file: PathBuf,
+    /// Only list the changes that would be carried out
+    #[arg(long)]
+    dry_run: bool,
+    /// Allow archiving customers, projects and types of work, and completing tasks
+    #[arg(long)]
+    allow_archive: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SourceArg {
+    Generic,
+    Toggl,
+    Harvest,
+}
+
+impl From<SourceArg> for TimeTrackSource {
+    fn from(source: SourceArg) -> Self {
+        match source {
+            SourceArg::Generic => TimeTrackSource::Generic,
+            SourceArg::Toggl => TimeTrackSource::Toggl,
+            SourceArg::Harvest => TimeTrackSource::Harvest,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ColumnArg {
+    Date,
+    UserId,
+    User,
+    UserName,
+    CustomerId,
+    Customer,
+    ProjectId,
+    Project,
+    TaskId,
+    Task,
+    TypeOfWork,
+    Billable,
+    Minutes,
+    Hours,
+    Comment,
+}
+
+impl From<ColumnArg> for ExportColumn {
+    fn from(column: ColumnArg) -> Self {
+        match column {
+            ColumnArg::Date => ExportColumn::Date,
+            ColumnArg::UserId => ExportColumn::UserId,
+            ColumnArg::User => ExportColumn::User,
+            ColumnArg::UserName => ExportColumn::UserName,
+            ColumnArg::CustomerId => ExportColumn::CustomerId,
+            ColumnArg::Customer => ExportColumn::Customer,
+            ColumnArg::ProjectId => ExportColumn::ProjectId,
+            ColumnArg::Project => ExportColumn::Project,
+            ColumnArg::TaskId => ExportColumn::TaskId,
+            ColumnArg::Task => ExportColumn::Task,
+            ColumnArg::TypeOfWork => ExportColumn::TypeOfWork,
+            ColumnArg::Billable => ExportColumn::Billable,
+            ColumnArg::Minutes => ExportColumn::Minutes,
+            ColumnArg::Hours => ExportColumn::Hours,
+            ColumnArg::Comment => ExportColumn::Comment,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormatArg {
+    Csv,
+    Ndjson,
+    Xlsx,
+}
+
+impl From<ExportFormatArg> for ExportFormat {
+    fn from(format: ExportFormatArg) -> Self {
+        match format {
+            ExportFormatArg::Csv => ExportFormat::Csv,
+            ExportFormatArg::Ndjson => ExportFormat::Ndjson,
+            ExportFormatArg::Xlsx => ExportFormat::Xlsx,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum TasksSort {
+    CreatedAsc,
+    CreatedDesc,
+    NameAsc,
+    NameDesc,
+    StatusAsc,
+    StatusDesc,
+}
+
+impl From<TasksSort> for GetTasksSortOrder {
+    fn from(sort: TasksSort) -> Self {
+        match sort {
+            TasksSort::CreatedAsc => GetTasksSortOrder::CreatedAsc,
+            TasksSort::CreatedDesc => GetTasksSortOrder::CreatedDesc,
+            TasksSort::NameAsc => GetTasksSortOrder::NameAsc,
+            TasksSort::NameDesc => GetTasksSortOrder::NameDesc,
+            TasksSort::StatusAsc => GetTasksSortOrder::StatusAsc,
+            TasksSort::StatusDesc => GetTasksSortOrder::StatusDesc,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum NameSort {
+    NameAsc,
+    NameDesc,
+}
+
+impl From<NameSort> for GetLeaveTypesSortOrder {
+    fn from(sort: NameSort) -> Self {
+        match sort {
+            NameSort::NameAsc => GetLeaveTypesSortOrder::NameAsc,
+            NameSort::NameDesc => GetLeaveTypesSortOrder::NameDesc,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum StatusArg {
+    Open,
+    Completed,
+}
+
+impl From<StatusArg> for TaskStatus {
+    fn from(status: StatusArg) -> Self {
+        match status {
+            StatusArg::Open => TaskStatus::Open,
+            StatusArg::Completed => TaskStatus::Completed,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum BalanceArg {
+    None,
+    Sick,
+    Pto,
+}
+
+impl From<BalanceArg> for LeaveTypeBalance {
+    fn from(balance: BalanceArg) -> Self {
+        match balance {
+            BalanceArg::None => LeaveTypeBalance::None,
+            BalanceArg::Sick => LeaveTypeBalance::Sick,
+            BalanceArg::Pto => LeaveTypeBalance::PTO,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum IncludeReferencedArg {
+    Customers,
+    Projects,
+    TypeOfWork,
+    WorkflowStatuses,
+}
+
+impl From<IncludeReferencedArg> for GetTasksIncludeReferenced {
+    fn from(include: IncludeReferencedArg) -> Self {
+        match include {
+            IncludeReferencedArg::Customers => GetTasksIncludeReferenced::Customers,
+            IncludeReferencedArg::Projects => GetTasksIncludeReferenced::Projects,
+            IncludeReferencedArg::TypeOfWork => GetTasksIncludeReferenced::TypeOfWork,
+            IncludeReferencedArg::WorkflowStatuses => GetTasksIncludeReferenced::WorkflowStatuses,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("actitime: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<(), ClientError> {
+    let client_context = connect(&cli)?;
+    let format = cli.output;
+
+    match cli.command {
+        Command::Whoami => {
+            let me = client_context.call_route(&get_users_me(), None)?;
+            output::print(format, std::slice::from_ref(&me), &me)
+        }
+        Command::Tasks(TasksCommand::List(args)) => {
+            let lenient = args.lenient;
+            let route = get_tasks(Some(args.into_parameters()));
+            let task_list = if lenient {
+                let page = client_context.call_route_lenient(&route, None)?;
+                for error in &page.errors {
+                    eprintln!("actitime: skipped {}", error);
+                }
+                page.value
+            } else {
+                client_context.call_route(&route, None)?
+            };
+            output::print(format, &task_list.items, &task_list.items)
+        }
+        Command::Tasks(TasksCommand::Import(args)) => {
+            let file = File::open(&args.file).map_err(|e| {
+                ClientError::Message(format!("Cannot read {}: {}", args.file.display(), e))
+            })?;
+            let report = TaskImport::new()
+                .with_create_missing(args.create_missing)
+                .with_dry_run(args.dry_run)
+                .run(&client_context, file)?;
+            output::print(format, &report.rows, &report)?;
+            eprintln!("Import: {}.", report);
+            if report.count(RowStatus::Invalid) > 0 {
+                return Err(ClientError::Message(
+                    "No task was created, fix the invalid rows first".to_string(),
+                ));
+            }
+            if report.count(RowStatus::Failed) > 0 {
+                return Err(ClientError::Message(
+                    "Some tasks were not created".to_string(),
+                ));
+            }
+            Ok(())
+        }
+        Command::Tasks(TasksCommand::Overrun(args)) => {
+            let mut analysis =
+                EstimateAnalysis::new(args.as_of.unwrap_or_else(|| Local::now().date_naive()));
+            if let Some(since) = args.since {
+                analysis = analysis.with_since(since);
+            }
+            let report = analysis.run(&client_context, args.task_parameters())?;
+            let rows: Vec<OverrunRow> = OverrunRow::from_report(&report, args.by)
+                .into_iter()
+                .filter(|row| args.all || row.over_estimate + row.past_deadline > 0)
+                .collect();
+            output::print(format, &rows, &report)?;
+            let totals = &report.totals;
+            eprintln!(
+                "{} tasks: {} over estimate, {} past deadline; tracked {} against {} estimated.",
+                totals.tasks,
+                totals.over_estimate,
+                totals.past_deadline,
+                totals.tracked_estimated_time,
+                totals.estimated_time
+            );
+            Ok(())
+        }
+        Command::TimeTrack(TimeTrackCommand::Import(args)) => {
+            let file = File::open(&args.file).map_err(|e| {
+                ClientError::Message(format!("Cannot read {}: {}", args.file.display(), e))
+            })?;
+            let mapping = match &args.mapping {
+                Some(path) => TimeTrackMapping::from_path(path)?,
+                None => TimeTrackMapping::default(),
+            };
+            let report = TimeTrackImport::new()
+                .with_source(args.source.into())
+                .with_mapping(mapping)
+                .with_dry_run(args.dry_run)
+                .with_strict(args.strict)
+                .with_overwrite(args.overwrite)
+                .run(&client_context, file)?;
+            for error in &report.errors {
+                eprintln!("actitime: line {}: {}", error.line, error.message);
+            }
+            output::print(format, &report.days, &report)?;
+            eprintln!("Import: {}.", report);
+            if report.has_errors() {
+                return Err(ClientError::Message(
+                    "No time was submitted, fix the errors first".to_string(),
+                ));
+            }
+            if report.count(RecordStatus::Failed) > 0 {
+                return Err(ClientError::Message(
+                    "Some records were not submitted".to_string(),
+                ));
+            }
+            Ok(())
+        }
+        Command::TimeTrack(TimeTrackCommand::Export(args)) => {
+            let format = ExportFormat::from(args.format);
+            if format == ExportFormat::Xlsx && args.file.is_none() {
+                return Err(ClientError::Message("XLSX export needs --file".to_string()));
+            }
+            let (export, parameters) = args.export_and_parameters();
+            let rows = export.rows(&client_context, parameters)?;
+            match &args.file {
+                Some(path) => {
+                    let file = File::create(path).map_err(|e| {
+                        ClientError::Message(format!("Cannot write {}: {}", path.display(), e))
+                    })?;
+                    export.write(&rows, format, BufWriter::new(file))?;
+                }
+                None => export.write(&rows, format, std::io::stdout().lock())?,
+            }
+            eprintln!("Exported {} rows.", rows.len());
+            Ok(())
+        }
+        Command::Billing(BillingCommand::Report(args)) => {
+            let report = BillingReport::build(&client_context, args.into_parameters())?;
+            output::print(format, &report.lines(), &report)?;
+            let totals = &report.totals;
+            eprintln!(
+                "Billable {}, non-billable {}, amount {:.2}.",
+                totals.billable_time, totals.non_billable_time, totals.amount
+            );
+            if totals.unrated_time > WorkDuration::default() {
+                eprintln!(
+                    "Warning: {} of billable time has no rate.",
+                    totals.unrated_time
+                );
+            }
+            Ok(())
+        }
+        Command::LeaveTypes(LeaveTypesCommand::List(args)) => {
+            let route = get_leave_types(Some(args.into_parameters()));
+            let leave_types = client_context.call_route(&route, None)?;
+            output::print(format, &leave_types.items, &leave_types.items)
+        }
+        Command::Schedule(ScheduleCommand::Show(args)) => {
+            let from = args.from.unwrap_or_else(start_of_current_week);
+            let to = args.to.unwrap_or(from + chrono::Days::new(6));
+            let schedule = match args.user {
+                Some(user_id) => {
+                    client_context.call_route(&get_users_schedule(user_id, from..=to)?, None)?
+                }
+                None => client_context.get_my_schedule(from..=to)?,
+            };
+            let days: Vec<ScheduleDay> = schedule
+                .days()
+                .map(|(date, scheduled)| ScheduleDay { date, scheduled })
+                .collect();
+            output::print(format, &days, &schedule)
+        }
+        Command::Structure(StructureCommand::Plan(args)) => {
+            let plan = StructureSpec::from_path(&args.file)?.plan(&client_context)?;
+            output::print(format, &plan.changes, &plan)?;
+            eprintln!("Plan: {}.", plan);
+            Ok(())
+        }
+        Command::Structure(StructureCommand::Apply(args)) => {
+            let plan = StructureSpec::from_path(&args.file)?.plan(&client_context)?;
+            let options = ApplyOptions::new()
+                .with_dry_run(args.dry_run)
+                .with_destroy_protection(!args.allow_archive);
+            let applied = plan.apply(&client_context, &options)?;
+            output::print(format, &applied, &applied)?;
+            let prefix = if args.dry_run { "Dry run" } else { "Applied" };
+            eprintln!("{}: {}.", prefix, plan);
+            Ok(())
+        }
+    }
+}
+
+fn connect(cli: &Cli) -> Result<ClientContext, ClientError> {
+    let config = match &cli.config {
+        Some(path) => Config::from_path(path)?,
+        None => Config::load()?,
+    };
+    let mut profile = config.profile(cli.profile.as_deref())?;
+    if let Some(organization) = &cli.organization {
+        profile = profile.with_organization(organization.clone());
+    }
+    if let Some(base_url) = &cli.base_url {
+        profile = profile.with_base_url(base_url.clone());
+    }
+    if let Some(username) = &cli.username {
+        profile = profile.with_username(username.clone());
+    }
+    profile.client_context()
+}
+
+fn start_of_current_week() -> NaiveDate {
+    let today = Local::now().date_naive();
+    today - chrono::Days::new(u64::from(today.weekday().num_days_from_monday()))
+}
+
+impl TasksOverrunArgs {
+    fn task_parameters(&self) -> GetTasksParameters {
+        let mut parameters = GetTasksParameters::new();
+        parameters.customer_ids = self.customer_ids.clone();
+        parameters.project_ids = self.project_ids.clone();
+        parameters.status = self.status.map(Into::into);
+        parameters
+    }
+}
+
+impl TasksListArgs {
+    fn into_parameters(self) -> GetTasksParameters {
+        let mut parameters = GetTasksParameters::new();
+        parameters.offset = self.offset;
+        parameters.limit = self.limit;
+        parameters.task_ids = self.task_ids;
+        parameters.customer_ids = self.customer_ids;
+        parameters.project_ids = self.project_ids;
+        parameters.type_of_work_ids = self.type_of_work_ids;
+        parameters.workflow_status_ids = self.workflow_status_ids;
+        parameters.sort_order = self.sort.map(Into::into);
+        parameters.name_filter = self.name;
+        parameters.contains_words = self.words;
+        parameters.status = self.status.map(Into::into);
+        parameters.include_referenced = self
+            .include_referenced
+            .map(|includes| includes.into_iter().map(Into::into).collect());
+        parameters
+    }
+}
+
+impl TimeTrackExportArgs {
+    fn export_and_parameters(&self) -> (TimeTrackExport, GetTimeTrackParameters) {
+        let columns = |columns: &Option<Vec<ColumnArg>>| {
+            columns
+                .as_ref()
+                .map(|columns| columns.iter().map(|column| (*column).into()).collect())
+        };
+        let mut export = TimeTrackExport::new();
+        if let Some(columns) = columns(&self.columns) {
+            export = export.with_columns(columns);
+        }
+        if let Some(sort_by) = columns(&self.sort) {
+            export = export.with_sort_by(sort_by);
+        }
+        if let Some(group_by) = columns(&self.group_by) {
+            export = export.with_group_by(group_by);
+        }
+
+        let mut parameters = GetTimeTrackParameters::new().with_dates(self.from..=self.to);
+        parameters.user_ids = self.user_ids.clone();
+        parameters.customer_ids = self.customer_ids.clone();
+        parameters.project_ids = self.project_ids.clone();
+        (export, parameters)
+    }
+}
+
+impl BillingReportArgs {
+    fn into_parameters(self) -> GetTimeTrackParameters {
+        let mut parameters = GetTimeTrackParameters::new().with_dates(self.from..=self.to);
+        parameters.user_ids = self.user_ids;
+        parameters.customer_ids = self.customer_ids;
+        parameters.project_ids = self.project_ids;
+        parameters
+    }
+}
+
+impl LeaveTypesListArgs {
+    fn into_parameters(self) -> GetLeaveTypesParameters {
+        GetLeaveTypesParameters {
+            offset: self.offset,
+            limit: self.limit,
+            type_ids: self.type_ids,
+            name_filter: self.name,
+            contains_words: self.words,
+            balance: self.balance.map(Into::into),
+            archived: self.archived,
+            sort_order: self.sort.map(Into::into),
+        }
+    }
+}
+
+/// One row of `schedule show`
+#[derive(Serialize)]
+struct ScheduleDay {
+    date: NaiveDate,
+    scheduled: WorkDuration,
+}
+
+/// One row of `tasks overrun`, for a task or the totals of a project or customer
+#[derive(Serialize)]
+struct OverrunRow {
+    customer: String,
+    project: String,
+    task: String,
+    tasks: usize,
+    estimated: Option<WorkDuration>,
+    tracked: WorkDuration,
+    overrun: WorkDuration,
+    deadline: Option<NaiveDate>,
+    over_estimate: usize,
+    past_deadline: usize,
+}
+
+impl OverrunRow {
+    fn from_report(report: &EstimateReport, level: OverrunLevel) -> Vec<Self> {
+        let from_totals = |customer: &str, project: &str, totals: &EstimateTotals| OverrunRow {
+            customer: customer.to_string(),
+            project: project.to_string(),
+            task: String::new(),
+            tasks: totals.tasks,
+            estimated: Some(totals.estimated_time),
+            tracked: totals.tracked_time,
+            overrun: totals.overrun_time,
+            deadline: None,
+            over_estimate: totals.over_estimate,
+            past_deadline: totals.past_deadline,
+        };
+        match level {
+            OverrunLevel::Customer => report
+                .customers
+                .iter()
+                .map(|customer| from_totals(&customer.customer, "", &customer.totals))
+                .collect(),
+            OverrunLevel::Project => report
+                .customers
+                .iter()
+                .flat_map(|customer| {
+                    customer.projects.iter().map(|project| {
+                        from_totals(&customer.customer, &project.project, &project.totals)
+                    })
+                })
+                .collect(),
+            OverrunLevel::Task => report
+                .tasks()
+                .map(|(customer, project, task)| {
+                    let has = |flag| usize::from(task.flags.contains(&flag));
+                    OverrunRow {
+                        customer: customer.customer.clone(),
+                        project: project.project.clone(),
+                        task: task.task.clone(),
+                        tasks: 1,
+                        estimated: task.estimated_time,
+                        tracked: task.tracked_time,
+                        overrun: task.overrun().unwrap_or_default(),
+                        deadline: task.deadline,
+                        over_estimate: has(EstimateFlag::OverEstimate),
+                        past_deadline: has(EstimateFlag::PastDeadline),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Tabular for OverrunRow {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "customer",
+            "project",
+            "task",
+            "tasks",
+            "estimated",
+            "tracked",
+            "overrun",
+            "deadline",
+            "over estimate",
+            "past deadline",
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.customer.clone(),
+            self.project.clone(),
+            self.task.clone(),
+            self.tasks.to_string(),
+            cell(&self.estimated),
+            self.tracked.to_string(),
+            self.overrun.to_string(),
+            cell(&self.deadline),
+            self.over_estimate.to_string(),
+            self.past_deadline.to_string(),
+        ]
+    }
+}
+
+impl Tabular for ScheduleDay {
+    fn headers() -> Vec<&'static str> {
+        vec!["date", "weekday", "scheduled"]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.date.to_string(),
+            self.date.weekday().to_string(),
+            self.scheduled.to_string(),
+        ]
+    }
+}
+
+impl Tabular for UserWithAllowedActions {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "id",
+            "username",
+            "full name",
+            "email",
+            "department",
+            "active",
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.username.clone(),
+            cell(&self.full_name),
+            self.email.clone(),
+            cell(&self.department_id),
+            cell(&self.active),
+        ]
+    }
+}
+
+impl Tabular for Task {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "id",
+            "name",
+            "status",
+            "customer",
+            "project",
+            "type of work",
+            "workflow status",
+            "deadline",
+            "estimate",
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            cell(&self.id),
+            self.name.clone(),
+            cell(&self.status),
+            cell(&self.customer_name),
+            cell(&self.project_name),
+            cell(&self.type_of_work_name),
+            cell(&self.workflow_status_name),
+            cell(&self.deadline),
+            cell(&self.estimated_time),
+        ]
+    }
+}
+
+impl Tabular for PlannedChange {
+    fn headers() -> Vec<&'static str> {
+        vec!["action", "entity", "fields"]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            action_cell(self.action),
+            self.address.to_string(),
+            self.field_names().join(", "),
+        ]
+    }
+}
+
+impl Tabular for AppliedChange {
+    fn headers() -> Vec<&'static str> {
+        vec!["action", "entity", "id"]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            action_cell(self.action),
+            self.address.to_string(),
+            cell(&self.id),
+        ]
+    }
+}
+
+impl Tabular for RowResult {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "line", "status", "customer", "project", "task", "id", "messages",
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.line.to_string(),
+            self.status.to_string(),
+            self.customer.clone(),
+            self.project.clone(),
+            self.task.clone(),
+            cell(&self.task_id),
+            self.messages.join("; "),
+        ]
+    }
+}
+
+impl Tabular for BillingLine {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "customer",
+            "project",
+            "task",
+            "user",
+            "type of work",
+            "rate",
+            "billable hours",
+            "non-billable hours",
+            "unrated hours",
+            "amount",
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.customer.clone(),
+            self.project.clone(),
+            self.task.clone(),
+            self.user.clone(),
+            cell(&self.type_of_work),
+            cell(&self.rate),
+            format!("{:.2}", self.billable_hours),
+            format!("{:.2}", self.non_billable_hours),
+            format!("{:.2}", self.unrated_hours),
+            format!("{:.2}", self.amount),
+        ]
+    }
+}
+
+impl Tabular for ImportedDay {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "user",
+            "date",
+            "scheduled",
+            "leave",
+            "tracked",
+            "imported",
+            "records",
+            "notes",
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        let records: Vec<String> = self
+            .records
+            .iter()
+            .map(|record| format!("{} {} ({})", record.task, record.time, record.status))
+            .collect();
+        let notes: Vec<&str> = self
+            .errors
+            .iter()
+            .chain(&self.warnings)
+            .map(String::as_str)
+            .chain(
+                self.records
+                    .iter()
+                    .filter_map(|record| record.message.as_deref()),
+            )
+            .collect();
+        vec![
+            self.user.clone(),
+            self.date.to_string(),
+            self.scheduled.to_string(),
+            self.leave.to_string(),
+            self.tracked.to_string(),
+            self.imported().to_string(),
+            records.join(", "),
+            notes.join("; "),
+        ]
+    }
+}
+
+fn action_cell(action: ChangeAction) -> String {
+    format!("{} {}", action.symbol(), action)
+}
+
+impl Tabular for LeaveType {
+    fn headers() -> Vec<&'static str> {
+        vec!["id", "name", "balance", "archived"]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.name.clone(),
+            self.balance.to_string(),
+            self.archived.to_string(),
+        ]
+    }
+}
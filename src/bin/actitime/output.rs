@@ -0,0 +1,91 @@
+use std::io::{self, Write};
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use actitime_rest_client::errors::ClientError;
+
+/// Output format selected with `--output`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Column-aligned table for terminals
+    Table,
+    /// Pretty-printed JSON of the API payload
+    Json,
+    /// Comma-separated values with a header row
+    Csv,
+}
+
+/// A value that can be rendered as rows of a table or CSV file
+pub trait Tabular {
+    /// Column headers, in the order of `row`.
+    fn headers() -> Vec<&'static str>;
+
+    /// Cells of the row representing `self`.
+    fn row(&self) -> Vec<String>;
+}
+
+/// Writes `rows` to stdout in the requested format.
+///
+/// `json` is serialized as-is for `OutputFormat::Json`, so list commands can output the
+/// payload items while the table and CSV formats use the flattened rows.
+pub fn print<T, J>(format: OutputFormat, rows: &[T], json: &J) -> Result<(), ClientError>
+where
+    T: Tabular,
+    J: Serialize + ?Sized,
+{
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, json).map_err(ClientError::SerdeJson)?;
+            writeln!(out).map_err(other)?;
+        }
+        OutputFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(out);
+            writer.write_record(T::headers()).map_err(other)?;
+            for row in rows {
+                writer.write_record(row.row()).map_err(other)?;
+            }
+            writer.flush().map_err(other)?;
+        }
+        OutputFormat::Table => write_table(&mut out, &T::headers(), rows).map_err(other)?,
+    }
+    Ok(())
+}
+
+fn write_table<T: Tabular>(out: &mut impl Write, headers: &[&str], rows: &[T]) -> io::Result<()> {
+    let cells: Vec<Vec<String>> = rows.iter().map(Tabular::row).collect();
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in &cells {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let header_cells: Vec<String> = headers.iter().map(|h| h.to_uppercase()).collect();
+    write_table_row(out, &header_cells, &widths)?;
+    for row in &cells {
+        write_table_row(out, row, &widths)?;
+    }
+    Ok(())
+}
+
+fn write_table_row(out: &mut impl Write, cells: &[String], widths: &[usize]) -> io::Result<()> {
+    let line = cells
+        .iter()
+        .zip(widths)
+        .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+        .collect::<Vec<_>>()
+        .join("  ");
+    writeln!(out, "{}", line.trim_end())
+}
+
+/// Formats an optional value as a table cell, leaving it empty when absent.
+pub fn cell<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(ToString::to_string).unwrap_or_default()
+}
+
+fn other(error: impl std::error::Error + Send + Sync + 'static) -> ClientError {
+    ClientError::Other(Box::new(error))
+}
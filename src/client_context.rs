@@ -1,68 +1,249 @@
-use base64::engine::{Engine, general_purpose::STANDARD as BASE64_STANDARD};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use reqwest::StatusCode;
 use reqwest::blocking::Client as HttpClient;
+use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderValue};
 
+use crate::auth::{AuthContext, Authenticator, BasicAuthenticator};
+use crate::conditional_cache::{ConditionalCache, Validators};
 use crate::errors::ClientError;
-use crate::payload::{ApiError, User, enums::Method};
-use crate::routes::Route;
+use crate::instrumentation::{CallSpan, decode_lenient_response, decode_response};
+use crate::middleware::{ApiRequest, ApiResponse, CallContext, Flow, Middleware, default_chain};
+use crate::payload::{
+    ApiError, LeaveTime, Lenient, ListPayload, Schedule, Task, TaskList, TimeTrack, User,
+    enums::Method,
+};
+use crate::reference_cache::{CacheStats, ReferenceCache, ReferenceData, ReferenceDataKind};
+use crate::routes::{
+    GetLeaveTimeParameters, GetTasksParameters, GetTimeTrackParameters, GetUsersParameters,
+    IntoDateRange, Route, checked_date_range, get_leave_time, get_tasks, get_time_track, get_users,
+    get_users_me, get_users_schedule,
+};
+
+/// Page size used when walking through every page of a listing
+pub(crate) const PAGE_SIZE: u32 = 100;
 
+/// Maximum number of times one route call is sent when middlewares ask for retries
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Handle to an actiTIME organization, shared by every route call
+///
+/// Cloning is cheap: clones share the same configuration and HTTP connection pool, and
+/// changes made through one clone (e.g. `set_basic_auth_token`) are visible to all of them.
+/// The handle is `Send + Sync`, so one context can serve many threads.
+#[derive(Clone)]
 pub struct ClientContext {
-    pub organization: String,
-    pub base_url: String,
-    pub basic_auth_token: Option<String>,
-    pub user_information: Option<User>,
-    pub http_client: HttpClient,
+    organization: Arc<str>,
+    settings: Arc<RwLock<Settings>>,
+    reference_cache: Arc<ReferenceCache>,
+    conditional_cache: Arc<ConditionalCache>,
 }
 
+/// Configuration that can change over the lifetime of a `ClientContext`
+struct Settings {
+    base_url: String,
+    authenticator: Option<Arc<dyn Authenticator>>,
+    user_information: Option<User>,
+    /// Changed with every authenticator, so cached responses are kept per identity
+    identity: u64,
+    http_client: HttpClient,
+    middlewares: Vec<Arc<dyn Middleware>>,
+    attach_body_to_decode_errors: bool,
+    conditional_requests: bool,
+}
+
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<ClientContext>();
+};
+
 impl ClientContext {
     pub fn new(organization: String) -> Self {
-        ClientContext {
-            organization: organization.clone(),
-            base_url: format!("https://online.actitime.com/{}/api/v1", organization).into(),
-            basic_auth_token: None,
+        let settings = Settings {
+            base_url: format!("https://online.actitime.com/{}/api/v1", organization),
+            authenticator: None,
             user_information: None,
-            http_client: HttpClient::new(),
-        }
-    }
-
-    pub fn set_basic_auth_token(&mut self, username: &str, password: &str) {
-        let token = BASE64_STANDARD.encode(format!("{}:{}", username, password));
-        self.basic_auth_token = Some(token);
-
-        let default_headers = {
-            let mut headers = reqwest::header::HeaderMap::new();
-            headers.insert(
-                reqwest::header::AUTHORIZATION,
-                format!("Basic {}", self.basic_auth_token.as_ref().unwrap())
-                    .parse()
-                    .expect("Failed to parse authorization header"),
-            );
-            headers.insert(
-                reqwest::header::CONTENT_TYPE,
-                "application/json"
-                    .parse()
-                    .expect("Failed to parse content-type header"),
-            );
-            headers
+            identity: 0,
+            http_client: build_http_client(),
+            middlewares: default_chain(),
+            attach_body_to_decode_errors: false,
+            conditional_requests: false,
         };
+        ClientContext {
+            organization: organization.into(),
+            settings: Arc::new(RwLock::new(settings)),
+            reference_cache: Arc::new(ReferenceCache::new()),
+            conditional_cache: Arc::new(ConditionalCache::new()),
+        }
+    }
+
+    /// Returns the organization this context was created for.
+    pub fn organization(&self) -> &str {
+        &self.organization
+    }
+
+    /// Returns the base URL every route path is appended to.
+    pub fn base_url(&self) -> String {
+        self.read_settings().base_url.clone()
+    }
+
+    /// Replaces the base URL, e.g. for self-hosted actiTIME installations.
+    ///
+    /// # Arguments
+    /// * `base_url` - The API base URL, such as `https://actitime.example.com/api/v1`.
+    pub fn set_base_url(&self, base_url: &str) {
+        self.write_settings().base_url = base_url.trim_end_matches('/').to_string();
+    }
+
+    /// Returns `true` if an authenticator is set.
+    pub fn is_authenticated(&self) -> bool {
+        self.read_settings().authenticator.is_some()
+    }
+
+    /// Returns the user stored with `set_user_information`, if any.
+    pub fn user_information(&self) -> Option<User> {
+        self.read_settings().user_information.clone()
+    }
+
+    /// Returns the HTTP client used for route calls.
+    pub fn http_client(&self) -> HttpClient {
+        self.read_settings().http_client.clone()
+    }
+
+    /// Authenticates every request with HTTP Basic credentials.
+    ///
+    /// Shorthand for `set_authenticator(BasicAuthenticator::new(username, password)?)`.
+    pub fn set_basic_auth_token(&self, username: &str, password: &str) {
+        // Base64 output is always a valid header value, so this cannot fail.
+        if let Ok(authenticator) = BasicAuthenticator::new(username, password) {
+            self.set_authenticator(authenticator);
+        }
+    }
+
+    /// Sets the strategy used to authenticate every request.
+    ///
+    /// Cached reference data, conditional responses and `user_information` are dropped, as
+    /// they may not belong to the new identity.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// client_context.set_authenticator(SessionAuthenticator::new("jdoe", &password)?);
+    /// ```
+    pub fn set_authenticator(&self, authenticator: impl Authenticator + 'static) {
+        {
+            let mut settings = self.write_settings();
+            settings.authenticator = Some(Arc::new(authenticator));
+            settings.identity += 1;
+            settings.user_information = None;
+        }
+        self.clear_caches();
+    }
+
+    /// Logs out: ends the session of the current authenticator, forgets it and resets the
+    /// HTTP client, so no credentials are left in its default headers or connections. Cached
+    /// reference data and conditional responses are dropped as well.
+    ///
+    /// # Returns
+    /// The error of the server-side logout, if any. The local state is cleared regardless.
+    pub fn logout(&self) -> Result<(), ClientError> {
+        let (authenticator, base_url, http_client) = {
+            let mut settings = self.write_settings();
+            let authenticator = settings.authenticator.take();
+            settings.identity += 1;
+            let http_client = std::mem::replace(&mut settings.http_client, build_http_client());
+            settings.user_information = None;
+            (authenticator, settings.base_url.clone(), http_client)
+        };
+        self.clear_caches();
+        match authenticator {
+            Some(authenticator) => authenticator.logout(&AuthContext {
+                http_client: &http_client,
+                base_url: &base_url,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Logs out, ignoring server-side logout errors. See `logout`.
+    pub fn clear_basic_auth_token(&self) {
+        let _ = self.logout();
+    }
+
+    pub fn set_user_information(&self, user: User) {
+        self.write_settings().user_information = Some(user);
+    }
+
+    /// Keeps the whole response body in `ClientError::Decode` errors, for debugging.
+    ///
+    /// Off by default, as bodies can be large and contain personal data.
+    pub fn set_attach_body_to_decode_errors(&self, attach_body: bool) {
+        self.write_settings().attach_body_to_decode_errors = attach_body;
+    }
+
+    /// Enables HTTP conditional requests for GET routes.
+    ///
+    /// Responses carrying an `ETag` or `Last-Modified` header are kept per authenticator and URL
+    /// and revalidated with `If-None-Match`/`If-Modified-Since`; on `304 Not Modified` the kept
+    /// body is decoded again. Off by default, as kept bodies stay in memory, up to
+    /// `conditional_cache::MAX_ENTRIES` of them. Disabling forgets them all.
+    pub fn set_conditional_requests(&self, enabled: bool) {
+        self.write_settings().conditional_requests = enabled;
+        if !enabled {
+            self.conditional_cache.clear();
+        }
+    }
+
+    /// Returns the validators kept for the URL of a route, e.g. the `ETag` to send with
+    /// `call_route_if_match`.
+    ///
+    /// Only GET responses are kept, and only while conditional requests are enabled. Any write
+    /// to the same URL forgets them, so read the resource again after changing it.
+    pub fn validators<PayloadType, ResponseType>(
+        &self,
+        route: &Route<PayloadType, ResponseType>,
+    ) -> Option<Validators>
+    where
+        PayloadType: serde::Serialize + serde::de::DeserializeOwned,
+        ResponseType: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        let settings = self.read_settings();
+        let url = format!("{}{}", settings.base_url, route.relative_path);
+        self.conditional_cache.validators(settings.identity, &url)
+    }
 
-        self.http_client = HttpClient::builder()
-            .timeout(std::time::Duration::from_secs(10))
-            .default_headers(default_headers)
-            .build()
-            .expect("Failed to build HTTP client");
+    /// Returns the middleware chain run around every request.
+    pub fn middlewares(&self) -> Vec<Arc<dyn Middleware>> {
+        self.read_settings().middlewares.clone()
     }
 
-    pub fn clear_basic_auth_token(&mut self) {
-        self.basic_auth_token = None;
+    /// Appends a middleware to the chain. Its `before_request` hook runs after the ones
+    /// already in the chain, its `after_response` hook before them.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// client_context.add_middleware(AfterResponseHook(|request: &ApiRequest, response: &ApiResponse| {
+    ///     println!("{} {} {:?}", request.url, response.status, response.elapsed);
+    ///     Ok(())
+    /// }));
+    /// ```
+    pub fn add_middleware(&self, middleware: impl Middleware + 'static) {
+        self.write_settings().middlewares.push(Arc::new(middleware));
     }
 
-    pub fn set_user_information(&mut self, user: User) {
-        self.user_information = Some(user);
+    /// Replaces the whole middleware chain.
+    ///
+    /// The chain starts as `middleware::default_chain()`; leaving out `Authentication`
+    /// sends requests without the credentials of the authenticator.
+    pub fn set_middlewares(&self, middlewares: Vec<Arc<dyn Middleware>>) {
+        self.write_settings().middlewares = middlewares;
     }
 
     pub fn call_route<PayloadType, ResponseType>(
         &self,
-        client_context: &ClientContext,
         route: &Route<PayloadType, ResponseType>,
         payload: Option<&PayloadType>,
     ) -> Result<ResponseType, crate::errors::ClientError>
@@ -70,40 +251,787 @@ impl ClientContext {
         PayloadType: serde::Serialize + serde::de::DeserializeOwned,
         ResponseType: serde::Serialize + serde::de::DeserializeOwned,
     {
-        if client_context.basic_auth_token.is_none() {
-            return Err(ClientError::Unauthorized);
+        self.execute_route(route, payload, None, decode_response)
+    }
+
+    /// Calls a write route only if the resource still matches `etag`, by sending `If-Match`.
+    ///
+    /// # Arguments
+    /// * `route` - The write route, e.g. `patch_task(task_id)`.
+    /// * `payload` - The payload of the route.
+    /// * `etag` - The `ETag` the resource was read with, see `validators`.
+    ///
+    /// # Returns
+    /// `ClientError::Conflict` if the resource was changed since it was read.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// client_context.set_conditional_requests(true);
+    /// let task = client_context.call_route(&get_task(task_id), None)?;
+    /// let etag = client_context
+    ///     .validators(&get_task(task_id))
+    ///     .and_then(|validators| validators.etag)
+    ///     .ok_or_else(|| ClientError::Message("No ETag for the task".to_string()))?;
+    /// match client_context.call_route_if_match(&patch_task(task_id), Some(&fields), &etag) {
+    ///     Err(ClientError::Conflict) => eprintln!("Task #{} was changed meanwhile, reload it", task.id),
+    ///     result => { result?; }
+    /// }
+    /// ```
+    pub fn call_route_if_match<PayloadType, ResponseType>(
+        &self,
+        route: &Route<PayloadType, ResponseType>,
+        payload: Option<&PayloadType>,
+        etag: &str,
+    ) -> Result<ResponseType, ClientError>
+    where
+        PayloadType: serde::Serialize + serde::de::DeserializeOwned,
+        ResponseType: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        self.execute_route(route, payload, Some(etag), decode_response)
+    }
+
+    /// Calls a list route in lenient mode: items that fail to decode are returned in
+    /// `Lenient::errors` instead of failing the whole page.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let page = client_context.call_route_lenient(&get_tasks(Some(parameters)), None)?;
+    /// for error in &page.errors {
+    ///     eprintln!("Skipped task #{}: {}", error.index, error.error);
+    /// }
+    /// let tasks = page.value.items;
+    /// ```
+    pub fn call_route_lenient<PayloadType, ResponseType>(
+        &self,
+        route: &Route<PayloadType, ResponseType>,
+        payload: Option<&PayloadType>,
+    ) -> Result<Lenient<ResponseType>, ClientError>
+    where
+        PayloadType: serde::Serialize + serde::de::DeserializeOwned,
+        ResponseType: ListPayload,
+    {
+        self.execute_route(route, payload, None, decode_lenient_response)
+    }
+
+    /// Sends a route call through the middleware chain and decodes a successful response
+    /// with `decode`.
+    fn execute_route<PayloadType, ResponseType, Decoded>(
+        &self,
+        route: &Route<PayloadType, ResponseType>,
+        payload: Option<&PayloadType>,
+        if_match: Option<&str>,
+        decode: impl FnOnce(&[u8], bool) -> Result<Decoded, ClientError>,
+    ) -> Result<Decoded, ClientError>
+    where
+        PayloadType: serde::Serialize + serde::de::DeserializeOwned,
+        ResponseType: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        let span = CallSpan::enter(&self.organization, &route.method, &route.template());
+        let (base_url, http_client, authenticator, middlewares, attach_body, conditional, identity) = {
+            let settings = self.read_settings();
+            (
+                settings.base_url.clone(),
+                settings.http_client.clone(),
+                settings.authenticator.clone(),
+                settings.middlewares.clone(),
+                settings.attach_body_to_decode_errors,
+                settings.conditional_requests,
+                settings.identity,
+            )
+        };
+        let body = payload
+            .map(serde_json::to_vec)
+            .transpose()
+            .map_err(ClientError::SerdeJson)?;
+        let mut request = ApiRequest {
+            method: route.method.clone(),
+            url: format!("{}{}", base_url, route.relative_path),
+            headers: HeaderMap::new(),
+            body,
+        };
+        if let Some(etag) = if_match {
+            request.set_header("If-Match", etag)?;
+        }
+        let cached = match route.method {
+            Method::GET if conditional => self.conditional_cache.get(identity, &request.url),
+            _ => None,
+        };
+        if let Some(cached) = &cached {
+            if let Some(etag) = &cached.validators.etag {
+                request.set_header("If-None-Match", etag)?;
+            }
+            if let Some(last_modified) = &cached.validators.last_modified {
+                request.set_header("If-Modified-Since", last_modified)?;
+            }
         }
 
-        let url = format!("{}{}", self.base_url, route.relative_path);
-        let request_builder = match route.method {
-            Method::GET => self.http_client.get(&url),
-            Method::POST => self.http_client.post(&url),
-            Method::PUT => self.http_client.put(&url),
-            Method::DELETE => self.http_client.delete(&url),
-            Method::PATCH => self.http_client.patch(&url),
+        let mut context = CallContext {
+            organization: &self.organization,
+            base_url: &base_url,
+            http_client: &http_client,
+            authenticator: authenticator.as_deref(),
+            attempt: 0,
+        };
+        let mut response = loop {
+            let (response, retry) = run_chain(&context, &middlewares, request.clone())
+                .inspect_err(|error| span.failed(error))?;
+            if !retry || context.attempt + 1 >= MAX_ATTEMPTS {
+                break response;
+            }
+            context.attempt += 1;
         };
+        span.response(response.status, context.attempt);
+        if route.method != Method::GET {
+            let mut written = vec![route.relative_path.clone()];
+            if route.relative_path.starts_with("/batch") {
+                match batch_write_paths(request.body.as_deref()) {
+                    Some(paths) => written.extend(paths),
+                    None => self.invalidate_all_reference_data(),
+                }
+            }
+            for path in &written {
+                if let Some(kind) = ReferenceDataKind::for_path(path) {
+                    self.reference_cache.invalidate(kind);
+                }
+                let path = path.trim_start_matches('/');
+                self.conditional_cache
+                    .remove(&format!("{}/{}", base_url, path));
+            }
+        } else if conditional {
+            match cached {
+                Some(cached) if response.status == StatusCode::NOT_MODIFIED => {
+                    response.status = StatusCode::OK;
+                    response.headers = cached.headers;
+                    response.body = cached.body;
+                }
+                _ if response.status.is_success() => {
+                    self.conditional_cache.store(
+                        identity,
+                        &request.url,
+                        &response.headers,
+                        &response.body,
+                    );
+                }
+                _ => {}
+            }
+        }
 
-        let request_builder = if let Some(ref payload) = payload {
-            request_builder.json(payload)
+        if response.status == StatusCode::NOT_MODIFIED {
+            // Answer to validators added by a middleware, with no kept body to stand in for it
+            return Err(ClientError::Message(format!(
+                "{} answered 304 Not Modified, but no response is kept for it; \
+                 send it without If-None-Match or If-Modified-Since",
+                request.url
+            )));
+        }
+        if response.status.is_success() {
+            decode(&response.body, attach_body)
+        } else if response.status == StatusCode::PRECONDITION_FAILED {
+            Err(ClientError::Conflict)
         } else {
-            request_builder
+            let response_text = response.text();
+            match serde_json::from_str::<ApiError>(&response_text) {
+                Ok(error) => Err(ClientError::ApiError(response.status.as_u16(), error)),
+                Err(_) if !response_text.is_empty() => Err(ClientError::Message(response_text)),
+                Err(e) => Err(ClientError::SerdeJson(e)),
+            }
+        }
+    }
+
+    /// Fetches the work schedule of the authenticated user.
+    ///
+    /// Uses the id from `user_information` when it is set, otherwise resolves it with `get_users_me`
+    /// and keeps the result in `user_information` for later calls.
+    ///
+    /// # Arguments
+    /// * `dates` - A single `NaiveDate`, a `(from, to)` pair, a `from..=to` or `from..to` range or a `NaiveWeek`.
+    ///
+    /// # Returns
+    /// A `ClientError::Message` without calling the API if the range is empty, e.g. `d..d`.
+    pub fn get_my_schedule(&self, dates: impl IntoDateRange) -> Result<Schedule, ClientError> {
+        let dates = checked_date_range(dates)?;
+        let user_id = match self.user_information() {
+            Some(user) => user.id,
+            None => {
+                let user = User::from(self.call_route(&get_users_me(), None)?);
+                let user_id = user.id;
+                self.set_user_information(user);
+                user_id
+            }
         };
+        self.call_route(&get_users_schedule(user_id, dates)?, None)
+    }
 
-        let response = request_builder.send().map_err(ClientError::Reqwest)?;
+    /// Fetches the work schedules of every user of a department.
+    ///
+    /// # Arguments
+    /// * `department_id` - The department whose users are fetched.
+    /// * `dates` - A single `NaiveDate`, a `(from, to)` pair, a `from..=to` or `from..to` range or a `NaiveWeek`.
+    ///
+    /// # Returns
+    /// The schedules keyed by user id, or a `ClientError::Message` if the range is empty.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let schedules = client_context.get_department_schedules(department_id, week)?;
+    /// let capacity: f64 = schedules
+    ///     .values()
+    ///     .map(|schedule| schedule.total_hours(schedule.date_from, schedule.date_to))
+    ///     .sum();
+    /// ```
+    pub fn get_department_schedules(
+        &self,
+        department_id: i32,
+        dates: impl IntoDateRange,
+    ) -> Result<BTreeMap<i32, Schedule>, ClientError> {
+        let dates = checked_date_range(dates)?;
+        let users =
+            self.get_all_users(GetUsersParameters::new().with_department_ids(vec![department_id]))?;
+        let mut schedules = BTreeMap::new();
+        for user in &users {
+            let schedule = self.call_route(&get_users_schedule(user.id, dates)?, None)?;
+            schedules.insert(user.id, schedule);
+        }
+        Ok(schedules)
+    }
 
-        let response_code = response.status().as_u16();
-        if response.status().is_success() {
-            let response_json = response.json().map_err(ClientError::Reqwest)?;
-            let response = serde_json::from_value(response_json).map_err(ClientError::SerdeJson)?;
-            Ok(response)
-        } else {
-            let response_json = response.text().unwrap_or_default();
-            if !response_json.is_empty() {
-                return Err(ClientError::Message(response_json));
+    /// Fetches every user matching `parameters`, walking through all pages.
+    ///
+    /// The `offset` and `limit` of `parameters` are ignored.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let users = client_context.get_all_users(GetUsersParameters::new())?;
+    /// ```
+    pub fn get_all_users(&self, parameters: GetUsersParameters) -> Result<Vec<User>, ClientError> {
+        let mut users = vec![];
+        let mut offset = 0;
+        loop {
+            let page_parameters = parameters.clone().with_offset(offset).with_limit(PAGE_SIZE);
+            let page = self.call_route(&get_users(Some(page_parameters)), None)?;
+            let page_len = page.items.len() as u32;
+            users.extend(page.items);
+            if page_len < PAGE_SIZE {
+                return Ok(users);
+            }
+            offset += PAGE_SIZE;
+        }
+    }
+
+    /// Fetches every task matching `parameters`, walking through all pages.
+    ///
+    /// The `offset` and `limit` of `parameters` are ignored.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let parameters = GetTasksParameters::new().with_project_ids(vec![project_id]);
+    /// let tasks = client_context.get_all_tasks(parameters)?;
+    /// ```
+    pub fn get_all_tasks(&self, parameters: GetTasksParameters) -> Result<Vec<Task>, ClientError> {
+        self.get_complete_task_list(parameters)
+            .map(|task_list| task_list.items)
+    }
+
+    /// Fetches every task matching `parameters` as one `TaskList`, merging the pages together
+    /// with the entities included through `include_referenced`.
+    ///
+    /// The `offset` and `limit` of `parameters` are ignored.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let parameters = GetTasksParameters::new()
+    ///     .with_include_referenced(vec![GetTasksIncludeReferenced::Projects]);
+    /// let task_list = client_context.get_complete_task_list(parameters)?;
+    /// let enriched = task_list.enriched();
+    /// ```
+    pub fn get_complete_task_list(
+        &self,
+        parameters: GetTasksParameters,
+    ) -> Result<TaskList, ClientError> {
+        let page = |offset| {
+            let page_parameters = parameters.clone().with_offset(offset).with_limit(PAGE_SIZE);
+            self.call_route(&get_tasks(Some(page_parameters)), None)
+        };
+        let mut task_list = page(0)?;
+        let mut page_len = task_list.items.len() as u32;
+        let mut offset = 0;
+        while page_len >= PAGE_SIZE {
+            offset += PAGE_SIZE;
+            let next_page = page(offset)?;
+            page_len = next_page.items.len() as u32;
+            task_list.merge(next_page);
+        }
+        Ok(task_list)
+    }
+
+    /// Fetches the time tracked over a date range, following `next_date_from` whenever the
+    /// server cuts the response short.
+    ///
+    /// # Arguments
+    /// * `parameters` - The filters; `with_dates` should be set, as the server otherwise
+    ///   picks the range.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let parameters = GetTimeTrackParameters::new().with_dates(month_start..=month_end);
+    /// let time_track = client_context.get_complete_time_track(parameters)?;
+    /// println!("{} tracked", time_track.total_time());
+    /// ```
+    pub fn get_complete_time_track(
+        &self,
+        mut parameters: GetTimeTrackParameters,
+    ) -> Result<TimeTrack, ClientError> {
+        let mut time_track = self.call_route(&get_time_track(Some(parameters.clone())), None)?;
+        while let Some(next_date_from) = time_track.next_date_from {
+            if parameters
+                .date_from
+                .is_some_and(|date_from| next_date_from <= date_from)
+            {
+                return Err(ClientError::Message(format!(
+                    "Time-track paging did not advance past {}",
+                    next_date_from
+                )));
+            }
+            parameters.date_from = Some(next_date_from);
+            let next = self.call_route(&get_time_track(Some(parameters.clone())), None)?;
+            time_track.merge(next);
+        }
+        Ok(time_track)
+    }
+
+    /// Fetches the leave time over a date range, following `next_date_from` until the
+    /// server has returned every day.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let parameters = GetLeaveTimeParameters::new().with_dates(from..=to);
+    /// let leave_time = client_context.get_complete_leave_time(parameters)?;
+    /// ```
+    pub fn get_complete_leave_time(
+        &self,
+        mut parameters: GetLeaveTimeParameters,
+    ) -> Result<LeaveTime, ClientError> {
+        let mut leave_time = self.call_route(&get_leave_time(Some(parameters.clone())), None)?;
+        while let Some(next_date_from) = leave_time.next_date_from {
+            if parameters
+                .date_from
+                .is_some_and(|date_from| next_date_from <= date_from)
+            {
+                return Err(ClientError::Message(format!(
+                    "Leave-time paging did not advance past {}",
+                    next_date_from
+                )));
+            }
+            parameters.date_from = Some(next_date_from);
+            let next = self.call_route(&get_leave_time(Some(parameters.clone())), None)?;
+            leave_time.merge(next);
+        }
+        Ok(leave_time)
+    }
+
+    /// Returns every entity of a reference data kind, from the cache while it is fresh.
+    ///
+    /// On a miss, all pages of the list route are fetched and cached.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let projects = client_context.reference_data::<Project>()?;
+    /// ```
+    pub fn reference_data<T: ReferenceData>(&self) -> Result<Arc<Vec<T>>, ClientError> {
+        let generation = match self.reference_cache.get::<T>() {
+            Ok(items) => return Ok(items),
+            Err(generation) => generation,
+        };
+        let mut items = vec![];
+        let mut offset = 0;
+        loop {
+            let page = self.call_route(&T::list_route(offset, PAGE_SIZE), None)?;
+            let page_len = page.items.len() as u32;
+            items.extend(page.items);
+            if page_len < PAGE_SIZE {
+                break;
+            }
+            offset += PAGE_SIZE;
+        }
+        let items = Arc::new(items);
+        self.reference_cache.store(Arc::clone(&items), generation);
+        Ok(items)
+    }
+
+    /// Looks up a reference data entity by id, through the cache.
+    pub fn reference_by_id<T: ReferenceData>(&self, id: i32) -> Result<Option<T>, ClientError> {
+        Ok(self
+            .reference_data::<T>()?
+            .iter()
+            .find(|item| item.id() == id)
+            .cloned())
+    }
+
+    /// Looks up a reference data entity by name, ignoring case, through the cache.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let customer = client_context.reference_by_name::<Customer>("Acme")?;
+    /// ```
+    pub fn reference_by_name<T: ReferenceData>(
+        &self,
+        name: &str,
+    ) -> Result<Option<T>, ClientError> {
+        Ok(self
+            .reference_data::<T>()?
+            .iter()
+            .find(|item| item.name().eq_ignore_ascii_case(name))
+            .cloned())
+    }
+
+    /// Sets how long a kind of reference data is cached. A zero duration disables caching.
+    pub fn set_reference_ttl(&self, kind: ReferenceDataKind, ttl: Duration) {
+        self.reference_cache.set_ttl(kind, ttl);
+    }
+
+    /// Drops the cached entities of a kind, so the next lookup fetches them again.
+    pub fn invalidate_reference_data(&self, kind: ReferenceDataKind) {
+        self.reference_cache.invalidate(kind);
+    }
+
+    /// Drops every cached reference data entity.
+    pub fn invalidate_all_reference_data(&self) {
+        for kind in ReferenceDataKind::ALL {
+            self.reference_cache.invalidate(kind);
+        }
+    }
+
+    /// Returns the cache statistics of every kind of reference data.
+    pub fn reference_cache_stats(&self) -> BTreeMap<ReferenceDataKind, CacheStats> {
+        self.reference_cache.stats()
+    }
+
+    /// Calls many routes in parallel and returns their results in the order of `requests`.
+    ///
+    /// At most `max_concurrency` requests are in flight at any time; a value of 0 is treated as 1.
+    /// A failing request does not stop the others.
+    ///
+    /// # Arguments
+    /// * `requests` - The routes to call, each with its optional payload.
+    /// * `max_concurrency` - The maximum number of simultaneous requests.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let requests = user_ids
+    ///     .iter()
+    ///     .map(|id| Ok((get_users_schedule(*id, week)?, None)))
+    ///     .collect::<Result<Vec<_>, ClientError>>()?;
+    /// let schedules = client_context.call_routes_concurrently(&requests, 8);
+    /// ```
+    pub fn call_routes_concurrently<PayloadType, ResponseType>(
+        &self,
+        requests: &[(Route<PayloadType, ResponseType>, Option<PayloadType>)],
+        max_concurrency: usize,
+    ) -> Vec<Result<ResponseType, ClientError>>
+    where
+        PayloadType: serde::Serialize + serde::de::DeserializeOwned + Sync,
+        ResponseType: serde::Serialize + serde::de::DeserializeOwned + Send + Sync,
+    {
+        let next_index = AtomicUsize::new(0);
+        let worker_count = max_concurrency.clamp(1, requests.len().max(1));
+
+        let mut results: Vec<Option<Result<ResponseType, ClientError>>> =
+            requests.iter().map(|_| None).collect();
+        thread::scope(|scope| {
+            let workers: Vec<_> = (0..worker_count)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut completed = vec![];
+                        loop {
+                            let index = next_index.fetch_add(1, Ordering::Relaxed);
+                            let Some((route, payload)) = requests.get(index) else {
+                                break;
+                            };
+                            completed.push((index, self.call_route(route, payload.as_ref())));
+                        }
+                        completed
+                    })
+                })
+                .collect();
+            for worker in workers {
+                // A panicking worker leaves its slots empty; they are reported below.
+                if let Ok(completed) = worker.join() {
+                    for (index, result) in completed {
+                        results[index] = Some(result);
+                    }
+                }
+            }
+        });
+
+        results
+            .into_iter()
+            .map(|result| {
+                result.unwrap_or_else(|| {
+                    Err(ClientError::Message("Request worker panicked".to_string()))
+                })
+            })
+            .collect()
+    }
+
+    /// Drops everything cached on behalf of the current identity.
+    fn clear_caches(&self) {
+        self.invalidate_all_reference_data();
+        self.conditional_cache.clear();
+    }
+
+    fn read_settings(&self) -> RwLockReadGuard<'_, Settings> {
+        self.settings.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write_settings(&self) -> RwLockWriteGuard<'_, Settings> {
+        self.settings
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Builds the HTTP client used for route calls, without any credentials.
+fn build_http_client() -> HttpClient {
+    let mut default_headers = HeaderMap::new();
+    default_headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    HttpClient::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .default_headers(default_headers)
+        .build()
+        .expect("Failed to build HTTP client")
+}
+
+/// Returns the relative URLs of the items of a `/batch` body that are not GET requests, or
+/// `None` if the body cannot be read as a list of batch items.
+fn batch_write_paths(body: Option<&[u8]>) -> Option<Vec<String>> {
+    let items: Vec<serde_json::Value> = serde_json::from_slice(body?).ok()?;
+    Some(
+        items
+            .iter()
+            .filter(|item| item.get("method").and_then(|method| method.as_str()) != Some("GET"))
+            .filter_map(|item| item.get("relativeUrl").and_then(|url| url.as_str()))
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+/// Runs one attempt of a call through the middleware chain.
+///
+/// # Returns
+/// The response, and `true` if a middleware asked to start the call over.
+fn run_chain(
+    context: &CallContext<'_>,
+    middlewares: &[Arc<dyn Middleware>],
+    mut request: ApiRequest,
+) -> Result<(ApiResponse, bool), ClientError> {
+    let mut entered = 0;
+    let mut short_circuit = None;
+    for middleware in middlewares {
+        if let Flow::Respond(response) = middleware.before_request(context, &mut request)? {
+            short_circuit = Some(response);
+            break;
+        }
+        entered += 1;
+    }
+
+    let mut response = match short_circuit {
+        Some(response) => response,
+        None => send_request(context.http_client, &request)?,
+    };
+    for middleware in middlewares[..entered].iter().rev() {
+        match middleware.after_response(context, &request, &mut response)? {
+            Flow::Continue => {}
+            Flow::Respond(replacement) => return Ok((replacement, false)),
+            Flow::Retry => return Ok((response, true)),
+        }
+    }
+    Ok((response, false))
+}
+
+/// Sends a request and reads the whole response.
+fn send_request(
+    http_client: &HttpClient,
+    request: &ApiRequest,
+) -> Result<ApiResponse, ClientError> {
+    let method = match request.method {
+        Method::GET => reqwest::Method::GET,
+        Method::POST => reqwest::Method::POST,
+        Method::PUT => reqwest::Method::PUT,
+        Method::DELETE => reqwest::Method::DELETE,
+        Method::PATCH => reqwest::Method::PATCH,
+        Method::Unknown(ref method) => reqwest::Method::from_bytes(method.as_bytes())
+            .map_err(|e| ClientError::Other(Box::new(e)))?,
+    };
+    let mut request_builder = http_client
+        .request(method, &request.url)
+        .headers(request.headers.clone());
+    if let Some(body) = &request.body {
+        request_builder = request_builder.body(body.clone());
+    }
+
+    let started = Instant::now();
+    let response = request_builder.send().map_err(ClientError::Reqwest)?;
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = response.bytes().map_err(ClientError::Reqwest)?.to_vec();
+    Ok(ApiResponse {
+        status,
+        headers,
+        body,
+        elapsed: started.elapsed(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    use reqwest::header::ETAG;
+
+    use super::*;
+    use crate::payload::EntityFields;
+    use crate::routes::{get_task, patch_task};
+
+    /// Answers every request with the next canned response, recording the requests.
+    #[derive(Clone, Default)]
+    struct Canned {
+        responses: Arc<Mutex<VecDeque<ApiResponse>>>,
+        requests: Arc<Mutex<Vec<ApiRequest>>>,
+    }
+
+    impl Canned {
+        fn respond(&self, status: StatusCode, etag: Option<&'static str>, body: &str) -> &Self {
+            let mut headers = HeaderMap::new();
+            if let Some(etag) = etag {
+                headers.insert(ETAG, HeaderValue::from_static(etag));
             }
-            let error =
-                serde_json::from_str::<ApiError>(&response_json).map_err(ClientError::SerdeJson)?;
-            Err(ClientError::ApiError(response_code, error))
+            let response = ApiResponse {
+                status,
+                headers,
+                body: body.as_bytes().to_vec(),
+                elapsed: Duration::ZERO,
+            };
+            self.lock_responses().push_back(response);
+            self
+        }
+
+        fn request_header(&self, index: usize, name: &str) -> Option<String> {
+            let requests = self.requests.lock().unwrap_or_else(PoisonError::into_inner);
+            let value = requests.get(index)?.headers.get(name)?.to_str().ok()?;
+            Some(value.to_string())
         }
+
+        fn lock_responses(&self) -> std::sync::MutexGuard<'_, VecDeque<ApiResponse>> {
+            self.responses
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+        }
+    }
+
+    impl Middleware for Canned {
+        fn before_request(
+            &self,
+            _context: &CallContext<'_>,
+            request: &mut ApiRequest,
+        ) -> Result<Flow, ClientError> {
+            self.requests
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(request.clone());
+            let response = self.lock_responses().pop_front();
+            response
+                .map(Flow::Respond)
+                .ok_or_else(|| ClientError::Message("No canned response left".to_string()))
+        }
+    }
+
+    fn canned_context(canned: &Canned) -> Result<ClientContext, ClientError> {
+        let client_context = ClientContext::new("acme".to_string());
+        client_context.set_authenticator(BasicAuthenticator::new("jdoe", "secret")?);
+        client_context.set_conditional_requests(true);
+        client_context.add_middleware(canned.clone());
+        Ok(client_context)
+    }
+
+    #[test]
+    fn replays_kept_response_on_not_modified() -> Result<(), ClientError> {
+        let canned = Canned::default();
+        canned
+            .respond(
+                StatusCode::OK,
+                Some("\"v1\""),
+                r#"{"id":7,"name":"Design"}"#,
+            )
+            .respond(StatusCode::NOT_MODIFIED, None, "");
+        let client_context = canned_context(&canned)?;
+
+        assert_eq!(
+            client_context.call_route(&get_task(7), None)?.name,
+            "Design"
+        );
+        let replayed = client_context.call_route(&get_task(7), None)?;
+        assert_eq!(replayed.name, "Design");
+        assert_eq!(canned.request_header(0, "If-None-Match"), None);
+        assert_eq!(
+            canned.request_header(1, "If-None-Match").as_deref(),
+            Some("\"v1\"")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fails_on_not_modified_without_kept_response() -> Result<(), ClientError> {
+        let canned = Canned::default();
+        canned.respond(StatusCode::NOT_MODIFIED, None, "");
+        let client_context = canned_context(&canned)?;
+
+        let result = client_context.call_route(&get_task(7), None);
+        assert!(matches!(
+            result,
+            Err(ClientError::Message(message)) if message.contains("304 Not Modified")
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn maps_precondition_failed_to_conflict() -> Result<(), ClientError> {
+        let canned = Canned::default();
+        canned
+            .respond(
+                StatusCode::OK,
+                Some("\"v1\""),
+                r#"{"id":7,"name":"Design"}"#,
+            )
+            .respond(StatusCode::PRECONDITION_FAILED, None, "");
+        let client_context = canned_context(&canned)?;
+
+        client_context.call_route(&get_task(7), None)?;
+        assert!(client_context.validators(&get_task(7)).is_some());
+        let fields = EntityFields::new();
+        let result = client_context.call_route_if_match(&patch_task(7), Some(&fields), "\"v1\"");
+        assert!(matches!(result, Err(ClientError::Conflict)));
+        assert_eq!(
+            canned.request_header(1, "If-Match").as_deref(),
+            Some("\"v1\"")
+        );
+        // The write forgets the kept response, even when refused
+        assert!(client_context.validators(&get_task(7)).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn batch_write_paths_skip_reads() {
+        let body = br#"[
+            {"method":"GET","relativeUrl":"/customers/1"},
+            {"method":"PATCH","relativeUrl":"/projects/7","body":{"name":"Web"}},
+            {"method":"POST","relativeUrl":"/typesOfWork"}
+        ]"#;
+        assert_eq!(
+            batch_write_paths(Some(body)),
+            Some(vec!["/projects/7".to_string(), "/typesOfWork".to_string()])
+        );
+        assert_eq!(batch_write_paths(Some(b"not json")), None);
+        assert_eq!(batch_write_paths(None), None);
     }
 }
@@ -0,0 +1,211 @@
+//! Validators and bodies of GET responses, for HTTP conditional requests.
+//!
+//! Once enabled with `ClientContext::set_conditional_requests`, every successful GET response
+//! carrying an `ETag` or `Last-Modified` header is kept per identity and URL. The next GET of
+//! that URL by the same identity sends `If-None-Match`/`If-Modified-Since`, and a
+//! `304 Not Modified` is answered with the kept body. Any write to a URL forgets what was kept
+//! for it, and at most `MAX_ENTRIES` responses are kept, dropping the least recently used.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use reqwest::header::{ETAG, HeaderMap, HeaderValue, LAST_MODIFIED};
+
+/// Validators of a response, as sent by the server
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Validators {
+    /// Value of the `ETag` header, quotes included
+    pub etag: Option<String>,
+    /// Value of the `Last-Modified` header
+    pub last_modified: Option<String>,
+}
+
+/// Response kept for a URL
+#[derive(Clone)]
+pub(crate) struct CachedResponse {
+    pub(crate) validators: Validators,
+    pub(crate) headers: HeaderMap,
+    pub(crate) body: Vec<u8>,
+}
+
+/// Maximum number of responses kept
+pub const MAX_ENTRIES: usize = 1000;
+
+/// Kept responses by identity and full URL, shared by every clone of a `ClientContext`
+///
+/// The identity is a number the context changes with every authenticator, so a response
+/// fetched with one set of credentials is never served to another.
+pub(crate) struct ConditionalCache {
+    entries: Mutex<Entries>,
+}
+
+struct Entries {
+    /// Responses with the tick of their last use
+    responses: HashMap<(u64, String), (u64, CachedResponse)>,
+    tick: u64,
+}
+
+impl Validators {
+    /// Reads the validators of a response, if it has any.
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value: &HeaderValue| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let validators = Validators {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        };
+        (validators.etag.is_some() || validators.last_modified.is_some()).then_some(validators)
+    }
+}
+
+impl ConditionalCache {
+    pub(crate) fn new() -> Self {
+        ConditionalCache {
+            entries: Mutex::new(Entries {
+                responses: HashMap::new(),
+                tick: 0,
+            }),
+        }
+    }
+
+    pub(crate) fn get(&self, identity: u64, url: &str) -> Option<CachedResponse> {
+        let mut entries = self.lock();
+        entries.tick += 1;
+        let tick = entries.tick;
+        let (last_used, response) = entries.responses.get_mut(&(identity, url.to_string()))?;
+        *last_used = tick;
+        Some(response.clone())
+    }
+
+    pub(crate) fn validators(&self, identity: u64, url: &str) -> Option<Validators> {
+        self.lock()
+            .responses
+            .get(&(identity, url.to_string()))
+            .map(|(_, response)| response.validators.clone())
+    }
+
+    pub(crate) fn store(&self, identity: u64, url: &str, headers: &HeaderMap, body: &[u8]) {
+        let mut entries = self.lock();
+        let key = (identity, url.to_string());
+        let Some(validators) = Validators::from_headers(headers) else {
+            entries.responses.remove(&key);
+            return;
+        };
+        if !entries.responses.contains_key(&key) && entries.responses.len() >= MAX_ENTRIES {
+            let least_recent = entries
+                .responses
+                .iter()
+                .min_by_key(|(_, (last_used, _))| *last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(least_recent) = least_recent {
+                entries.responses.remove(&least_recent);
+            }
+        }
+        entries.tick += 1;
+        let response = CachedResponse {
+            validators,
+            headers: headers.clone(),
+            body: body.to_vec(),
+        };
+        let tick = entries.tick;
+        entries.responses.insert(key, (tick, response));
+    }
+
+    /// Forgets the responses kept for `url`, whatever the identity.
+    pub(crate) fn remove(&self, url: &str) {
+        self.lock()
+            .responses
+            .retain(|(_, kept_url), _| kept_url != url);
+    }
+
+    pub(crate) fn clear(&self) {
+        self.lock().responses.clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Entries> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn etag(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn keeps_responses_per_identity() {
+        let cache = ConditionalCache::new();
+        cache.store(1, "https://x/api/v1/tasks", &etag("\"a\""), b"[]");
+        assert!(cache.get(1, "https://x/api/v1/tasks").is_some());
+        assert!(cache.get(2, "https://x/api/v1/tasks").is_none());
+        cache.remove("https://x/api/v1/tasks");
+        assert!(cache.get(1, "https://x/api/v1/tasks").is_none());
+    }
+
+    #[test]
+    fn reads_validators_from_headers() {
+        assert_eq!(Validators::from_headers(&HeaderMap::new()), None);
+        let mut headers = etag("\"a\"");
+        headers.insert(
+            LAST_MODIFIED,
+            HeaderValue::from_static("Tue, 01 Oct 2024 08:00:00 GMT"),
+        );
+        assert_eq!(
+            Validators::from_headers(&headers),
+            Some(Validators {
+                etag: Some("\"a\"".to_string()),
+                last_modified: Some("Tue, 01 Oct 2024 08:00:00 GMT".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn forgets_responses_stored_without_validators() {
+        let cache = ConditionalCache::new();
+        cache.store(1, "https://x/api/v1/tasks", &etag("\"a\""), b"[1]");
+        let kept = cache
+            .get(1, "https://x/api/v1/tasks")
+            .map(|response| response.body);
+        assert_eq!(kept.as_deref(), Some(&b"[1]"[..]));
+        assert_eq!(
+            cache
+                .validators(1, "https://x/api/v1/tasks")
+                .and_then(|validators| validators.etag),
+            Some("\"a\"".to_string())
+        );
+        cache.store(1, "https://x/api/v1/tasks", &HeaderMap::new(), b"[2]");
+        assert!(cache.get(1, "https://x/api/v1/tasks").is_none());
+    }
+
+    #[test]
+    fn clears_every_identity() {
+        let cache = ConditionalCache::new();
+        cache.store(1, "https://x/a", &etag("\"a\""), b"{}");
+        cache.store(2, "https://x/b", &etag("\"b\""), b"{}");
+        cache.clear();
+        assert!(cache.get(1, "https://x/a").is_none());
+        assert!(cache.get(2, "https://x/b").is_none());
+    }
+
+    #[test]
+    fn drops_least_recently_used_beyond_capacity() {
+        let cache = ConditionalCache::new();
+        for index in 0..MAX_ENTRIES {
+            cache.store(1, &format!("https://x/{}", index), &etag("\"a\""), b"{}");
+        }
+        assert!(cache.get(1, "https://x/0").is_some());
+        cache.store(1, "https://x/new", &etag("\"a\""), b"{}");
+        assert_eq!(cache.lock().responses.len(), MAX_ENTRIES);
+        assert!(cache.get(1, "https://x/0").is_some());
+        assert!(cache.get(1, "https://x/1").is_none());
+    }
+}
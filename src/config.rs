@@ -0,0 +1,447 @@
+//! Profile-based configuration shared by every tool built on this crate.
+//!
+//! Profiles are read from a TOML file, by default `$ACTITIME_CONFIG`, then
+//! `$XDG_CONFIG_HOME/actitime/config.toml` or `~/.config/actitime/config.toml`
+//! (`%APPDATA%\actitime\config.toml` on Windows):
+//!
+//! ``` toml
+//! default_profile = "work"
+//!
+//! [profiles.work]
+//! organization = "acme"
+//! username = "jdoe"
+//! password = { env = "ACTITIME_WORK_PASSWORD" }
+//!
+//! [profiles.on-premise]
+//! base_url = "https://actitime.example.com/api/v1"
+//! username = "jdoe"
+//! password = { command = "pass show actitime" }
+//! ```
+//!
+//! A password is either a literal string or one of `{ env = "VAR" }`, `{ file = "path" }`
+//! and `{ command = "shell command" }`.
+//!
+//! The environment variables `ACTITIME_PROFILE`, `ACTITIME_ORGANIZATION`, `ACTITIME_BASE_URL`,
+//! `ACTITIME_USERNAME` and `ACTITIME_PASSWORD` override the values of the file. An organization
+//! set through the environment replaces a `base_url` of the file, unless `ACTITIME_BASE_URL` is
+//! set as well.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+
+use serde::Deserialize;
+use zeroize::Zeroizing;
+
+use crate::client_context::ClientContext;
+use crate::errors::ClientError;
+use crate::secret::Secret;
+
+/// Name of the profile used when none is selected
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Contents of a configuration file
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Config {
+    /// Profile used when none is selected explicitly or through `ACTITIME_PROFILE`
+    #[serde(default)]
+    pub default_profile: Option<String>,
+    /// Profiles by name
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// Connection settings of one profile
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Profile {
+    /// actiTIME Online organization, used to build the base URL
+    #[serde(default)]
+    pub organization: Option<String>,
+    /// Full API base URL, e.g. for self-hosted installations. Takes precedence over `organization`.
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Username used for authentication
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Where to read the password from
+    #[serde(default)]
+    pub password: Option<PasswordSource>,
+}
+
+/// Source of a profile password
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum PasswordSource {
+    /// Password written in the configuration file
+    Literal(Secret),
+    /// Password read from an environment variable
+    Env {
+        /// Name of the environment variable
+        env: String,
+    },
+    /// Password read from the first line of a file
+    File {
+        /// Path of the file
+        file: PathBuf,
+    },
+    /// Password printed by an external command, e.g. a password manager
+    Command {
+        /// Shell command whose first output line is the password
+        command: String,
+    },
+}
+
+impl FromStr for Config {
+    type Err = ClientError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        toml::from_str(s).map_err(|e| ClientError::Other(Box::new(e)))
+    }
+}
+
+impl Config {
+    /// Loads the configuration from the default location.
+    ///
+    /// A missing file is not an error: the returned configuration is empty, so profiles can
+    /// still be defined entirely through environment variables.
+    pub fn load() -> Result<Self, ClientError> {
+        match default_path() {
+            Some(path) if path.exists() => Config::from_path(&path),
+            _ => Ok(Config::default()),
+        }
+    }
+
+    /// Loads the configuration from `path`.
+    pub fn from_path(path: &Path) -> Result<Self, ClientError> {
+        let contents = fs::read_to_string(path).map_err(|e| {
+            ClientError::Message(format!("Cannot read config {}: {}", path.display(), e))
+        })?;
+        contents.parse()
+    }
+
+    /// Resolves a profile and applies the environment overrides.
+    ///
+    /// # Arguments
+    /// * `name` - The profile to use. Falls back to `ACTITIME_PROFILE`, then `default_profile`,
+    ///   then `"default"`.
+    ///
+    /// # Returns
+    /// The resolved profile, or a `ClientError::Message` if an explicitly selected profile does not exist.
+    pub fn profile(&self, name: Option<&str>) -> Result<Profile, ClientError> {
+        let explicit = name
+            .map(str::to_string)
+            .or_else(|| env_var("ACTITIME_PROFILE"))
+            .or_else(|| self.default_profile.clone());
+        let profile = match &explicit {
+            Some(name) => self.profiles.get(name).cloned().ok_or_else(|| {
+                ClientError::Message(format!("Profile '{}' is not configured", name))
+            })?,
+            None => self
+                .profiles
+                .get(DEFAULT_PROFILE)
+                .cloned()
+                .unwrap_or_default(),
+        };
+        Ok(profile.with_env_overrides())
+    }
+
+    /// Builds an authenticated `ClientContext` for a profile.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let client_context = Config::load()?.client_context(None)?;
+    /// let me = client_context.call_route(&get_users_me(), None)?;
+    /// ```
+    pub fn client_context(&self, name: Option<&str>) -> Result<ClientContext, ClientError> {
+        self.profile(name)?.client_context()
+    }
+}
+
+impl Profile {
+    /// Replaces values with the ones from `ACTITIME_*` environment variables, when set.
+    pub fn with_env_overrides(self) -> Self {
+        self.with_overrides(env_var)
+    }
+
+    /// Sets the organization and drops the base URL, which would otherwise take precedence.
+    pub fn with_organization(mut self, organization: String) -> Self {
+        self.organization = Some(organization);
+        self.base_url = None;
+        self
+    }
+
+    /// Sets the `base_url` field and returns the modified profile.
+    pub fn with_base_url(mut self, base_url: String) -> Self {
+        self.base_url = Some(base_url);
+        self
+    }
+
+    /// Sets the `username` field and returns the modified profile.
+    pub fn with_username(mut self, username: String) -> Self {
+        self.username = Some(username);
+        self
+    }
+
+    fn with_overrides(mut self, variable: impl Fn(&str) -> Option<String>) -> Self {
+        if let Some(organization) = variable("ACTITIME_ORGANIZATION") {
+            self = self.with_organization(organization);
+        }
+        if let Some(base_url) = variable("ACTITIME_BASE_URL") {
+            self = self.with_base_url(base_url);
+        }
+        if let Some(username) = variable("ACTITIME_USERNAME") {
+            self = self.with_username(username);
+        }
+        if let Some(password) = variable("ACTITIME_PASSWORD") {
+            self.password = Some(PasswordSource::Literal(Secret::new(password)));
+        }
+        self
+    }
+
+    /// Builds an authenticated `ClientContext` from the profile.
+    ///
+    /// # Returns
+    /// The `ClientContext`, or a `ClientError::Message` naming the missing setting.
+    pub fn client_context(&self) -> Result<ClientContext, ClientError> {
+        let username = self
+            .username
+            .as_deref()
+            .ok_or_else(|| missing("username", "ACTITIME_USERNAME"))?;
+        let password = self
+            .password
+            .as_ref()
+            .ok_or_else(|| missing("password", "ACTITIME_PASSWORD"))?
+            .resolve()?;
+
+        let client_context = match (&self.base_url, &self.organization) {
+            (Some(base_url), organization) => {
+                let client_context = ClientContext::new(organization.clone().unwrap_or_default());
+                client_context.set_base_url(base_url);
+                client_context
+            }
+            (None, Some(organization)) => ClientContext::new(organization.clone()),
+            (None, None) => {
+                return Err(missing("organization or base_url", "ACTITIME_ORGANIZATION"));
+            }
+        };
+        client_context.set_basic_auth_token(username, password.expose_secret());
+        Ok(client_context)
+    }
+}
+
+impl PasswordSource {
+    /// Reads the password from its source.
+    pub fn resolve(&self) -> Result<Secret, ClientError> {
+        match self {
+            PasswordSource::Literal(password) => Ok(password.clone()),
+            PasswordSource::Env { env } => env_var(env).map(Secret::new).ok_or_else(|| {
+                ClientError::Message(format!("Environment variable {} is not set", env))
+            }),
+            PasswordSource::File { file } => fs::read_to_string(file)
+                .map(|contents| first_line(&Zeroizing::new(contents)))
+                .map_err(|e| {
+                    ClientError::Message(format!(
+                        "Cannot read password file {}: {}",
+                        file.display(),
+                        e
+                    ))
+                }),
+            PasswordSource::Command { command } => run_password_command(command),
+        }
+    }
+}
+
+/// Default location of the configuration file.
+pub fn default_path() -> Option<PathBuf> {
+    if let Some(path) = env_var("ACTITIME_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    let config_dir = if cfg!(windows) {
+        env_var("APPDATA").map(PathBuf::from)
+    } else {
+        env_var("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env_var("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+    config_dir.map(|dir| dir.join("actitime").join("config.toml"))
+}
+
+fn run_password_command(command: &str) -> Result<Secret, ClientError> {
+    let output = if cfg!(windows) {
+        Command::new("cmd").args(["/C", command]).output()
+    } else {
+        Command::new("sh").args(["-c", command]).output()
+    }
+    .map_err(|e| ClientError::Message(format!("Cannot run password command: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(ClientError::Message(format!(
+            "Password command exited with {}",
+            output.status
+        )));
+    }
+    let stdout = Zeroizing::new(output.stdout);
+    Ok(first_line(&String::from_utf8_lossy(&stdout)))
+}
+
+fn first_line(contents: &str) -> Secret {
+    Secret::from(contents.lines().next().unwrap_or_default())
+}
+
+fn env_var(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+fn missing(setting: &str, variable: &str) -> ClientError {
+    ClientError::Message(format!(
+        "No {} configured (set it in the profile or through {})",
+        setting, variable
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const CONFIG: &str = r#"
+        default_profile = "work"
+
+        [profiles.work]
+        organization = "acme"
+        username = "jdoe"
+        password = { env = "ACTITIME_TEST_UNSET_PASSWORD" }
+
+        [profiles.on-premise]
+        base_url = "https://actitime.example.com/api/v1"
+        username = "jdoe"
+        password = "hunter2"
+    "#;
+
+    fn overrides(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let variables: HashMap<String, String> = pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        move |name| variables.get(name).cloned()
+    }
+
+    #[test]
+    fn parses_profiles_and_password_sources() -> Result<(), ClientError> {
+        let config: Config = CONFIG.parse()?;
+        assert_eq!(config.default_profile.as_deref(), Some("work"));
+        let work = &config.profiles["work"];
+        assert_eq!(work.organization.as_deref(), Some("acme"));
+        assert_eq!(
+            work.password,
+            Some(PasswordSource::Env {
+                env: "ACTITIME_TEST_UNSET_PASSWORD".to_string()
+            })
+        );
+        let on_premise = &config.profiles["on-premise"];
+        assert_eq!(
+            on_premise.password,
+            Some(PasswordSource::Literal(Secret::from("hunter2")))
+        );
+        assert!("[profiles.work]\npassword = 42".parse::<Config>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn selects_profiles_by_name_then_default() -> Result<(), ClientError> {
+        let config: Config = CONFIG.parse()?;
+        let on_premise = config.profile(Some("on-premise"))?;
+        assert!(on_premise.base_url.is_some());
+        assert!(config.profile(Some("missing")).is_err());
+        let empty = Config::default().profile(None)?;
+        assert!(empty.organization.is_none() && empty.base_url.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn environment_organization_replaces_file_base_url() -> Result<(), ClientError> {
+        let config: Config = CONFIG.parse()?;
+        let on_premise = &config.profiles["on-premise"];
+
+        let profile = on_premise
+            .clone()
+            .with_overrides(overrides(&[("ACTITIME_ORGANIZATION", "globex")]));
+        assert_eq!(profile.organization.as_deref(), Some("globex"));
+        assert_eq!(profile.base_url, None);
+
+        let profile = on_premise.clone().with_overrides(overrides(&[
+            ("ACTITIME_ORGANIZATION", "globex"),
+            ("ACTITIME_BASE_URL", "https://other.example.com/api/v1"),
+            ("ACTITIME_USERNAME", "admin"),
+            ("ACTITIME_PASSWORD", "s3cret"),
+        ]));
+        assert_eq!(
+            profile.base_url.as_deref(),
+            Some("https://other.example.com/api/v1")
+        );
+        assert_eq!(profile.username.as_deref(), Some("admin"));
+        assert_eq!(
+            profile.password,
+            Some(PasswordSource::Literal(Secret::from("s3cret")))
+        );
+
+        let context = on_premise
+            .clone()
+            .with_organization("globex".to_string())
+            .client_context()?;
+        assert_eq!(context.organization(), "globex");
+        assert!(context.base_url().contains("globex"));
+        Ok(())
+    }
+
+    #[test]
+    fn requires_username_password_and_location() {
+        let profile = Profile {
+            username: Some("jdoe".to_string()),
+            password: Some(PasswordSource::Literal(Secret::from("hunter2"))),
+            ..Profile::default()
+        };
+        assert!(profile.client_context().is_err());
+        assert!(
+            profile
+                .clone()
+                .with_base_url("http://localhost/api/v1".to_string())
+                .client_context()
+                .is_ok()
+        );
+        let no_password = Profile {
+            password: None,
+            ..profile.with_organization("acme".to_string())
+        };
+        assert!(no_password.client_context().is_err());
+    }
+
+    #[test]
+    fn resolves_password_sources() -> Result<(), ClientError> {
+        let missing = PasswordSource::Env {
+            env: "ACTITIME_TEST_UNSET_PASSWORD".to_string(),
+        };
+        assert!(missing.resolve().is_err());
+
+        let path = env::temp_dir().join(format!("actitime-password-{}", std::process::id()));
+        fs::write(&path, "from-file\nignored\n").map_err(|e| ClientError::Other(Box::new(e)))?;
+        let from_file = PasswordSource::File { file: path.clone() }.resolve();
+        let _ = fs::remove_file(&path);
+        assert_eq!(from_file?.expose_secret(), "from-file");
+
+        if cfg!(unix) {
+            let command = PasswordSource::Command {
+                command: "printf 'from-command\\nignored'".to_string(),
+            };
+            assert_eq!(command.resolve()?.expose_secret(), "from-command");
+            let failing = PasswordSource::Command {
+                command: "exit 3".to_string(),
+            };
+            assert!(failing.resolve().is_err());
+        }
+        Ok(())
+    }
+}
@@ -10,10 +10,15 @@ pub enum ClientError {
     Unauthorized,
     BadRequest,
     Timeout,
+    /// The resource changed since it was read: the server answered `412 Precondition Failed`
+    /// to a request sent with `If-Match`
+    Conflict,
     ApiError(u16, ApiError),
     Message(String),
     Reqwest(reqwest::Error),
     SerdeJson(serde_json::Error),
+    /// A response body that does not match the expected payload
+    Decode(DecodeError),
     Other(Box<dyn StdError + Send + Sync>),
 }
 
@@ -24,12 +29,14 @@ impl fmt::Display for ClientError {
             ClientError::Unauthorized => write!(f, "Unauthorized access"),
             ClientError::BadRequest => write!(f, "Bad request"),
             ClientError::Timeout => write!(f, "Operation timed out"),
+            ClientError::Conflict => write!(f, "Resource was modified by someone else"),
             ClientError::ApiError(status_code, api_error) => {
                 write!(f, "API Error ({}): {:?}", status_code, api_error)
             }
             ClientError::Message(msg) => write!(f, "Error: {}", msg),
             ClientError::Reqwest(e) => write!(f, "Reqwest error: {}", e),
             ClientError::SerdeJson(e) => write!(f, "Serde JSON error: {}", e),
+            ClientError::Decode(e) => write!(f, "Decode error: {}", e),
             ClientError::Other(e) => write!(f, "Other error: {}", e),
         }
     }
@@ -39,8 +46,110 @@ impl StdError for ClientError {
     fn source(&self) -> Option<&(dyn StdError + 'static)> {
         match self {
             ClientError::Reqwest(e) => Some(e),
+            ClientError::Decode(e) => Some(e),
             ClientError::Other(e) => Some(&**e),
             _ => None,
         }
     }
 }
+
+/// Maximum number of characters of the raw response kept in `DecodeError::snippet`
+const SNIPPET_LENGTH: usize = 160;
+
+/// Failure to decode a response body, with the location of the offending value
+#[derive(Debug)]
+pub struct DecodeError {
+    /// Path of the value that failed to decode, e.g. `items[37].deadline`, or `.` for the root
+    pub path: String,
+    /// Underlying JSON error, including the line and column
+    pub source: serde_json::Error,
+    /// Raw response around the error position, truncated to a few lines
+    pub snippet: String,
+    /// Whole response body, when `ClientContext::set_attach_body_to_decode_errors` is enabled
+    pub body: Option<String>,
+}
+
+impl DecodeError {
+    /// Decodes `body` as JSON, reporting the path of the offending value on failure.
+    ///
+    /// # Arguments
+    /// * `body` - The raw response body.
+    /// * `attach_body` - Whether the whole body is kept in the error.
+    pub fn decode<T: serde::de::DeserializeOwned>(
+        body: &[u8],
+        attach_body: bool,
+    ) -> Result<T, DecodeError> {
+        let deserializer = &mut serde_json::Deserializer::from_slice(body);
+        serde_path_to_error::deserialize(deserializer).map_err(|error| {
+            let path = error.path().to_string();
+            let source = error.into_inner();
+            let text = String::from_utf8_lossy(body);
+            DecodeError {
+                path,
+                snippet: snippet(&text, source.line(), source.column()),
+                body: attach_body.then(|| text.into_owned()),
+                source,
+            }
+        })
+    }
+
+    /// Decodes an already parsed JSON value, prefixing the path of the offending value with
+    /// `path_prefix`, e.g. `items[37]` for a list item.
+    pub(crate) fn decode_value<T: serde::de::DeserializeOwned>(
+        value: &serde_json::Value,
+        path_prefix: &str,
+    ) -> Result<T, DecodeError> {
+        serde_path_to_error::deserialize(value).map_err(|error| {
+            let path = match error.path().to_string() {
+                path if path_prefix.is_empty() => path,
+                path if path == "." => path_prefix.to_string(),
+                path if path.starts_with('[') => format!("{}{}", path_prefix, path),
+                path => format!("{}.{}", path_prefix, path),
+            };
+            DecodeError {
+                path,
+                snippet: snippet(&value.to_string(), 1, 1),
+                body: None,
+                source: error.into_inner(),
+            }
+        })
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} near `{}`", self.path, self.source, self.snippet)
+    }
+}
+
+impl StdError for DecodeError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Part of `text` around a 1-based line and column, truncated to `SNIPPET_LENGTH` characters.
+fn snippet(text: &str, line: usize, column: usize) -> String {
+    let offset = text
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(|line| line.chars().count())
+        .sum::<usize>()
+        + column.saturating_sub(1);
+    let start = offset.saturating_sub(SNIPPET_LENGTH / 2);
+    let length = text.chars().count();
+    let end = (start + SNIPPET_LENGTH).min(length);
+
+    let mut snippet = text
+        .chars()
+        .skip(start)
+        .take(end - start)
+        .collect::<String>();
+    if start > 0 {
+        snippet.insert_str(0, "...");
+    }
+    if end < length {
+        snippet.push_str("...");
+    }
+    snippet
+}
@@ -0,0 +1,500 @@
+//! Estimate vs actual analysis of tasks, rolled up per project and customer.
+//!
+//! Each task's `estimated_time` is compared with the time tracked against it up to a given day.
+//! Tasks are flagged when their tracked time exceeds the estimate, or when they are still open
+//! after their deadline.
+//!
+//! ```ignore
+//! let report = EstimateAnalysis::new(today)
+//!     .run(&client_context, GetTasksParameters::new().with_project_ids(vec![7]))?;
+//! for task in report.flagged() {
+//!     println!("{}: {}", task.task, task.flags.len());
+//! }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::client_context::{ClientContext, PAGE_SIZE};
+use crate::errors::ClientError;
+use crate::payload::{Task, WorkDuration, enums::TaskStatus};
+use crate::routes::{GetTasksParameters, GetTimeTrackParameters};
+
+/// Reason a task is flagged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EstimateFlag {
+    /// More time is tracked than estimated
+    OverEstimate,
+    /// The task is still open after its deadline
+    PastDeadline,
+}
+
+impl fmt::Display for EstimateFlag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EstimateFlag::OverEstimate => write!(f, "over estimate"),
+            EstimateFlag::PastDeadline => write!(f, "past deadline"),
+        }
+    }
+}
+
+/// Estimate and tracked time of one task
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskEstimate {
+    /// Identifier of the task
+    pub task_id: i32,
+    /// Name of the task
+    pub task: String,
+    /// Status of the task
+    pub status: Option<TaskStatus>,
+    /// Estimated time of the task
+    pub estimated_time: Option<WorkDuration>,
+    /// Time tracked against the task up to the analysis day
+    pub tracked_time: WorkDuration,
+    /// Deadline of the task
+    pub deadline: Option<NaiveDate>,
+    /// Reasons the task is flagged, empty if it is on track
+    pub flags: Vec<EstimateFlag>,
+}
+
+impl TaskEstimate {
+    /// Returns the time tracked beyond the estimate, or `None` without an estimate.
+    pub fn overrun(&self) -> Option<WorkDuration> {
+        self.estimated_time
+            .map(|estimate| (self.tracked_time - estimate).max(WorkDuration::default()))
+    }
+
+    /// Returns the estimate left after the tracked time, or `None` without an estimate.
+    pub fn remaining(&self) -> Option<WorkDuration> {
+        self.estimated_time
+            .map(|estimate| (estimate - self.tracked_time).max(WorkDuration::default()))
+    }
+
+    /// Returns the tracked time as a percentage of a non-zero estimate.
+    pub fn used_percent(&self) -> Option<f64> {
+        self.estimated_time
+            .filter(|estimate| *estimate > WorkDuration::default())
+            .map(|estimate| self.tracked_time.hours() / estimate.hours() * 100.0)
+    }
+
+    /// Returns `true` if the task has a flag.
+    pub fn is_flagged(&self) -> bool {
+        !self.flags.is_empty()
+    }
+}
+
+/// Estimates and tracked time summed over several tasks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct EstimateTotals {
+    /// Number of tasks
+    pub tasks: usize,
+    /// Number of tasks with an estimate
+    pub estimated_tasks: usize,
+    /// Sum of the estimates
+    pub estimated_time: WorkDuration,
+    /// Time tracked against all tasks
+    pub tracked_time: WorkDuration,
+    /// Time tracked against tasks with an estimate, to compare with `estimated_time`
+    pub tracked_estimated_time: WorkDuration,
+    /// Sum of the time tracked beyond each estimate
+    pub overrun_time: WorkDuration,
+    /// Number of tasks over estimate
+    pub over_estimate: usize,
+    /// Number of open tasks past their deadline
+    pub past_deadline: usize,
+}
+
+impl EstimateTotals {
+    fn add(&mut self, task: &TaskEstimate) {
+        self.tasks += 1;
+        self.tracked_time += task.tracked_time;
+        if let Some(estimate) = task.estimated_time {
+            self.estimated_tasks += 1;
+            self.estimated_time += estimate;
+            self.tracked_estimated_time += task.tracked_time;
+        }
+        self.overrun_time += task.overrun().unwrap_or_default();
+        for flag in &task.flags {
+            match flag {
+                EstimateFlag::OverEstimate => self.over_estimate += 1,
+                EstimateFlag::PastDeadline => self.past_deadline += 1,
+            }
+        }
+    }
+}
+
+/// Tasks of one project
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectEstimate {
+    /// Identifier of the project
+    pub project_id: Option<i32>,
+    /// Name of the project
+    pub project: String,
+    /// Totals of the project
+    pub totals: EstimateTotals,
+    /// Tasks of the project, by name
+    pub tasks: Vec<TaskEstimate>,
+}
+
+/// Projects of one customer
+#[derive(Debug, Clone, Serialize)]
+pub struct CustomerEstimate {
+    /// Identifier of the customer
+    pub customer_id: Option<i32>,
+    /// Name of the customer
+    pub customer: String,
+    /// Totals of the customer
+    pub totals: EstimateTotals,
+    /// Projects of the customer, by name
+    pub projects: Vec<ProjectEstimate>,
+}
+
+/// Estimate vs actual of tasks per customer and project
+#[derive(Debug, Clone, Serialize)]
+pub struct EstimateReport {
+    /// First day of tracked time taken into account
+    pub date_from: NaiveDate,
+    /// Day of the analysis, the last day of tracked time taken into account
+    pub as_of: NaiveDate,
+    /// Totals of all tasks
+    pub totals: EstimateTotals,
+    /// Customers, by name
+    pub customers: Vec<CustomerEstimate>,
+}
+
+impl EstimateReport {
+    /// Returns the tasks of the report with their customer and project.
+    pub fn tasks(
+        &self,
+    ) -> impl Iterator<Item = (&CustomerEstimate, &ProjectEstimate, &TaskEstimate)> + '_ {
+        self.customers.iter().flat_map(|customer| {
+            customer.projects.iter().flat_map(move |project| {
+                project
+                    .tasks
+                    .iter()
+                    .map(move |task| (customer, project, task))
+            })
+        })
+    }
+
+    /// Returns the tasks that are over estimate or past their deadline.
+    pub fn flagged(&self) -> Vec<&TaskEstimate> {
+        self.tasks()
+            .map(|(_, _, task)| task)
+            .filter(|task| task.is_flagged())
+            .collect()
+    }
+}
+
+/// Comparison of task estimates with tracked time
+#[derive(Debug, Clone)]
+pub struct EstimateAnalysis {
+    /// Day of the analysis: time tracked after it is ignored and deadlines before it are past
+    pub as_of: NaiveDate,
+    /// First day of tracked time to take into account; defaults to the earliest creation date
+    /// of the tasks, tasks without one counting from `as_of`
+    pub since: Option<NaiveDate>,
+}
+
+impl EstimateAnalysis {
+    /// Creates an analysis as of `as_of`.
+    pub fn new(as_of: NaiveDate) -> Self {
+        Self { as_of, since: None }
+    }
+
+    /// Sets the `since` field and returns the modified analysis.
+    pub fn with_since(mut self, since: NaiveDate) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    /// Fetches the tasks matching `parameters` and the time tracked against them.
+    ///
+    /// # Arguments
+    /// * `client_context` - The client context used to fetch the tasks and time-track.
+    /// * `parameters` - The task filters; paging fields are ignored.
+    ///
+    /// # Returns
+    /// The report, or a `ClientError::Message` without calling the API if `since` is after
+    /// `as_of`.
+    pub fn run(
+        &self,
+        client_context: &ClientContext,
+        parameters: GetTasksParameters,
+    ) -> Result<EstimateReport, ClientError> {
+        if let Some(since) = self.since.filter(|since| *since > self.as_of) {
+            return Err(ClientError::Message(format!(
+                "Empty analysis period: {} is after {}",
+                since, self.as_of
+            )));
+        }
+        let tasks = client_context.get_all_tasks(parameters)?;
+        let date_from = self.since.unwrap_or_else(|| self.earliest_creation(&tasks));
+
+        let task_ids: Vec<i32> = tasks.iter().filter_map(|task| task.id).collect();
+        let mut tracked: HashMap<i32, WorkDuration> = HashMap::new();
+        for chunk in task_ids.chunks(PAGE_SIZE as usize) {
+            let parameters = GetTimeTrackParameters::new()
+                .with_dates(date_from..=self.as_of)
+                .with_task_ids(chunk.to_vec());
+            for entry in client_context
+                .get_complete_time_track(parameters)?
+                .entries()
+            {
+                *tracked.entry(entry.record.task_id).or_default() += entry.record.time;
+            }
+        }
+
+        Ok(self.report(&tasks, &tracked, date_from))
+    }
+
+    /// Returns the earliest creation date of the tasks, or `as_of` if it is earlier.
+    fn earliest_creation(&self, tasks: &[Task]) -> NaiveDate {
+        tasks
+            .iter()
+            .filter_map(|task| task.created)
+            .fold(self.as_of, NaiveDate::min)
+    }
+
+    /// Builds the report from tasks and the time tracked against each of them.
+    ///
+    /// # Arguments
+    /// * `tasks` - The tasks to analyse; tasks without an identifier are skipped.
+    /// * `tracked` - The time tracked per task identifier.
+    /// * `date_from` - The first day of tracked time in `tracked`.
+    pub fn report(
+        &self,
+        tasks: &[Task],
+        tracked: &HashMap<i32, WorkDuration>,
+        date_from: NaiveDate,
+    ) -> EstimateReport {
+        // Keyed by names first so that customers, projects and tasks come out sorted by name
+        type Key = (String, Option<i32>, String, Option<i32>, String, i32);
+        let mut sorted: BTreeMap<Key, (String, String, TaskEstimate)> = BTreeMap::new();
+        for task in tasks {
+            let Some(task_id) = task.id else {
+                continue;
+            };
+            let name = |name: &Option<String>, id: Option<i32>| match (name, id) {
+                (Some(name), _) => name.clone(),
+                (None, Some(id)) => format!("#{}", id),
+                (None, None) => String::new(),
+            };
+            let customer = name(&task.customer_name, task.customer_id);
+            let project = name(&task.project_name, task.project_id);
+            let estimate = self.task_estimate(task, task_id, tracked);
+            let key = (
+                customer.to_lowercase(),
+                task.customer_id,
+                project.to_lowercase(),
+                task.project_id,
+                task.name.to_lowercase(),
+                task_id,
+            );
+            sorted.insert(key, (customer, project, estimate));
+        }
+
+        let mut report = EstimateReport {
+            date_from,
+            as_of: self.as_of,
+            totals: EstimateTotals::default(),
+            customers: vec![],
+        };
+        for ((_, customer_id, _, project_id, _, _), (customer, project, task)) in sorted {
+            report.totals.add(&task);
+
+            if report
+                .customers
+                .last()
+                .is_none_or(|c| c.customer_id != customer_id || c.customer != customer)
+            {
+                report.customers.push(CustomerEstimate {
+                    customer_id,
+                    customer,
+                    totals: EstimateTotals::default(),
+                    projects: vec![],
+                });
+            }
+            let Some(customer) = report.customers.last_mut() else {
+                continue;
+            };
+            customer.totals.add(&task);
+
+            if customer
+                .projects
+                .last()
+                .is_none_or(|p| p.project_id != project_id || p.project != project)
+            {
+                customer.projects.push(ProjectEstimate {
+                    project_id,
+                    project,
+                    totals: EstimateTotals::default(),
+                    tasks: vec![],
+                });
+            }
+            let Some(project) = customer.projects.last_mut() else {
+                continue;
+            };
+            project.totals.add(&task);
+            project.tasks.push(task);
+        }
+        report
+    }
+
+    fn task_estimate(
+        &self,
+        task: &Task,
+        task_id: i32,
+        tracked: &HashMap<i32, WorkDuration>,
+    ) -> TaskEstimate {
+        let tracked_time = tracked.get(&task_id).copied().unwrap_or_default();
+        let mut flags = vec![];
+        if task
+            .estimated_time
+            .is_some_and(|estimate| tracked_time > estimate)
+        {
+            flags.push(EstimateFlag::OverEstimate);
+        }
+        let open = task.status == Some(TaskStatus::Open);
+        if open && task.deadline.is_some_and(|deadline| deadline < self.as_of) {
+            flags.push(EstimateFlag::PastDeadline);
+        }
+        TaskEstimate {
+            task_id,
+            task: task.name.clone(),
+            status: task.status.clone(),
+            estimated_time: task.estimated_time,
+            tracked_time,
+            deadline: task.deadline,
+            flags,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> Result<NaiveDate, ClientError> {
+        NaiveDate::from_ymd_opt(2024, month, day)
+            .ok_or_else(|| ClientError::Message(format!("Invalid date 2024-{}-{}", month, day)))
+    }
+
+    fn task(id: i32, project_id: i32, project: &str, status: TaskStatus) -> Task {
+        Task::new(format!("Task {}", id))
+            .with_id(id)
+            .with_status(status)
+            .with_customer_id(1)
+            .with_customer_name("Acme".to_string())
+            .with_project_id(project_id)
+            .with_project_name(project.to_string())
+    }
+
+    #[test]
+    fn flags_overrun_and_past_deadline() -> Result<(), ClientError> {
+        let tasks = vec![
+            task(1, 10, "Website", TaskStatus::Open)
+                .with_estimated_time(WorkDuration::from_hours(2))
+                .with_deadline(date(3, 1)?),
+            task(2, 10, "Website", TaskStatus::Open)
+                .with_estimated_time(WorkDuration::from_hours(4))
+                .with_deadline(date(12, 1)?),
+            task(3, 20, "App", TaskStatus::Completed).with_deadline(date(1, 1)?),
+            task(4, 20, "App", TaskStatus::Open),
+        ];
+        let tracked = HashMap::from([
+            (1, WorkDuration::from_minutes(150)),
+            (2, WorkDuration::from_minutes(60)),
+            (3, WorkDuration::from_minutes(30)),
+        ]);
+        let report = EstimateAnalysis::new(date(3, 31)?).report(&tasks, &tracked, date(1, 1)?);
+
+        let flags: Vec<(i32, Vec<EstimateFlag>)> = report
+            .tasks()
+            .map(|(_, _, task)| (task.task_id, task.flags.clone()))
+            .collect();
+        assert_eq!(
+            flags,
+            vec![
+                (3, vec![]),
+                (4, vec![]),
+                (
+                    1,
+                    vec![EstimateFlag::OverEstimate, EstimateFlag::PastDeadline]
+                ),
+                (2, vec![]),
+            ]
+        );
+        let overrun = report.flagged().first().and_then(|task| task.overrun());
+        assert_eq!(overrun, Some(WorkDuration::from_minutes(30)));
+        Ok(())
+    }
+
+    #[test]
+    fn rolls_up_to_projects_and_customers() -> Result<(), ClientError> {
+        let tasks = vec![
+            task(1, 10, "Website", TaskStatus::Open)
+                .with_estimated_time(WorkDuration::from_hours(2)),
+            task(2, 10, "Website", TaskStatus::Open)
+                .with_estimated_time(WorkDuration::from_hours(1)),
+            task(3, 20, "App", TaskStatus::Open),
+        ];
+        let tracked = HashMap::from([
+            (1, WorkDuration::from_minutes(180)),
+            (2, WorkDuration::from_minutes(30)),
+            (3, WorkDuration::from_minutes(45)),
+        ]);
+        let report = EstimateAnalysis::new(date(3, 31)?).report(&tasks, &tracked, date(1, 1)?);
+
+        assert_eq!(report.customers.len(), 1);
+        let projects: Vec<(&str, usize)> = report.customers[0]
+            .projects
+            .iter()
+            .map(|project| (project.project.as_str(), project.totals.tasks))
+            .collect();
+        assert_eq!(projects, [("App", 1), ("Website", 2)]);
+
+        let totals = report.totals;
+        assert_eq!(totals.tasks, 3);
+        assert_eq!(totals.estimated_tasks, 2);
+        assert_eq!(totals.estimated_time, WorkDuration::from_minutes(180));
+        assert_eq!(totals.tracked_time, WorkDuration::from_minutes(255));
+        assert_eq!(
+            totals.tracked_estimated_time,
+            WorkDuration::from_minutes(210)
+        );
+        assert_eq!(totals.overrun_time, WorkDuration::from_minutes(60));
+        assert_eq!(totals.over_estimate, 1);
+        assert_eq!(report.customers[0].totals, totals);
+        Ok(())
+    }
+
+    #[test]
+    fn starts_at_earliest_creation_date() -> Result<(), ClientError> {
+        let analysis = EstimateAnalysis::new(date(3, 31)?);
+        let tasks = vec![
+            task(1, 10, "Website", TaskStatus::Open).with_created(date(2, 1)?),
+            task(2, 10, "Website", TaskStatus::Open).with_created(date(1, 15)?),
+            task(3, 10, "Website", TaskStatus::Open),
+        ];
+        assert_eq!(analysis.earliest_creation(&tasks), date(1, 15)?);
+        assert_eq!(analysis.earliest_creation(&tasks[2..]), date(3, 31)?);
+        Ok(())
+    }
+
+    #[test]
+    fn refuses_since_after_as_of() -> Result<(), ClientError> {
+        let analysis = EstimateAnalysis::new(date(3, 31)?).with_since(date(4, 1)?);
+        let client_context = ClientContext::new("acme".to_string());
+        let result = analysis.run(&client_context, GetTasksParameters::new());
+        assert!(matches!(
+            result,
+            Err(ClientError::Message(message)) if message.contains("2024-04-01 is after 2024-03-31")
+        ));
+        Ok(())
+    }
+}
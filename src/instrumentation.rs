@@ -0,0 +1,100 @@
+//! Optional `tracing` instrumentation of route calls.
+//!
+//! Without the `tracing` feature nothing is recorded. Spans only carry the route template and
+//! never the URL, headers or bodies, so neither names nor credentials are recorded.
+
+use reqwest::StatusCode;
+
+use crate::errors::{ClientError, DecodeError};
+use crate::payload::enums::Method;
+use crate::payload::{Lenient, ListPayload};
+
+/// Span covering one `call_route`, from the first attempt to the decoded response
+pub(crate) struct CallSpan {
+    #[cfg(feature = "tracing")]
+    span: tracing::span::EnteredSpan,
+    #[cfg(feature = "tracing")]
+    started: std::time::Instant,
+}
+
+impl CallSpan {
+    /// Opens and enters the span of a call.
+    #[cfg(feature = "tracing")]
+    pub(crate) fn enter(organization: &str, method: &Method, template: &str) -> Self {
+        let span = tracing::info_span!(
+            "actitime.call_route",
+            organization = organization,
+            method = %method,
+            route = template,
+            status = tracing::field::Empty,
+            duration_ms = tracing::field::Empty,
+            retries = tracing::field::Empty,
+        );
+        CallSpan {
+            span: span.entered(),
+            started: std::time::Instant::now(),
+        }
+    }
+
+    /// Opens and enters the span of a call.
+    #[cfg(not(feature = "tracing"))]
+    pub(crate) fn enter(_organization: &str, _method: &Method, _template: &str) -> Self {
+        CallSpan {}
+    }
+
+    /// Records the final status of the call and how many times it was retried.
+    pub(crate) fn response(&self, _status: StatusCode, _retries: u32) {
+        #[cfg(feature = "tracing")]
+        {
+            self.span.record("status", _status.as_u16());
+            self.span.record("retries", _retries);
+            self.span
+                .record("duration_ms", self.started.elapsed().as_millis() as u64);
+        }
+    }
+
+    /// Logs a call that failed before a response was received.
+    pub(crate) fn failed(&self, _error: &ClientError) {
+        #[cfg(feature = "tracing")]
+        {
+            self.span
+                .record("duration_ms", self.started.elapsed().as_millis() as u64);
+            tracing::warn!(error = %_error, "API call failed");
+        }
+    }
+}
+
+/// Decodes a JSON response body, logging the path of the offending value on failure.
+pub(crate) fn decode_response<T: serde::de::DeserializeOwned>(
+    body: &[u8],
+    attach_body: bool,
+) -> Result<T, ClientError> {
+    DecodeError::decode(body, attach_body).map_err(|error| {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(
+            path = %error.path,
+            error = %error.source,
+            snippet = %error.snippet,
+            "Failed to decode response"
+        );
+        ClientError::Decode(error)
+    })
+}
+
+/// Decodes a list response body in lenient mode, logging every skipped item.
+pub(crate) fn decode_lenient_response<T: ListPayload>(
+    body: &[u8],
+    attach_body: bool,
+) -> Result<Lenient<T>, ClientError> {
+    let lenient = T::decode_lenient(body, attach_body)?;
+    #[cfg(feature = "tracing")]
+    for error in &lenient.errors {
+        tracing::warn!(
+            index = error.index,
+            path = %error.error.path,
+            error = %error.error.source,
+            "Skipped list item that failed to decode"
+        );
+    }
+    Ok(lenient)
+}
@@ -1,6 +1,23 @@
 pub mod actitime_client;
+pub mod auth;
+pub mod billing;
 pub mod client_context;
+pub mod conditional_cache;
+#[cfg(feature = "config")]
+pub mod config;
 mod date_formatter;
 pub mod errors;
+pub mod estimates;
+mod instrumentation;
+pub mod middleware;
 pub mod payload;
+pub mod reference_cache;
 pub mod routes;
+pub mod secret;
+pub mod snapshot;
+pub mod structure;
+#[cfg(feature = "csv")]
+pub mod task_import;
+pub mod time_track_export;
+#[cfg(feature = "csv")]
+pub mod time_track_import;
@@ -0,0 +1,241 @@
+//! Hooks running around every request sent by a `ClientContext`.
+//!
+//! A middleware can change a request before it is sent (method, URL, headers and body) and
+//! inspect or replace the response before it is decoded. Middlewares run as an onion: the
+//! `before_request` hooks in chain order, the `after_response` hooks in reverse order.
+//!
+//! ``` ignore
+//! client_context.add_middleware(BeforeRequestHook(|request: &mut ApiRequest| {
+//!     request.set_header("X-Correlation-Id", &Uuid::new_v4().to_string())
+//! }));
+//! ```
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use reqwest::blocking::Client as HttpClient;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+use crate::auth::{AuthContext, Authenticator};
+use crate::errors::ClientError;
+use crate::payload::enums::Method;
+
+/// Request about to be sent, as seen by the middlewares
+#[derive(Debug, Clone)]
+pub struct ApiRequest {
+    /// HTTP method
+    pub method: Method,
+    /// Full URL, including the query string
+    pub url: String,
+    /// Headers added to the defaults of the HTTP client
+    pub headers: HeaderMap,
+    /// Serialized JSON payload, if any
+    pub body: Option<Vec<u8>>,
+}
+
+/// Response received for a request, as seen by the middlewares
+#[derive(Debug, Clone)]
+pub struct ApiResponse {
+    /// HTTP status
+    pub status: StatusCode,
+    /// Response headers
+    pub headers: HeaderMap,
+    /// Raw response body
+    pub body: Vec<u8>,
+    /// Time between sending the request and receiving the whole body
+    pub elapsed: Duration,
+}
+
+/// Information about the call a hook runs for
+pub struct CallContext<'a> {
+    /// Organization of the `ClientContext`
+    pub organization: &'a str,
+    /// API base URL
+    pub base_url: &'a str,
+    /// HTTP client the request is sent with
+    pub http_client: &'a HttpClient,
+    /// Authenticator of the `ClientContext`, if one is set
+    pub authenticator: Option<&'a dyn Authenticator>,
+    /// Number of times the request was already sent for this call (0 on the first attempt)
+    pub attempt: u32,
+}
+
+/// What the chain does after a hook ran
+#[derive(Debug)]
+pub enum Flow {
+    /// Run the next hook
+    Continue,
+    /// Stop here and use this response.
+    ///
+    /// Returned by `before_request`, the request is not sent; returned by `after_response`,
+    /// the remaining hooks are skipped.
+    Respond(ApiResponse),
+    /// Start the call over from the first `before_request` hook.
+    ///
+    /// Only meaningful in `after_response`; `before_request` treats it as `Continue`.
+    Retry,
+}
+
+/// Hook pair running around every request
+///
+/// Both hooks default to doing nothing. Returning an error aborts the call with that error.
+pub trait Middleware: Send + Sync {
+    /// Runs before the request is sent and may change it.
+    fn before_request(
+        &self,
+        _context: &CallContext<'_>,
+        _request: &mut ApiRequest,
+    ) -> Result<Flow, ClientError> {
+        Ok(Flow::Continue)
+    }
+
+    /// Runs after the response is received and may inspect or change it.
+    fn after_response(
+        &self,
+        _context: &CallContext<'_>,
+        _request: &ApiRequest,
+        _response: &mut ApiResponse,
+    ) -> Result<Flow, ClientError> {
+        Ok(Flow::Continue)
+    }
+}
+
+/// Middleware running a closure before every request
+///
+/// # Example
+/// ``` ignore
+/// client_context.add_middleware(BeforeRequestHook(|request: &mut ApiRequest| {
+///     request.set_header("X-Gateway-Key", &gateway_key)
+/// }));
+/// ```
+pub struct BeforeRequestHook<F>(pub F);
+
+impl<F> Middleware for BeforeRequestHook<F>
+where
+    F: Fn(&mut ApiRequest) -> Result<(), ClientError> + Send + Sync,
+{
+    fn before_request(
+        &self,
+        _context: &CallContext<'_>,
+        request: &mut ApiRequest,
+    ) -> Result<Flow, ClientError> {
+        (self.0)(request).map(|_| Flow::Continue)
+    }
+}
+
+/// Middleware running a closure after every response
+///
+/// # Example
+/// ``` ignore
+/// client_context.add_middleware(AfterResponseHook(|request: &ApiRequest, response: &ApiResponse| {
+///     metrics.record(&request.url, response.status, response.elapsed);
+///     Ok(())
+/// }));
+/// ```
+pub struct AfterResponseHook<F>(pub F);
+
+impl<F> Middleware for AfterResponseHook<F>
+where
+    F: Fn(&ApiRequest, &ApiResponse) -> Result<(), ClientError> + Send + Sync,
+{
+    fn after_response(
+        &self,
+        _context: &CallContext<'_>,
+        request: &ApiRequest,
+        response: &mut ApiResponse,
+    ) -> Result<Flow, ClientError> {
+        (self.0)(request, response).map(|_| Flow::Continue)
+    }
+}
+
+/// Signs requests with the authenticator of the `ClientContext` and, when the server answers
+/// `401 Unauthorized`, lets it refresh the credentials and retries once
+///
+/// Fails with `ClientError::Unauthorized` when no authenticator is set.
+pub struct Authentication;
+
+impl Middleware for Authentication {
+    fn before_request(
+        &self,
+        context: &CallContext<'_>,
+        request: &mut ApiRequest,
+    ) -> Result<Flow, ClientError> {
+        let authenticator = context.authenticator.ok_or(ClientError::Unauthorized)?;
+        authenticator.authenticate(&context.auth_context(), request)?;
+        Ok(Flow::Continue)
+    }
+
+    fn after_response(
+        &self,
+        context: &CallContext<'_>,
+        _request: &ApiRequest,
+        response: &mut ApiResponse,
+    ) -> Result<Flow, ClientError> {
+        if response.status != StatusCode::UNAUTHORIZED || context.attempt > 0 {
+            return Ok(Flow::Continue);
+        }
+        match context.authenticator {
+            Some(authenticator) if authenticator.reauthenticate(&context.auth_context())? => {
+                Ok(Flow::Retry)
+            }
+            _ => Ok(Flow::Continue),
+        }
+    }
+}
+
+/// The chain every new `ClientContext` starts with: `Authentication`.
+pub fn default_chain() -> Vec<Arc<dyn Middleware>> {
+    vec![Arc::new(Authentication)]
+}
+
+impl ApiRequest {
+    /// Creates a request without headers or body.
+    pub fn new(method: Method, url: &str) -> Self {
+        ApiRequest {
+            method,
+            url: url.to_string(),
+            headers: HeaderMap::new(),
+            body: None,
+        }
+    }
+
+    /// Sets a header, replacing any previous value.
+    ///
+    /// # Returns
+    /// An error if `name` or `value` is not a valid header.
+    pub fn set_header(&mut self, name: &str, value: &str) -> Result<(), ClientError> {
+        let name =
+            HeaderName::from_bytes(name.as_bytes()).map_err(|e| ClientError::Other(Box::new(e)))?;
+        let value = HeaderValue::from_str(value).map_err(|e| ClientError::Other(Box::new(e)))?;
+        self.headers.insert(name, value);
+        Ok(())
+    }
+}
+
+impl ApiResponse {
+    /// Creates a response without headers, e.g. to short-circuit a call from a hook.
+    pub fn new(status: StatusCode, body: Vec<u8>) -> Self {
+        ApiResponse {
+            status,
+            headers: HeaderMap::new(),
+            body,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Returns the body as text, replacing invalid UTF-8.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+impl CallContext<'_> {
+    /// Returns the context passed to the authenticator.
+    pub fn auth_context(&self) -> AuthContext<'_> {
+        AuthContext {
+            http_client: self.http_client,
+            base_url: self.base_url,
+        }
+    }
+}
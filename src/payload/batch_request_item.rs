@@ -1,4 +1,4 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::marker::PhantomData;
 
 use crate::payload::PayloadTrait;
@@ -31,6 +31,34 @@ where
     _response_type: PhantomData<ResponseType>,
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawBatchRequestItem<BodyType> {
+    id: Option<String>,
+    method: Method,
+    relative_url: String,
+    include_response_body: Option<bool>,
+    body: Option<BodyType>,
+}
+
+impl<'de, BodyType, ResponseType> Deserialize<'de> for BatchRequestItem<BodyType, ResponseType>
+where
+    BodyType: Serialize + for<'a> Deserialize<'a>,
+    ResponseType: Serialize + for<'a> Deserialize<'a>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawBatchRequestItem::<BodyType>::deserialize(deserializer)?;
+        Ok(Self {
+            id: raw.id,
+            method: raw.method,
+            relative_url: raw.relative_url,
+            include_response_body: raw.include_response_body,
+            body: raw.body,
+            _response_type: PhantomData,
+        })
+    }
+}
+
 // impl<BodyType, ResponseType> PayloadTrait for BatchRequestItem<BodyType, ResponseType>
 // where
 //     BodyType: Serialize + for<'de> Deserialize<'de>,
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::payload::ExtraFields;
+
+/// Outcome of one request of a batch, as returned by `post_batch_requests`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchResultItem {
+    /// Identifier given to the request with `BatchRequestItem::with_id`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// HTTP status of the request
+    pub status: u16,
+    /// Response body, present depending on `includeResponseBody` and on errors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub body: Option<Value>,
+    /// Fields returned by the API that are not modelled above, sent back unchanged
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+impl BatchResultItem {
+    /// Returns `true` when the request succeeded.
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Returns the error message of a failed request, falling back to its status.
+    pub fn error_message(&self) -> Option<String> {
+        if self.is_success() {
+            return None;
+        }
+        let message = self
+            .body
+            .as_ref()
+            .and_then(|body| body.get("message"))
+            .and_then(Value::as_str);
+        Some(match message {
+            Some(message) => format!("{} (status {})", message, self.status),
+            None => format!("Request failed with status {}", self.status),
+        })
+    }
+}
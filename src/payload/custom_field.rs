@@ -0,0 +1,326 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::errors::ClientError;
+use crate::payload::{
+    Customer, ExtraFields, Project, Task, TaskWithAllowedActions,
+    enums::{CustomFieldEntity, CustomFieldType},
+};
+
+/// Wire format of custom field dates
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Custom field defined by an administrator for tasks, projects or customers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomFieldDefinition {
+    /// Unique custom field identifier (read-only)
+    pub id: i32,
+    /// Name of the custom field
+    pub name: String,
+    /// Type of the values of the field
+    #[serde(rename = "type")]
+    pub field_type: CustomFieldType,
+    /// Kind of entity the field is defined for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entity: Option<CustomFieldEntity>,
+    /// Options of a dropdown field
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<Vec<CustomFieldOption>>,
+    /// Fields returned by the API that are not modelled above, sent back unchanged
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+/// Option of a dropdown custom field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomFieldOption {
+    /// Unique option identifier
+    pub id: i32,
+    /// Displayed name of the option
+    pub name: String,
+}
+
+/// Value of one custom field on a task, project or customer
+///
+/// The JSON type of `value` depends on the field type: a string for text fields, a number for
+/// number fields, the option id for dropdown fields and a `YYYY-MM-DD` string for date fields.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomFieldValue {
+    /// Identifier of the custom field (`CustomFieldDefinition.id`)
+    pub id: i32,
+    /// Raw value, `null` when the field is not set
+    #[serde(default)]
+    pub value: Value,
+}
+
+/// Custom field value decoded according to the type of its field
+#[derive(Debug, Clone, PartialEq)]
+pub enum CustomFieldData {
+    /// Value of a text field
+    Text(String),
+    /// Value of a number field
+    Number(f64),
+    /// Option id of a dropdown field
+    Dropdown(i32),
+    /// Value of a date field
+    Date(NaiveDate),
+    /// Field not set
+    Empty,
+}
+
+/// Payloads carrying custom field values
+pub trait CustomFields {
+    /// Returns the custom field values, empty if none were returned.
+    fn custom_fields(&self) -> &[CustomFieldValue];
+
+    /// Returns the custom field values for modification.
+    fn custom_fields_mut(&mut self) -> &mut Vec<CustomFieldValue>;
+
+    /// Returns the value of a custom field, if present.
+    fn custom_field(&self, field_id: i32) -> Option<&CustomFieldValue> {
+        self.custom_fields()
+            .iter()
+            .find(|custom_field| custom_field.id == field_id)
+    }
+
+    /// Sets the value of a custom field, replacing any previous value.
+    fn set_custom_field(&mut self, value: CustomFieldValue) {
+        let custom_fields = self.custom_fields_mut();
+        match custom_fields
+            .iter_mut()
+            .find(|custom_field| custom_field.id == value.id)
+        {
+            Some(custom_field) => *custom_field = value,
+            None => custom_fields.push(value),
+        }
+    }
+
+    /// Returns `true` if the custom field `expected.id` holds `expected.value`.
+    ///
+    /// Numbers are compared by value, so `CustomFieldValue::number(id, 5.0)` matches a `5`
+    /// sent by the server.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let billing_code = CustomFieldValue::text(BILLING_CODE_FIELD, "B-17");
+    /// let billable = task_list
+    ///     .items
+    ///     .iter()
+    ///     .filter(|task| task.custom_field_equals(&billing_code));
+    /// ```
+    fn custom_field_equals(&self, expected: &CustomFieldValue) -> bool {
+        self.custom_field(expected.id)
+            .is_some_and(|custom_field| values_equal(&custom_field.value, &expected.value))
+    }
+}
+
+impl CustomFieldDefinition {
+    /// Returns the dropdown option with the given id.
+    pub fn option(&self, option_id: i32) -> Option<&CustomFieldOption> {
+        self.options
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .find(|option| option.id == option_id)
+    }
+
+    /// Returns the dropdown option with the given name, ignoring case.
+    pub fn option_by_name(&self, name: &str) -> Option<&CustomFieldOption> {
+        self.options
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .find(|option| option.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Decodes a value of this field according to its type.
+    ///
+    /// # Returns
+    /// The typed value, or a `ClientError::Message` if the value does not match the field type
+    /// or refers to an unknown dropdown option.
+    pub fn read(&self, value: &CustomFieldValue) -> Result<CustomFieldData, ClientError> {
+        if value.value.is_null() {
+            return Ok(CustomFieldData::Empty);
+        }
+        let data = match self.field_type {
+            CustomFieldType::Text => value
+                .as_text()
+                .map(|text| CustomFieldData::Text(text.into())),
+            CustomFieldType::Number => value.as_number().map(CustomFieldData::Number),
+            CustomFieldType::Dropdown => value
+                .as_dropdown_option()
+                .filter(|option_id| self.option(*option_id).is_some())
+                .map(CustomFieldData::Dropdown),
+            CustomFieldType::Date => value.as_date().map(CustomFieldData::Date),
+            CustomFieldType::Unknown(_) => None,
+        };
+        data.ok_or_else(|| self.mismatch(&value.value.to_string()))
+    }
+
+    /// Builds a value for this field, checking that it matches the field type.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let billing_code = definition.value(CustomFieldData::Text("B-17".to_string()))?;
+    /// task.set_custom_field(billing_code);
+    /// ```
+    pub fn value(&self, data: CustomFieldData) -> Result<CustomFieldValue, ClientError> {
+        let id = self.id;
+        match (&self.field_type, data) {
+            (_, CustomFieldData::Empty) => Ok(CustomFieldValue::empty(id)),
+            (CustomFieldType::Text, CustomFieldData::Text(text)) => {
+                Ok(CustomFieldValue::text(id, text))
+            }
+            (CustomFieldType::Number, CustomFieldData::Number(number)) => {
+                CustomFieldValue::number(id, number)
+            }
+            (CustomFieldType::Dropdown, CustomFieldData::Dropdown(option_id))
+                if self.option(option_id).is_some() =>
+            {
+                Ok(CustomFieldValue::dropdown(id, option_id))
+            }
+            (CustomFieldType::Date, CustomFieldData::Date(date)) => {
+                Ok(CustomFieldValue::date(id, date))
+            }
+            (_, data) => Err(self.mismatch(&format!("{:?}", data))),
+        }
+    }
+
+    fn mismatch(&self, value: &str) -> ClientError {
+        ClientError::Message(format!(
+            "Value {} does not fit custom field '{}' of type {}",
+            value, self.name, self.field_type
+        ))
+    }
+}
+
+impl CustomFieldValue {
+    /// Creates the value of a text field.
+    pub fn text(field_id: i32, text: impl Into<String>) -> Self {
+        CustomFieldValue {
+            id: field_id,
+            value: Value::String(text.into()),
+        }
+    }
+
+    /// Creates the value of a number field.
+    ///
+    /// # Returns
+    /// A `ClientError::Message` for NaN or an infinite number, which JSON cannot carry; use
+    /// `empty` to clear the field.
+    pub fn number(field_id: i32, number: f64) -> Result<Self, ClientError> {
+        let number = serde_json::Number::from_f64(number).ok_or_else(|| {
+            ClientError::Message(format!(
+                "Custom field #{} cannot hold the number {}",
+                field_id, number
+            ))
+        })?;
+        Ok(CustomFieldValue {
+            id: field_id,
+            value: Value::Number(number),
+        })
+    }
+
+    /// Creates the value of a dropdown field from the id of the selected option.
+    pub fn dropdown(field_id: i32, option_id: i32) -> Self {
+        CustomFieldValue {
+            id: field_id,
+            value: Value::from(option_id),
+        }
+    }
+
+    /// Creates the value of a date field.
+    pub fn date(field_id: i32, date: NaiveDate) -> Self {
+        CustomFieldValue {
+            id: field_id,
+            value: Value::String(date.format(DATE_FORMAT).to_string()),
+        }
+    }
+
+    /// Creates a value clearing the field.
+    pub fn empty(field_id: i32) -> Self {
+        CustomFieldValue {
+            id: field_id,
+            value: Value::Null,
+        }
+    }
+
+    /// Returns `true` if the field is not set.
+    pub fn is_empty(&self) -> bool {
+        self.value.is_null()
+    }
+
+    /// Returns the value of a text field.
+    pub fn as_text(&self) -> Option<&str> {
+        self.value.as_str()
+    }
+
+    /// Returns the value of a number field.
+    pub fn as_number(&self) -> Option<f64> {
+        self.value.as_f64()
+    }
+
+    /// Returns the selected option id of a dropdown field.
+    pub fn as_dropdown_option(&self) -> Option<i32> {
+        self.value.as_i64().and_then(|id| i32::try_from(id).ok())
+    }
+
+    /// Returns the value of a date field.
+    pub fn as_date(&self) -> Option<NaiveDate> {
+        self.value
+            .as_str()
+            .and_then(|date| NaiveDate::parse_from_str(date, DATE_FORMAT).ok())
+    }
+}
+
+macro_rules! impl_custom_fields {
+    ($($payload:ty),* $(,)?) => {
+        $(
+            impl CustomFields for $payload {
+                fn custom_fields(&self) -> &[CustomFieldValue] {
+                    self.custom_fields.as_deref().unwrap_or_default()
+                }
+
+                fn custom_fields_mut(&mut self) -> &mut Vec<CustomFieldValue> {
+                    self.custom_fields.get_or_insert_with(Vec::new)
+                }
+            }
+        )*
+    };
+}
+
+impl_custom_fields!(Customer, Project, Task, TaskWithAllowedActions);
+
+/// Compares two raw values, numbers by value as the server drops the fraction of whole numbers.
+fn values_equal(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(left), Value::Number(right)) => left.as_f64() == right.as_f64(),
+        _ => left == right,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_compare_by_value() {
+        assert!(values_equal(&Value::from(5), &Value::from(5.0)));
+        assert!(!values_equal(&Value::from(5), &Value::from(5.5)));
+        assert!(!values_equal(&Value::from(5), &Value::from("5")));
+        assert!(values_equal(&Value::from("B-17"), &Value::from("B-17")));
+    }
+
+    #[test]
+    fn number_rejects_non_finite() {
+        assert!(CustomFieldValue::number(3, f64::NAN).is_err());
+        assert!(CustomFieldValue::number(3, f64::INFINITY).is_err());
+        assert!(
+            CustomFieldValue::number(3, 2.5).is_ok_and(|value| value.value == Value::from(2.5))
+        );
+    }
+}
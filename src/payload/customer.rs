@@ -1,7 +1,10 @@
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
-use crate::{date_formatter::date_format, payload::PayloadTrait};
+use crate::{
+    date_formatter::date_format,
+    payload::{CustomFieldValue, ExtraFields, PayloadTrait},
+};
 
 /// Workflow status model representing a status in a workflow
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +24,13 @@ pub struct Customer {
     /// Description of the project
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Values of the custom fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub custom_fields: Option<Vec<CustomFieldValue>>,
+    /// Fields returned by the API that are not modelled above, sent back unchanged
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 impl PayloadTrait for Customer {
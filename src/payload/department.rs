@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+use crate::payload::ExtraFields;
+
+/// Department model representing a group of users
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Department {
+    /// Unique department identifier (read-only)
+    pub id: i32,
+    /// Name of the department
+    pub name: String,
+    /// Fields returned by the API that are not modelled above, sent back unchanged
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
@@ -0,0 +1,62 @@
+use serde::Serialize;
+
+use crate::payload::{Customer, Project, Task, TypeOfWork, WorkflowStatus};
+
+/// Kind of entity a task refers to through one of its `*_id` fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReferenceKind {
+    /// Reference through `Task.customer_id`
+    Customer,
+    /// Reference through `Task.project_id`
+    Project,
+    /// Reference through `Task.type_of_work_id`
+    TypeOfWork,
+    /// Reference through `Task.workflow_status_id`
+    WorkflowStatus,
+}
+
+/// A task reference that could not be resolved against the included entities
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingReference {
+    /// Identifier of the task holding the reference
+    pub task_id: Option<i32>,
+    /// Kind of the referenced entity
+    pub kind: ReferenceKind,
+    /// Identifier of the referenced entity
+    pub referenced_id: i32,
+}
+
+/// Task together with the referenced entities included in the same response
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnrichedTask<'a> {
+    /// The task itself
+    pub task: &'a Task,
+    /// Customer the task belongs to
+    pub customer: Option<&'a Customer>,
+    /// Project the task belongs to
+    pub project: Option<&'a Project>,
+    /// Type of work assigned to the task
+    pub type_of_work: Option<&'a TypeOfWork>,
+    /// Workflow status of the task
+    pub workflow_status: Option<&'a WorkflowStatus>,
+}
+
+/// Result of resolving the referenced entities of a `TaskList`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnrichedTaskList<'a> {
+    /// Tasks with their resolved references, in the order of `TaskList.items`
+    pub items: Vec<EnrichedTask<'a>>,
+    /// References that were requested but not present in the response
+    pub missing_references: Vec<MissingReference>,
+}
+
+impl EnrichedTaskList<'_> {
+    /// Returns `true` if every requested reference was resolved.
+    pub fn is_complete(&self) -> bool {
+        self.missing_references.is_empty()
+    }
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::errors::ClientError;
+use crate::payload::{CustomFieldValue, ExtraFields};
+
+/// Fields sent to a create or patch route, keyed by their wire name
+///
+/// Only the fields that are set are sent, so a patch leaves every other field unchanged.
+///
+/// # Example
+/// ``` ignore
+/// let changes = EntityFields::new()
+///     .with_field("description", "Phase 2")?
+///     .with_custom_field(CustomFieldValue::text(BILLING_CODE_FIELD, "B-17"));
+/// client_context.call_route(&patch_project(project_id), Some(&changes))?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct EntityFields {
+    /// Field values by wire name
+    pub fields: ExtraFields,
+}
+
+impl EntityFields {
+    /// Creates an empty set of fields.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a field and returns the modified set.
+    ///
+    /// # Arguments
+    /// * `name` - The wire name of the field, e.g. `"customerId"`.
+    /// * `value` - The value of the field.
+    pub fn with_field<T: Serialize>(mut self, name: &str, value: T) -> Result<Self, ClientError> {
+        let value = serde_json::to_value(value).map_err(ClientError::SerdeJson)?;
+        self.fields.insert(name.to_string(), value);
+        Ok(self)
+    }
+
+    /// Sets a custom field value and returns the modified set.
+    ///
+    /// A previous value of the same custom field is replaced.
+    pub fn with_custom_field(mut self, custom_field: CustomFieldValue) -> Self {
+        let entry = self
+            .fields
+            .entry("customFields")
+            .or_insert_with(|| Value::Array(vec![]));
+        if !entry.is_array() {
+            *entry = Value::Array(vec![]);
+        }
+        if let Value::Array(custom_fields) = entry {
+            custom_fields.retain(|value| value.get("id") != Some(&Value::from(custom_field.id)));
+            custom_fields.push(serde_json::json!({
+                "id": custom_field.id,
+                "value": custom_field.value,
+            }));
+        }
+        self
+    }
+}
@@ -1,16 +1,93 @@
-use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Implements `as_str`, `Display`, `FromStr` and string (de)serialization for an enum
+/// with an `Unknown(String)` fallback variant.
+///
+/// Parsing is case-insensitive; values the crate does not know about are kept in `Unknown`
+/// and written back unchanged.
+macro_rules! wire_enum {
+    ($name:ident { $($variant:ident => $wire:literal),+ $(,)? }) => {
+        impl $name {
+            /// Returns the value as sent over the wire.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $wire,)+
+                    $name::Unknown(value) => value,
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Infallible;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                $(if s.eq_ignore_ascii_case($wire) {
+                    return Ok($name::$variant);
+                })+
+                Ok($name::Unknown(s.to_string()))
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = String::deserialize(deserializer)?;
+                let Ok(parsed) = value.parse::<$name>();
+                Ok(parsed)
+            }
+        }
+    };
+}
 
 /// Task status enumeration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum TaskStatus {
     /// Task is active
     Open,
     /// Task is completed
     Completed,
+    /// Status not known to this client
+    Unknown(String),
+}
+
+wire_enum!(TaskStatus {
+    Open => "open",
+    Completed => "completed",
+});
+
+/// Workflow status type enumeration
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WorkflowStatusType {
+    /// Tasks in this workflow status are open
+    Open,
+    /// Tasks in this workflow status are completed
+    Completed,
+    /// Type not known to this client
+    Unknown(String),
 }
 
+wire_enum!(WorkflowStatusType {
+    Open => "open",
+    Completed => "completed",
+});
+
 /// Leave type balance enumeration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum LeaveTypeBalance {
     /// No balance tracking
     None,
@@ -18,11 +95,59 @@ pub enum LeaveTypeBalance {
     Sick,
     /// Paid time off balance tracking
     PTO,
+    /// Balance type not known to this client
+    Unknown(String),
+}
+
+wire_enum!(LeaveTypeBalance {
+    None => "none",
+    Sick => "sick",
+    PTO => "pto",
+});
+
+/// Custom field type enumeration
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CustomFieldType {
+    /// Free text
+    Text,
+    /// Number
+    Number,
+    /// One option out of a predefined list
+    Dropdown,
+    /// Calendar date
+    Date,
+    /// Type not known to this client
+    Unknown(String),
 }
 
+wire_enum!(CustomFieldType {
+    Text => "text",
+    Number => "number",
+    Dropdown => "dropdown",
+    Date => "date",
+});
+
+/// Kind of entity a custom field is defined for
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CustomFieldEntity {
+    /// Tasks
+    Task,
+    /// Projects
+    Project,
+    /// Customers
+    Customer,
+    /// Entity not known to this client
+    Unknown(String),
+}
+
+wire_enum!(CustomFieldEntity {
+    Task => "task",
+    Project => "project",
+    Customer => "customer",
+});
+
 /// HTTP method enumeration
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "UPPERCASE")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Method {
     /// HTTP GET method
     GET,
@@ -34,4 +159,14 @@ pub enum Method {
     DELETE,
     /// HTTP PATCH method
     PATCH,
+    /// Any other HTTP method
+    Unknown(String),
 }
+
+wire_enum!(Method {
+    GET => "GET",
+    POST => "POST",
+    PUT => "PUT",
+    DELETE => "DELETE",
+    PATCH => "PATCH",
+});
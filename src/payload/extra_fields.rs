@@ -0,0 +1,94 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use crate::errors::ClientError;
+use crate::payload::{
+    BatchResultItem, Customer, Department, LeaveTimeDay, LeaveTimeRecord, LeaveType, Project, Task,
+    TaskWithAllowedActions, TimeTrackDay, TimeTrackRecord, TypeOfWork, User,
+    UserWithAllowedActions, UserWithPassword, WorkflowStatus,
+};
+
+/// JSON fields of a payload that the crate does not model, keyed by their wire name
+pub type ExtraFields = Map<String, Value>;
+
+/// Payloads keeping the fields they do not model, so they survive a GET then PUT round trip
+pub trait Extensible {
+    /// Returns the fields that are not modelled by the payload.
+    fn extra(&self) -> &ExtraFields;
+
+    /// Returns the fields that are not modelled by the payload, for modification.
+    fn extra_mut(&mut self) -> &mut ExtraFields;
+
+    /// Reads an extension field as `T`.
+    ///
+    /// # Arguments
+    /// * `name` - The wire name of the field, e.g. `"billingCode"`.
+    ///
+    /// # Returns
+    /// `None` if the field is absent or `null`, or an error if it does not decode as `T`.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let billing_code: Option<String> = task.extra_field("billingCode")?;
+    /// ```
+    fn extra_field<T: DeserializeOwned>(&self, name: &str) -> Result<Option<T>, ClientError> {
+        match self.extra().get(name) {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => T::deserialize(value)
+                .map(Some)
+                .map_err(ClientError::SerdeJson),
+        }
+    }
+
+    /// Sets an extension field, replacing any previous value.
+    ///
+    /// # Arguments
+    /// * `name` - The wire name of the field.
+    /// * `value` - The new value.
+    fn set_extra_field<T: Serialize>(&mut self, name: &str, value: T) -> Result<(), ClientError> {
+        let value = serde_json::to_value(value).map_err(ClientError::SerdeJson)?;
+        self.extra_mut().insert(name.to_string(), value);
+        Ok(())
+    }
+
+    /// Removes an extension field and returns its value, if it was set.
+    fn remove_extra_field(&mut self, name: &str) -> Option<Value> {
+        self.extra_mut().remove(name)
+    }
+}
+
+macro_rules! impl_extensible {
+    ($($payload:ty),* $(,)?) => {
+        $(
+            impl Extensible for $payload {
+                fn extra(&self) -> &ExtraFields {
+                    &self.extra
+                }
+
+                fn extra_mut(&mut self) -> &mut ExtraFields {
+                    &mut self.extra
+                }
+            }
+        )*
+    };
+}
+
+impl_extensible!(
+    BatchResultItem,
+    Customer,
+    Department,
+    LeaveTimeDay,
+    LeaveTimeRecord,
+    LeaveType,
+    Project,
+    Task,
+    TaskWithAllowedActions,
+    TimeTrackDay,
+    TimeTrackRecord,
+    TypeOfWork,
+    User,
+    UserWithAllowedActions,
+    UserWithPassword,
+    WorkflowStatus,
+);
@@ -0,0 +1,79 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::date_formatter::{date_format, date_format_option};
+use crate::payload::{ExtraFields, WorkDuration};
+
+/// Leave time of users over a date range, as returned by `get_leave_time`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaveTime {
+    /// First day of the range
+    #[serde(with = "date_format")]
+    pub date_from: NaiveDate,
+    /// Last day of the range (inclusive)
+    #[serde(with = "date_format")]
+    pub date_to: NaiveDate,
+    /// Leave time per user and day; days without leave may be omitted
+    #[serde(default)]
+    pub data: Vec<LeaveTimeDay>,
+    /// First day left out when the response was cut short by `stopAfter`
+    #[serde(
+        with = "date_format_option",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub next_date_from: Option<NaiveDate>,
+}
+
+/// Leave time of one user on one day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaveTimeDay {
+    /// Identifier of the user (`User.id`)
+    pub user_id: i32,
+    /// Day of the leave
+    #[serde(with = "date_format")]
+    pub date: NaiveDate,
+    /// Leave time per leave type
+    #[serde(default)]
+    pub records: Vec<LeaveTimeRecord>,
+    /// Fields returned by the API that are not modelled above, sent back unchanged
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+/// Leave time of a user of one leave type on one day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaveTimeRecord {
+    /// Identifier of the leave type (`LeaveType.id`)
+    pub leave_type_id: i32,
+    /// Time taken as leave (minutes on the wire)
+    pub leave_time: WorkDuration,
+    /// Fields returned by the API that are not modelled above, sent back unchanged
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+impl LeaveTime {
+    /// Sums the leave time of a user on a day.
+    pub fn total_for(&self, user_id: i32, date: NaiveDate) -> WorkDuration {
+        self.data
+            .iter()
+            .filter(|day| day.user_id == user_id && day.date == date)
+            .flat_map(|day| day.records.iter())
+            .map(|record| record.leave_time)
+            .sum()
+    }
+
+    /// Appends the leave time starting at `next_date_from` to this one.
+    ///
+    /// # Arguments
+    /// * `next` - The leave time fetched from `next_date_from` on.
+    pub fn merge(&mut self, next: LeaveTime) {
+        self.data.extend(next.data);
+        self.date_to = self.date_to.max(next.date_to);
+        self.next_date_from = next.next_date_from;
+    }
+}
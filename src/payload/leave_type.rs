@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::payload::{PayloadTrait, enums::LeaveTypeBalance};
+use crate::payload::{ExtraFields, PayloadTrait, enums::LeaveTypeBalance};
 
 /// Project model representing a project in ActiTime
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,6 +14,9 @@ pub struct LeaveType {
     pub balance: LeaveTypeBalance,
     /// Archived status of the leave type
     pub archived: bool,
+    /// Fields returned by the API that are not modelled above, sent back unchanged
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 impl PayloadTrait for LeaveType {
@@ -0,0 +1,147 @@
+use std::fmt;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::errors::{ClientError, DecodeError};
+use crate::payload::{PaginationListing, TaskList};
+
+/// A list payload decoded in lenient mode, with the items that failed to decode
+#[derive(Debug)]
+pub struct Lenient<ListType> {
+    /// The list, without the items that failed to decode
+    pub value: ListType,
+    /// The items that failed to decode, in response order
+    pub errors: Vec<ItemDecodeError>,
+}
+
+/// An item of a list payload that failed to decode
+#[derive(Debug)]
+pub struct ItemDecodeError {
+    /// Index of the item in the `items` array of the response
+    pub index: usize,
+    /// Raw JSON of the item
+    pub raw: Value,
+    /// Decode error, with the path from the root of the response, e.g. `items[37].deadline`
+    pub error: DecodeError,
+}
+
+/// List payloads whose `items` can be decoded one by one
+///
+/// In lenient mode an item that does not match `Item` is reported in `Lenient::errors`
+/// instead of failing the whole page. Any other part of the payload is still decoded strictly.
+pub trait ListPayload: Serialize + DeserializeOwned {
+    /// Type of the list items
+    type Item: DeserializeOwned;
+
+    /// Returns the items of the list.
+    fn items_mut(&mut self) -> &mut Vec<Self::Item>;
+
+    /// Decodes a response body, skipping the items that fail to decode.
+    ///
+    /// # Arguments
+    /// * `body` - The raw response body.
+    /// * `attach_body` - Whether the whole body is kept in a top-level `DecodeError`.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let page = TaskList::decode_lenient(&body, false)?;
+    /// for error in &page.errors {
+    ///     eprintln!("Skipped task #{}: {}", error.index, error.error);
+    /// }
+    /// ```
+    fn decode_lenient(body: &[u8], attach_body: bool) -> Result<Lenient<Self>, ClientError> {
+        let mut value: Value =
+            DecodeError::decode(body, attach_body).map_err(ClientError::Decode)?;
+
+        let raw_items = match value.get_mut("items") {
+            Some(Value::Array(items)) => std::mem::take(items),
+            _ => vec![],
+        };
+        let mut items = Vec::with_capacity(raw_items.len());
+        let mut errors = vec![];
+        for (index, item) in raw_items.into_iter().enumerate() {
+            match DecodeError::decode_value(&item, &format!("items[{}]", index)) {
+                Ok(decoded) => items.push(decoded),
+                Err(error) => errors.push(ItemDecodeError {
+                    index,
+                    raw: item,
+                    error,
+                }),
+            }
+        }
+
+        let mut list: Self = DecodeError::decode_value(&value, "").map_err(|mut error| {
+            error.body = attach_body.then(|| String::from_utf8_lossy(body).into_owned());
+            ClientError::Decode(error)
+        })?;
+        *list.items_mut() = items;
+        Ok(Lenient {
+            value: list,
+            errors,
+        })
+    }
+}
+
+impl ListPayload for TaskList {
+    type Item = crate::payload::Task;
+
+    fn items_mut(&mut self) -> &mut Vec<Self::Item> {
+        &mut self.items
+    }
+}
+
+impl<ItemType> ListPayload for PaginationListing<ItemType>
+where
+    ItemType: Serialize + DeserializeOwned,
+{
+    type Item = ItemType;
+
+    fn items_mut(&mut self) -> &mut Vec<Self::Item> {
+        &mut self.items
+    }
+}
+
+impl<ListType> Lenient<ListType> {
+    /// Returns `true` if every item decoded.
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl fmt::Display for ItemDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payload::Customer;
+
+    #[test]
+    fn skips_items_that_fail_to_decode() -> Result<(), ClientError> {
+        let body = br#"{"offset":0,"limit":100,"items":[
+            {"id":1,"name":"Acme","archived":false,"created":"2024-01-01","url":"u"},
+            {"id":2,"name":"Globex","archived":"no","created":"2024-01-01","url":"u"}
+        ]}"#;
+        let page = PaginationListing::<Customer>::decode_lenient(body, false)?;
+        assert_eq!(page.value.items.len(), 1);
+        assert_eq!(page.errors.len(), 1);
+        assert_eq!(page.errors[0].index, 1);
+        assert_eq!(page.errors[0].error.path, "items[1].archived");
+        Ok(())
+    }
+
+    #[test]
+    fn still_rejects_an_invalid_envelope() {
+        let body = br#"{"offset":"zero","limit":100,"items":[]}"#;
+        let result = PaginationListing::<Customer>::decode_lenient(body, true);
+        assert!(matches!(
+            result,
+            Err(ClientError::Decode(DecodeError { ref path, body: Some(_), .. })) if path == "offset"
+        ));
+    }
+}
@@ -1,10 +1,18 @@
 mod allowed_actions;
 mod api_error;
 mod batch_request_item;
+mod batch_result_item;
+mod custom_field;
 mod customer;
+mod department;
+mod enriched_task;
+mod entity_fields;
 /// Enumerations used in the ActiTime API
 pub mod enums;
+mod extra_fields;
+mod leave_time;
 mod leave_type;
+mod lenient;
 mod pagination_listing;
 mod payload_trait;
 mod project;
@@ -12,17 +20,29 @@ mod schedule;
 mod task;
 mod task_list;
 mod task_with_allowed_actions;
+mod time_track;
 mod type_of_work;
 mod user;
 mod user_with_allowed_actions;
 mod user_with_password;
+mod work_duration;
 mod workflow_status;
 
 pub use allowed_actions::AllowedActions;
 pub use api_error::ApiError;
 pub use batch_request_item::BatchRequestItem;
+pub use batch_result_item::BatchResultItem;
+pub use custom_field::{
+    CustomFieldData, CustomFieldDefinition, CustomFieldOption, CustomFieldValue, CustomFields,
+};
 pub use customer::Customer;
+pub use department::Department;
+pub use enriched_task::{EnrichedTask, EnrichedTaskList, MissingReference, ReferenceKind};
+pub use entity_fields::EntityFields;
+pub use extra_fields::{Extensible, ExtraFields};
+pub use leave_time::{LeaveTime, LeaveTimeDay, LeaveTimeRecord};
 pub use leave_type::LeaveType;
+pub use lenient::{ItemDecodeError, Lenient, ListPayload};
 pub use pagination_listing::PaginationListing;
 pub use payload_trait::PayloadTrait;
 pub use project::Project;
@@ -30,8 +50,10 @@ pub use schedule::Schedule;
 pub use task::Task;
 pub use task_list::TaskList;
 pub use task_with_allowed_actions::TaskWithAllowedActions;
+pub use time_track::{TimeTrack, TimeTrackDay, TimeTrackEntry, TimeTrackRecord};
 pub use type_of_work::TypeOfWork;
 pub use user::User;
 pub use user_with_allowed_actions::UserWithAllowedActions;
 pub use user_with_password::UserWithPassword;
+pub use work_duration::WorkDuration;
 pub use workflow_status::WorkflowStatus;
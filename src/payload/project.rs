@@ -2,7 +2,7 @@ use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
 use crate::date_formatter::date_format;
-use crate::payload::PayloadTrait;
+use crate::payload::{CustomFieldValue, ExtraFields, PayloadTrait};
 
 /// Project model representing a project in ActiTime
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +26,13 @@ pub struct Project {
     /// Description of the project
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Values of the custom fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub custom_fields: Option<Vec<CustomFieldValue>>,
+    /// Fields returned by the API that are not modelled above, sent back unchanged
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 impl PayloadTrait for Project {
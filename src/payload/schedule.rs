@@ -1,22 +1,46 @@
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
-use crate::{date_formatter::date_format, payload::PayloadTrait};
+use crate::{
+    date_formatter::date_format,
+    errors::ClientError,
+    payload::{PayloadTrait, WorkDuration},
+};
 
 /// Schedule model representing a user's work schedule
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", try_from = "RawSchedule")]
 pub struct Schedule {
     /// Start date of the schedule
     /// Example: "2020-01-01"
     #[serde(with = "date_format", default)]
     pub date_from: NaiveDate,
-    /// End date of the schedule
+    /// End date of the schedule (inclusive)
     /// Example: "2020-07-01"
     #[serde(with = "date_format", default)]
     pub date_to: NaiveDate,
-    /// Workday durations in minutes for each day in the specified date range
-    pub entries: Vec<u32>,
+    /// Workday durations (minutes on the wire) for each day in the specified date range.
+    /// The first entry is `date_from`, the last one is `date_to`.
+    pub entries: Vec<WorkDuration>,
+}
+
+/// Wire representation of `Schedule`, validated before conversion
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawSchedule {
+    #[serde(with = "date_format", default)]
+    date_from: NaiveDate,
+    #[serde(with = "date_format", default)]
+    date_to: NaiveDate,
+    entries: Vec<WorkDuration>,
+}
+
+impl TryFrom<RawSchedule> for Schedule {
+    type Error = ClientError;
+
+    fn try_from(raw: RawSchedule) -> Result<Self, Self::Error> {
+        Schedule::new(raw.date_from, raw.date_to, raw.entries)
+    }
 }
 
 impl PayloadTrait for Schedule {
@@ -28,3 +52,184 @@ impl PayloadTrait for Schedule {
         serde_json::from_str(json_str).unwrap()
     }
 }
+
+impl Schedule {
+    /// Creates a new `Schedule`, checking that there is one entry per day of the range.
+    ///
+    /// # Arguments
+    /// * `date_from` - The first day of the schedule.
+    /// * `date_to` - The last day of the schedule (inclusive).
+    /// * `entries` - Scheduled time for each day from `date_from` to `date_to`.
+    ///
+    /// # Returns
+    /// The `Schedule`, or a `ClientError::Message` if the entries do not match the date range.
+    pub fn new(
+        date_from: NaiveDate,
+        date_to: NaiveDate,
+        entries: Vec<WorkDuration>,
+    ) -> Result<Self, ClientError> {
+        let schedule = Schedule {
+            date_from,
+            date_to,
+            entries,
+        };
+        schedule.validate()?;
+        Ok(schedule)
+    }
+
+    /// Checks that the schedule has exactly one entry per day from `date_from` to `date_to`.
+    ///
+    /// # Returns
+    /// `Ok(())` if the schedule is consistent, otherwise a `ClientError::Message`.
+    pub fn validate(&self) -> Result<(), ClientError> {
+        if self.date_to < self.date_from {
+            return Err(ClientError::Message(format!(
+                "Schedule ends ({}) before it starts ({})",
+                self.date_to, self.date_from
+            )));
+        }
+        let expected = self.day_count();
+        if self.entries.len() != expected {
+            return Err(ClientError::Message(format!(
+                "Schedule from {} to {} has {} entries, expected {}",
+                self.date_from,
+                self.date_to,
+                self.entries.len(),
+                expected
+            )));
+        }
+        Ok(())
+    }
+
+    /// Number of days covered by the schedule, `date_to` included.
+    pub fn day_count(&self) -> usize {
+        ((self.date_to - self.date_from).num_days() + 1).max(0) as usize
+    }
+
+    /// Returns `true` if `date` lies within the schedule.
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.date_from <= date && date <= self.date_to
+    }
+
+    /// Iterates over the `(date, scheduled time)` pairs of the schedule.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// for (date, scheduled) in schedule.days() {
+    ///     println!("{}: {}", date, scheduled);
+    /// }
+    /// ```
+    pub fn days(&self) -> impl Iterator<Item = (NaiveDate, WorkDuration)> + '_ {
+        self.date_from.iter_days().zip(self.entries.iter().copied())
+    }
+
+    /// Iterates over the days with scheduled working time.
+    pub fn working_days(&self) -> impl Iterator<Item = (NaiveDate, WorkDuration)> + '_ {
+        self.days().filter(|(_, scheduled)| !scheduled.is_zero())
+    }
+
+    /// Iterates over the days without scheduled working time (weekends, holidays, days off).
+    pub fn non_working_days(&self) -> impl Iterator<Item = NaiveDate> + '_ {
+        self.days()
+            .filter(|(_, scheduled)| scheduled.is_zero())
+            .map(|(date, _)| date)
+    }
+
+    /// Returns the scheduled time for `date`, or `None` if it lies outside the schedule.
+    pub fn scheduled_time(&self, date: NaiveDate) -> Option<WorkDuration> {
+        if !self.contains(date) {
+            return None;
+        }
+        let index = (date - self.date_from).num_days() as usize;
+        self.entries.get(index).copied()
+    }
+
+    /// Returns whether `date` is a working day, or `None` if it lies outside the schedule.
+    pub fn is_working_day(&self, date: NaiveDate) -> Option<bool> {
+        self.scheduled_time(date)
+            .map(|scheduled| !scheduled.is_zero())
+    }
+
+    /// Sums the scheduled time from `from` to `to` (both inclusive).
+    ///
+    /// Days outside the schedule are not counted.
+    pub fn total_scheduled(&self, from: NaiveDate, to: NaiveDate) -> WorkDuration {
+        self.days()
+            .filter(|(date, _)| from <= *date && *date <= to)
+            .map(|(_, scheduled)| scheduled)
+            .sum()
+    }
+
+    /// Sums the scheduled hours from `from` to `to` (both inclusive).
+    ///
+    /// Days outside the schedule are not counted.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let expected_hours = schedule.total_hours(month_start, month_end);
+    /// let utilization = tracked_hours / expected_hours;
+    /// ```
+    pub fn total_hours(&self, from: NaiveDate, to: NaiveDate) -> f64 {
+        self.total_scheduled(from, to).hours()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap_or_default()
+    }
+
+    fn minutes(entries: &[i64]) -> Vec<WorkDuration> {
+        entries
+            .iter()
+            .copied()
+            .map(WorkDuration::from_minutes)
+            .collect()
+    }
+
+    #[test]
+    fn accepts_one_entry_per_day() {
+        let schedule = Schedule::new(date(1), date(3), minutes(&[480, 0, 240]));
+        assert!(schedule.is_ok_and(|schedule| schedule.day_count() == 3));
+    }
+
+    #[test]
+    fn rejects_entry_count_mismatch() {
+        assert!(Schedule::new(date(1), date(3), minutes(&[480, 0])).is_err());
+        assert!(Schedule::new(date(1), date(1), minutes(&[480, 0])).is_err());
+    }
+
+    #[test]
+    fn rejects_reversed_range() {
+        assert!(Schedule::new(date(3), date(1), vec![]).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_payload() {
+        let json = r#"{"dateFrom":"2024-03-01","dateTo":"2024-03-02","entries":[480]}"#;
+        assert!(serde_json::from_str::<Schedule>(json).is_err());
+    }
+
+    #[test]
+    fn looks_up_days() -> Result<(), ClientError> {
+        let schedule = Schedule::new(date(1), date(3), minutes(&[480, 0, 240]))?;
+        assert_eq!(
+            schedule.scheduled_time(date(3)),
+            Some(WorkDuration::from_minutes(240))
+        );
+        assert_eq!(schedule.scheduled_time(date(4)), None);
+        assert_eq!(schedule.is_working_day(date(2)), Some(false));
+        assert_eq!(
+            schedule.non_working_days().collect::<Vec<_>>(),
+            vec![date(2)]
+        );
+        assert_eq!(
+            schedule.total_scheduled(date(2), date(9)),
+            WorkDuration::from_minutes(240)
+        );
+        Ok(())
+    }
+}
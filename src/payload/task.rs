@@ -3,7 +3,9 @@ use serde::{Deserialize, Serialize};
 
 use super::enums::TaskStatus;
 use crate::date_formatter::date_format_option;
-use crate::payload::{PayloadTrait, TaskWithAllowedActions};
+use crate::payload::{
+    CustomFieldValue, CustomFields, ExtraFields, PayloadTrait, TaskWithAllowedActions, WorkDuration,
+};
 
 /// User model representing an ActiTime user
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,15 +51,22 @@ pub struct Task {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(with = "date_format_option", default)]
     pub deadline: Option<NaiveDate>,
-    /// Estimated time for the task in hours
+    /// Estimated time for the task (minutes on the wire)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub estimated_time: Option<i32>,
+    pub estimated_time: Option<WorkDuration>,
     /// Customer ID associated with the task
     #[serde(skip_serializing_if = "Option::is_none")]
     pub customer_id: Option<i32>,
     /// Project ID associated with the task
     #[serde(skip_serializing_if = "Option::is_none")]
     pub project_id: Option<i32>,
+    /// Values of the custom fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub custom_fields: Option<Vec<CustomFieldValue>>,
+    /// Fields returned by the API that are not modelled above, sent back unchanged
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 impl PayloadTrait for Task {
@@ -101,6 +110,8 @@ impl Task {
             estimated_time: None,
             customer_id: None,
             project_id: None,
+            custom_fields: None,
+            extra: ExtraFields::new(),
         }
     }
 
@@ -251,11 +262,11 @@ impl Task {
     /// Sets the `estimated_time` field and returns the modified `Task`.
     ///
     /// # Arguments
-    /// * `estimated_time` - The estimated time for the task in hours.
+    /// * `estimated_time` - The estimated time for the task.
     ///
     /// # Returns
     /// The modified `Task` with the updated `estimated_time`.
-    pub fn with_estimated_time(mut self, estimated_time: i32) -> Self {
+    pub fn with_estimated_time(mut self, estimated_time: WorkDuration) -> Self {
         self.estimated_time = Some(estimated_time);
         self
     }
@@ -284,6 +295,18 @@ impl Task {
         self
     }
 
+    /// Sets a custom field value and returns the modified `Task`.
+    ///
+    /// # Arguments
+    /// * `custom_field` - The value, replacing any previous value of the same field.
+    ///
+    /// # Returns
+    /// The modified `Task` with the updated `custom_fields`.
+    pub fn with_custom_field(mut self, custom_field: CustomFieldValue) -> Self {
+        self.set_custom_field(custom_field);
+        self
+    }
+
     /// Creates a `Task` instance from a JSON string.
     ///
     /// # Arguments
@@ -321,6 +344,8 @@ impl From<TaskWithAllowedActions> for Task {
             estimated_time: task_with_actions.estimated_time,
             customer_id: task_with_actions.customer_id,
             project_id: task_with_actions.project_id,
+            custom_fields: task_with_actions.custom_fields,
+            extra: task_with_actions.extra,
         }
     }
 }
@@ -344,6 +369,8 @@ impl From<&TaskWithAllowedActions> for Task {
             estimated_time: task_with_actions.estimated_time,
             customer_id: task_with_actions.customer_id,
             project_id: task_with_actions.project_id,
+            custom_fields: task_with_actions.custom_fields.clone(),
+            extra: task_with_actions.extra.clone(),
         }
     }
 }
@@ -1,6 +1,11 @@
+use std::collections::{HashMap, HashSet};
+
 use serde::{Deserialize, Serialize};
 
-use crate::payload::{Customer, PayloadTrait, Project, Task, TypeOfWork, WorkflowStatus};
+use crate::payload::{
+    Customer, EnrichedTask, EnrichedTaskList, MissingReference, PayloadTrait, Project,
+    ReferenceKind, Task, TypeOfWork, WorkflowStatus,
+};
 
 /// Task list model representing a list of tasks
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,3 +36,181 @@ impl PayloadTrait for TaskList {
         serde_json::from_str(json_str).unwrap()
     }
 }
+
+impl TaskList {
+    /// Resolves the referenced customers, projects, types of work and workflow statuses of every task.
+    ///
+    /// Only the kinds included in the response (see `GetTasksIncludeReferenced`) are resolved.
+    /// A task referring to an id that is absent from an included collection is reported in
+    /// `missing_references` instead of being dropped.
+    ///
+    /// # Returns
+    /// An `EnrichedTaskList` borrowing from this `TaskList`.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let enriched = task_list.enriched();
+    /// for task in &enriched.items {
+    ///     println!("{} / {:?}", task.task.name, task.project.map(|p| &p.name));
+    /// }
+    /// ```
+    pub fn enriched(&self) -> EnrichedTaskList<'_> {
+        let customers = index_by_id(&self.customers, |customer| customer.id);
+        let projects = index_by_id(&self.projects, |project| project.id);
+        let types_of_work = index_by_id(&self.types_of_work, |type_of_work| type_of_work.id);
+        let workflow_statuses = index_by_id(&self.workflow_statuses, |status| status.id);
+
+        let mut missing = vec![];
+        let items = self
+            .items
+            .iter()
+            .map(|task| EnrichedTask {
+                task,
+                customer: resolve_reference(
+                    &customers,
+                    task.customer_id,
+                    task,
+                    ReferenceKind::Customer,
+                    &mut missing,
+                ),
+                project: resolve_reference(
+                    &projects,
+                    task.project_id,
+                    task,
+                    ReferenceKind::Project,
+                    &mut missing,
+                ),
+                type_of_work: resolve_reference(
+                    &types_of_work,
+                    task.type_of_work_id,
+                    task,
+                    ReferenceKind::TypeOfWork,
+                    &mut missing,
+                ),
+                workflow_status: resolve_reference(
+                    &workflow_statuses,
+                    task.workflow_status_id,
+                    task,
+                    ReferenceKind::WorkflowStatus,
+                    &mut missing,
+                ),
+            })
+            .collect();
+
+        EnrichedTaskList {
+            items,
+            missing_references: missing,
+        }
+    }
+
+    /// Appends the next page of a paginated task listing to this one.
+    ///
+    /// Items are appended in order and the referenced entity collections are merged,
+    /// skipping entities whose id is already present, so `enriched` keeps working on
+    /// the combined listing. `offset` and `limit` then span both pages.
+    ///
+    /// # Arguments
+    /// * `next_page` - The page following this one.
+    pub fn merge(&mut self, next_page: TaskList) {
+        let end = (self.offset + self.limit).max(next_page.offset + next_page.limit);
+        self.offset = self.offset.min(next_page.offset);
+        self.limit = end - self.offset;
+        self.items.extend(next_page.items);
+        merge_by_id(&mut self.customers, next_page.customers, |c| c.id);
+        merge_by_id(&mut self.projects, next_page.projects, |p| p.id);
+        merge_by_id(&mut self.types_of_work, next_page.types_of_work, |t| t.id);
+        merge_by_id(
+            &mut self.workflow_statuses,
+            next_page.workflow_statuses,
+            |w| w.id,
+        );
+    }
+}
+
+/// Indexes an included entity collection by id, or returns `None` if it was not included.
+fn index_by_id<T>(entities: &Option<Vec<T>>, id: impl Fn(&T) -> i32) -> Option<HashMap<i32, &T>> {
+    entities
+        .as_ref()
+        .map(|entities| entities.iter().map(|entity| (id(entity), entity)).collect())
+}
+
+fn resolve_reference<'a, T>(
+    index: &Option<HashMap<i32, &'a T>>,
+    referenced_id: Option<i32>,
+    task: &Task,
+    kind: ReferenceKind,
+    missing_references: &mut Vec<MissingReference>,
+) -> Option<&'a T> {
+    let (index, referenced_id) = (index.as_ref()?, referenced_id?);
+    let entity = index.get(&referenced_id).copied();
+    if entity.is_none() {
+        missing_references.push(MissingReference {
+            task_id: task.id,
+            kind,
+            referenced_id,
+        });
+    }
+    entity
+}
+
+fn merge_by_id<T>(target: &mut Option<Vec<T>>, source: Option<Vec<T>>, id: impl Fn(&T) -> i32) {
+    let Some(source) = source else {
+        return;
+    };
+    let target = target.get_or_insert_with(Vec::new);
+    let mut known: HashSet<i32> = target.iter().map(&id).collect();
+    for entity in source {
+        if known.insert(id(&entity)) {
+            target.push(entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(
+        offset: i32,
+        task_ids: &[i32],
+        project_ids: &[i32],
+    ) -> Result<TaskList, serde_json::Error> {
+        let items: Vec<String> = task_ids
+            .iter()
+            .map(|id| {
+                format!(
+                    r#"{{"id":{},"name":"Task {}","projectId":{}}}"#,
+                    id,
+                    id,
+                    id % 2
+                )
+            })
+            .collect();
+        let projects: Vec<String> = project_ids
+            .iter()
+            .map(|id| {
+                format!(
+                    r#"{{"id":{},"name":"Project {}","customerId":1,"customerName":"Acme","archived":false,"created":"2024-01-01","url":"u"}}"#,
+                    id, id
+                )
+            })
+            .collect();
+        serde_json::from_str(&format!(
+            r#"{{"items":[{}],"offset":{},"limit":2,"projects":[{}]}}"#,
+            items.join(","),
+            offset,
+            projects.join(",")
+        ))
+    }
+
+    #[test]
+    fn merge_spans_both_pages() -> Result<(), serde_json::Error> {
+        let mut task_list = page(0, &[1, 2], &[1, 0])?;
+        task_list.merge(page(2, &[3], &[1])?);
+        assert_eq!((task_list.offset, task_list.limit), (0, 4));
+        assert_eq!(task_list.items.len(), 3);
+        assert_eq!(task_list.projects.as_ref().map(Vec::len), Some(2));
+        assert!(task_list.enriched().missing_references.is_empty());
+        Ok(())
+    }
+}
@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use super::enums::TaskStatus;
 use crate::{
     date_formatter::date_format_option,
-    payload::{AllowedActions, PayloadTrait},
+    payload::{AllowedActions, CustomFieldValue, ExtraFields, PayloadTrait, WorkDuration},
 };
 
 /// User model representing an ActiTime user
@@ -53,15 +53,22 @@ pub struct TaskWithAllowedActions {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(with = "date_format_option", default)]
     pub deadline: Option<NaiveDate>,
-    /// Estimated time for the task in hours
+    /// Estimated time for the task (minutes on the wire)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub estimated_time: Option<i32>,
+    pub estimated_time: Option<WorkDuration>,
     /// Customer ID associated with the task
     #[serde(skip_serializing_if = "Option::is_none")]
     pub customer_id: Option<i32>,
     /// Project ID associated with the task
     #[serde(skip_serializing_if = "Option::is_none")]
     pub project_id: Option<i32>,
+    /// Values of the custom fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub custom_fields: Option<Vec<CustomFieldValue>>,
+    /// Fields returned by the API that are not modelled above, sent back unchanged
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 impl PayloadTrait for TaskWithAllowedActions {
@@ -107,6 +114,8 @@ impl TaskWithAllowedActions {
             estimated_time: None,
             customer_id: None,
             project_id: None,
+            custom_fields: None,
+            extra: ExtraFields::new(),
         }
     }
 
@@ -257,11 +266,11 @@ impl TaskWithAllowedActions {
     /// Sets the `estimated_time` field and returns the modified `TaskWithAllowedActions`.
     ///
     /// # Arguments
-    /// * `estimated_time` - The estimated time for the task in hours.
+    /// * `estimated_time` - The estimated time for the task.
     ///
     /// # Returns
     /// The modified `TaskWithAllowedActions` with the updated `estimated_time`.
-    pub fn with_estimated_time(mut self, estimated_time: i32) -> Self {
+    pub fn with_estimated_time(mut self, estimated_time: WorkDuration) -> Self {
         self.estimated_time = Some(estimated_time);
         self
     }
@@ -0,0 +1,106 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::date_formatter::{date_format, date_format_option};
+use crate::payload::{ExtraFields, WorkDuration};
+
+/// Time tracked by users over a date range, as returned by `get_time_track`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeTrack {
+    /// First day of the range
+    #[serde(with = "date_format")]
+    pub date_from: NaiveDate,
+    /// Last day of the range (inclusive)
+    #[serde(with = "date_format")]
+    pub date_to: NaiveDate,
+    /// Tracked time per user and day; days without records may be omitted
+    #[serde(default)]
+    pub data: Vec<TimeTrackDay>,
+    /// First day left out when the response was cut short by `stopAfter`
+    #[serde(
+        with = "date_format_option",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub next_date_from: Option<NaiveDate>,
+}
+
+/// Time tracked by one user on one day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeTrackDay {
+    /// Identifier of the user (`User.id`)
+    pub user_id: i32,
+    /// Day the time was tracked on
+    #[serde(with = "date_format")]
+    pub date: NaiveDate,
+    /// Time tracked per task
+    #[serde(default)]
+    pub records: Vec<TimeTrackRecord>,
+    /// Fields returned by the API that are not modelled above, sent back unchanged
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+/// Time tracked by a user on one task and day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeTrackRecord {
+    /// Identifier of the task (`Task.id`)
+    pub task_id: i32,
+    /// Tracked time (minutes on the wire)
+    pub time: WorkDuration,
+    /// Comment entered with the time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// Fields returned by the API that are not modelled above, sent back unchanged
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+/// One record of a `TimeTrack`, with the user and day it belongs to
+#[derive(Debug, Clone, Copy)]
+pub struct TimeTrackEntry<'a> {
+    /// Identifier of the user (`User.id`)
+    pub user_id: i32,
+    /// Day the time was tracked on
+    pub date: NaiveDate,
+    /// The record
+    pub record: &'a TimeTrackRecord,
+}
+
+impl TimeTrack {
+    /// Iterates over every record, in response order.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// for entry in time_track.entries() {
+    ///     println!("{} {} #{}: {}", entry.date, entry.user_id, entry.record.task_id, entry.record.time);
+    /// }
+    /// ```
+    pub fn entries(&self) -> impl Iterator<Item = TimeTrackEntry<'_>> + '_ {
+        self.data.iter().flat_map(|day| {
+            day.records.iter().map(move |record| TimeTrackEntry {
+                user_id: day.user_id,
+                date: day.date,
+                record,
+            })
+        })
+    }
+
+    /// Sums the time of every record.
+    pub fn total_time(&self) -> WorkDuration {
+        self.entries().map(|entry| entry.record.time).sum()
+    }
+
+    /// Appends the time-track starting at `next_date_from` to this one.
+    ///
+    /// # Arguments
+    /// * `next` - The time-track fetched from `next_date_from` on.
+    pub fn merge(&mut self, next: TimeTrack) {
+        self.data.extend(next.data);
+        self.date_to = self.date_to.max(next.date_to);
+        self.next_date_from = next.next_date_from;
+    }
+}
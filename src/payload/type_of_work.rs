@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::payload::PayloadTrait;
+use crate::payload::{ExtraFields, PayloadTrait};
 
 /// Workflow status model representing a status in a workflow
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +19,9 @@ pub struct TypeOfWork {
     pub billable: bool,
     /// Default status of the type of work. If 'true', type of work is default. If 'false', type of work is non-default.
     pub default: bool,
+    /// Fields returned by the API that are not modelled above, sent back unchanged
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 impl PayloadTrait for TypeOfWork {
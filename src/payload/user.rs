@@ -2,7 +2,7 @@ use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
 use crate::date_formatter::date_format_option;
-use crate::payload::{PayloadTrait, UserWithAllowedActions, UserWithPassword};
+use crate::payload::{ExtraFields, PayloadTrait, UserWithAllowedActions, UserWithPassword};
 
 /// User model representing an ActiTime user
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,6 +43,9 @@ pub struct User {
     pub middle_name: Option<String>,
     /// Last name of user
     pub last_name: String,
+    /// Fields returned by the API that are not modelled above, sent back unchanged
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 impl PayloadTrait for User {
@@ -77,6 +80,7 @@ impl User {
             first_name,
             middle_name: None,
             last_name,
+            extra: ExtraFields::new(),
         }
     }
 
@@ -132,6 +136,7 @@ impl From<UserWithAllowedActions> for User {
             first_name: user_with_actions.first_name,
             middle_name: user_with_actions.middle_name,
             last_name: user_with_actions.last_name,
+            extra: user_with_actions.extra,
         }
     }
 }
@@ -151,6 +156,7 @@ impl From<&UserWithAllowedActions> for User {
             first_name: user_with_actions.first_name.clone(),
             middle_name: user_with_actions.middle_name.clone(),
             last_name: user_with_actions.last_name.clone(),
+            extra: user_with_actions.extra.clone(),
         }
     }
 }
@@ -170,6 +176,7 @@ impl From<UserWithPassword> for User {
             first_name: user_with_password.first_name,
             middle_name: user_with_password.middle_name,
             last_name: user_with_password.last_name,
+            extra: user_with_password.extra,
         }
     }
 }
@@ -189,6 +196,7 @@ impl From<&UserWithPassword> for User {
             first_name: user_with_password.first_name.clone(),
             middle_name: user_with_password.middle_name.clone(),
             last_name: user_with_password.last_name.clone(),
+            extra: user_with_password.extra.clone(),
         }
     }
 }
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::date_formatter::date_format_option;
 use crate::payload::AllowedActions;
+use crate::payload::ExtraFields;
 use crate::payload::PayloadTrait;
 use crate::payload::User;
 
@@ -48,6 +49,9 @@ pub struct UserWithAllowedActions {
     pub middle_name: Option<String>,
     /// Last name of user
     pub last_name: String,
+    /// Fields returned by the API that are not modelled above, sent back unchanged
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 impl UserWithAllowedActions {
@@ -73,6 +77,7 @@ impl UserWithAllowedActions {
             first_name,
             middle_name: None,
             last_name,
+            extra: ExtraFields::new(),
         }
     }
 
@@ -148,6 +153,7 @@ impl From<User> for UserWithAllowedActions {
             first_name: user.first_name,
             middle_name: user.middle_name,
             last_name: user.last_name,
+            extra: user.extra,
         }
     }
 }
@@ -1,7 +1,11 @@
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
-use crate::{date_formatter::date_format_option, payload::PayloadTrait};
+use crate::{
+    date_formatter::date_format_option,
+    payload::{ExtraFields, PayloadTrait},
+    secret::Secret,
+};
 
 /// User model representing an ActiTime user
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,8 +31,8 @@ pub struct UserWithPassword {
     pub release_date: Option<NaiveDate>,
     /// User email (visible only for users with 'Manage Accounts & Permissions' access right)
     pub email: String,
-    /// User password (required for creating a new user)
-    pub password: String,
+    /// User password (required for creating a new user), redacted in `Debug` output
+    pub password: Secret,
     /// Full name (first name + MI. + last name) - read-only
     #[serde(skip_serializing_if = "Option::is_none")]
     pub full_name: Option<String>,
@@ -44,6 +48,9 @@ pub struct UserWithPassword {
     pub middle_name: Option<String>,
     /// Last name of user
     pub last_name: String,
+    /// Fields returned by the API that are not modelled above, sent back unchanged
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 impl PayloadTrait for UserWithPassword {
@@ -73,13 +80,14 @@ impl UserWithPassword {
             hired: None,
             release_date: None,
             email,
-            password,
+            password: Secret::new(password),
             full_name: None,
             username,
             active: None,
             first_name,
             middle_name: None,
             last_name,
+            extra: ExtraFields::new(),
         }
     }
 
@@ -0,0 +1,366 @@
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::ClientError;
+
+/// Amount of working time, stored with the minute resolution used by actiTIME
+///
+/// Serializes as a plain number of minutes, the way the API represents estimates,
+/// schedule entries and tracked time. Arithmetic saturates instead of overflowing; use the
+/// `checked_*` methods to detect overflow.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct WorkDuration(i64);
+
+impl WorkDuration {
+    /// A duration of zero minutes.
+    pub const ZERO: WorkDuration = WorkDuration(0);
+
+    /// Creates a duration from a number of minutes.
+    pub const fn from_minutes(minutes: i64) -> Self {
+        WorkDuration(minutes)
+    }
+
+    /// Creates a duration from a number of whole hours, saturating on overflow.
+    pub const fn from_hours(hours: i64) -> Self {
+        WorkDuration(hours.saturating_mul(60))
+    }
+
+    /// Creates a duration from a fractional number of hours, rounded to the nearest minute.
+    ///
+    /// Saturates on overflow, and NaN gives zero; see `try_from_hours_f64` to reject them.
+    pub fn from_hours_f64(hours: f64) -> Self {
+        WorkDuration((hours * 60.0).round() as i64)
+    }
+
+    /// Creates a duration from a fractional number of hours, rounded to the nearest minute.
+    ///
+    /// # Returns
+    /// `None` for NaN, infinity or a number of minutes that does not fit.
+    pub fn try_from_hours_f64(hours: f64) -> Option<Self> {
+        minutes_from_f64(hours * 60.0).map(WorkDuration)
+    }
+
+    /// Adds two durations, returning `None` on overflow.
+    pub const fn checked_add(self, rhs: WorkDuration) -> Option<Self> {
+        match self.0.checked_add(rhs.0) {
+            Some(minutes) => Some(WorkDuration(minutes)),
+            None => None,
+        }
+    }
+
+    /// Subtracts two durations, returning `None` on overflow.
+    pub const fn checked_sub(self, rhs: WorkDuration) -> Option<Self> {
+        match self.0.checked_sub(rhs.0) {
+            Some(minutes) => Some(WorkDuration(minutes)),
+            None => None,
+        }
+    }
+
+    /// Multiplies a duration, returning `None` on overflow.
+    pub const fn checked_mul(self, rhs: i64) -> Option<Self> {
+        match self.0.checked_mul(rhs) {
+            Some(minutes) => Some(WorkDuration(minutes)),
+            None => None,
+        }
+    }
+
+    /// Returns the duration in minutes.
+    pub const fn minutes(&self) -> i64 {
+        self.0
+    }
+
+    /// Returns the duration in fractional hours.
+    pub fn hours(&self) -> f64 {
+        self.0 as f64 / 60.0
+    }
+
+    /// Returns `true` if the duration is zero.
+    pub const fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Formats the duration as decimal hours with two decimals, e.g. `1.50`.
+    pub fn to_decimal_hours_string(&self) -> String {
+        format!("{:.2}", self.hours())
+    }
+}
+
+/// Formats the duration as `h:mm`, e.g. `1:30` or `-0:15`.
+impl fmt::Display for WorkDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let minutes = self.0.unsigned_abs();
+        write!(f, "{}{}:{:02}", sign, minutes / 60, minutes % 60)
+    }
+}
+
+/// Parses `h:mm` (`1:30`), hours (`1.5h`, `1,5h`), minutes (`90m`), combined (`1h 30m`)
+/// and plain numbers, which are read as minutes.
+impl FromStr for WorkDuration {
+    type Err = ClientError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ClientError::Message(format!("Invalid duration: '{}'", s));
+        let trimmed = s.trim();
+        let (negative, unsigned) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest.trim_start()),
+            None => (false, trimmed),
+        };
+        if unsigned.is_empty() || unsigned.contains(['-', '+']) {
+            return Err(invalid());
+        }
+
+        let minutes = if let Some((hours, minutes)) = unsigned.split_once(':') {
+            let hours: i64 = hours.trim().parse().map_err(|_| invalid())?;
+            let minutes: i64 = minutes.trim().parse().map_err(|_| invalid())?;
+            if minutes >= 60 || hours < 0 || minutes < 0 {
+                return Err(invalid());
+            }
+            hours
+                .checked_mul(60)
+                .and_then(|hours| hours.checked_add(minutes))
+                .ok_or_else(invalid)?
+        } else {
+            parse_unit_parts(unsigned).ok_or_else(invalid)?
+        };
+
+        Ok(WorkDuration(if negative { -minutes } else { minutes }))
+    }
+}
+
+/// Parses a sequence of `<number><unit>` parts such as `1.5h`, `90m` or `1h 30m`.
+/// A lone number without unit is read as minutes.
+fn parse_unit_parts(s: &str) -> Option<i64> {
+    let compact: String = s
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| {
+            if c == ',' {
+                '.'
+            } else {
+                c.to_ascii_lowercase()
+            }
+        })
+        .collect();
+    if let Ok(minutes) = compact.parse::<i64>() {
+        return Some(minutes);
+    }
+
+    let mut total = 0.0;
+    let mut rest = compact.as_str();
+    while !rest.is_empty() {
+        let number_end = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let (number, tail) = rest.split_at(number_end);
+        let value: f64 = number.parse().ok()?;
+        let unit_end = tail
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_end);
+        total += match unit {
+            "h" | "hr" | "hrs" | "hour" | "hours" => value * 60.0,
+            "m" | "min" | "mins" | "minute" | "minutes" => value,
+            _ => return None,
+        };
+        rest = tail;
+    }
+    minutes_from_f64(total)
+}
+
+/// Rounds a number of minutes, or returns `None` if it is not finite or does not fit.
+fn minutes_from_f64(minutes: f64) -> Option<i64> {
+    // i64::MAX as f64 rounds up to 2^63, the first value that does not fit
+    (minutes.is_finite() && minutes.abs() < i64::MAX as f64).then(|| minutes.round() as i64)
+}
+
+/// Clamps to `chrono::Duration::MIN` or `MAX` beyond their range of about ±1.5e14 minutes.
+impl From<WorkDuration> for chrono::Duration {
+    fn from(duration: WorkDuration) -> Self {
+        chrono::Duration::try_minutes(duration.0).unwrap_or(if duration.0 < 0 {
+            chrono::Duration::MIN
+        } else {
+            chrono::Duration::MAX
+        })
+    }
+}
+
+/// Truncates to whole minutes.
+impl From<chrono::Duration> for WorkDuration {
+    fn from(duration: chrono::Duration) -> Self {
+        WorkDuration(duration.num_minutes())
+    }
+}
+
+impl From<u32> for WorkDuration {
+    fn from(minutes: u32) -> Self {
+        WorkDuration(i64::from(minutes))
+    }
+}
+
+impl From<i32> for WorkDuration {
+    fn from(minutes: i32) -> Self {
+        WorkDuration(i64::from(minutes))
+    }
+}
+
+impl Add for WorkDuration {
+    type Output = WorkDuration;
+
+    fn add(self, rhs: WorkDuration) -> Self::Output {
+        WorkDuration(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl AddAssign for WorkDuration {
+    fn add_assign(&mut self, rhs: WorkDuration) {
+        self.0 = self.0.saturating_add(rhs.0);
+    }
+}
+
+impl Sub for WorkDuration {
+    type Output = WorkDuration;
+
+    fn sub(self, rhs: WorkDuration) -> Self::Output {
+        WorkDuration(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl SubAssign for WorkDuration {
+    fn sub_assign(&mut self, rhs: WorkDuration) {
+        self.0 = self.0.saturating_sub(rhs.0);
+    }
+}
+
+impl Mul<i64> for WorkDuration {
+    type Output = WorkDuration;
+
+    fn mul(self, rhs: i64) -> Self::Output {
+        WorkDuration(self.0.saturating_mul(rhs))
+    }
+}
+
+impl Neg for WorkDuration {
+    type Output = WorkDuration;
+
+    fn neg(self) -> Self::Output {
+        WorkDuration(self.0.saturating_neg())
+    }
+}
+
+impl Sum for WorkDuration {
+    fn sum<I: Iterator<Item = WorkDuration>>(iter: I) -> Self {
+        iter.fold(WorkDuration::ZERO, |total, duration| total + duration)
+    }
+}
+
+impl<'a> Sum<&'a WorkDuration> for WorkDuration {
+    fn sum<I: Iterator<Item = &'a WorkDuration>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Option<i64> {
+        s.parse::<WorkDuration>()
+            .ok()
+            .map(|duration| duration.minutes())
+    }
+
+    #[test]
+    fn parses_hours_and_minutes() {
+        assert_eq!(parse("1:30"), Some(90));
+        assert_eq!(parse(" 0:05 "), Some(5));
+        assert_eq!(parse("10:00"), Some(600));
+        assert_eq!(parse("1:60"), None);
+        assert_eq!(parse("1:-5"), None);
+        assert_eq!(parse(":30"), None);
+    }
+
+    #[test]
+    fn parses_units() {
+        assert_eq!(parse("1h 30m"), Some(90));
+        assert_eq!(parse("1H30M"), Some(90));
+        assert_eq!(parse("2 hours 15 min"), Some(135));
+        assert_eq!(parse("90m"), Some(90));
+        assert_eq!(parse("1.5h"), Some(90));
+        assert_eq!(parse("1,25h"), Some(75));
+        assert_eq!(parse("0.01h"), Some(1));
+        assert_eq!(parse("1d"), None);
+        assert_eq!(parse("h"), None);
+    }
+
+    #[test]
+    fn parses_plain_minutes_and_signs() {
+        assert_eq!(parse("45"), Some(45));
+        assert_eq!(parse("-45"), Some(-45));
+        assert_eq!(parse("-1:15"), Some(-75));
+        assert_eq!(parse("- 2h"), Some(-120));
+        assert_eq!(parse("-"), None);
+        assert_eq!(parse("--5"), None);
+        assert_eq!(parse("-+5"), None);
+        assert_eq!(parse("+5"), None);
+        assert_eq!(parse("1:+30"), None);
+        assert_eq!(parse("-1:-30"), None);
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("  "), None);
+    }
+
+    #[test]
+    fn displays_as_hours_and_minutes() {
+        assert_eq!(WorkDuration::from_minutes(90).to_string(), "1:30");
+        assert_eq!(WorkDuration::from_minutes(-15).to_string(), "-0:15");
+        assert_eq!(
+            WorkDuration::from_minutes(5).to_decimal_hours_string(),
+            "0.08"
+        );
+    }
+
+    #[test]
+    fn rejects_overflowing_input() {
+        assert!("999999999999999999:00".parse::<WorkDuration>().is_err());
+        assert!("99999999999999999:00".parse::<WorkDuration>().is_ok());
+        assert!("99999999999999999999h".parse::<WorkDuration>().is_err());
+        assert!("1e400h".parse::<WorkDuration>().is_err());
+        assert_eq!(WorkDuration::try_from_hours_f64(f64::INFINITY), None);
+        assert_eq!(WorkDuration::try_from_hours_f64(f64::NAN), None);
+        assert_eq!(
+            WorkDuration::try_from_hours_f64(1.5),
+            Some(WorkDuration::from_minutes(90))
+        );
+    }
+
+    #[test]
+    fn converts_to_chrono_without_overflow() {
+        let minutes = chrono::Duration::from(WorkDuration::from_minutes(90));
+        assert_eq!(minutes, chrono::Duration::minutes(90));
+        let max = chrono::Duration::from(WorkDuration::from_minutes(i64::MAX));
+        assert_eq!(max, chrono::Duration::MAX);
+        let min = chrono::Duration::from(WorkDuration::from_minutes(i64::MIN));
+        assert_eq!(min, chrono::Duration::MIN);
+    }
+
+    #[test]
+    fn arithmetic_saturates() {
+        let max = WorkDuration::from_minutes(i64::MAX);
+        assert_eq!(max + WorkDuration::from_minutes(1), max);
+        assert_eq!(max * 2, max);
+        assert_eq!(WorkDuration::from_hours(i64::MAX), max);
+        assert_eq!(max.checked_add(WorkDuration::from_minutes(1)), None);
+        assert_eq!(max.checked_mul(2), None);
+        assert_eq!(
+            WorkDuration::from_minutes(i64::MIN).checked_sub(WorkDuration::from_minutes(1)),
+            None
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::payload::PayloadTrait;
+use crate::payload::{ExtraFields, PayloadTrait, enums::WorkflowStatusType};
 
 /// Workflow status model representing a status in a workflow
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,12 +10,15 @@ pub struct WorkflowStatus {
     pub id: i32,
     /// Name of the workflow status
     pub name: String,
-    /// Status
+    /// Type of the workflow status (open or completed)
     #[serde(skip_serializing_if = "Option::is_none", rename = "type")]
-    pub status: Option<String>,
+    pub status: Option<WorkflowStatusType>,
     /// Allowed actions for this workflow status
     #[serde(skip_serializing_if = "Option::is_none")]
     pub allowed_actions: Option<Vec<String>>,
+    /// Fields returned by the API that are not modelled above, sent back unchanged
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 impl PayloadTrait for WorkflowStatus {
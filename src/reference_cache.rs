@@ -0,0 +1,396 @@
+//! Cache of slow-changing reference data shared by every clone of a `ClientContext`.
+//!
+//! Customers, projects, types of work, workflow statuses, leave types and departments are
+//! fetched in full through their list routes and kept for a time-to-live per kind (15 minutes
+//! by default). Every non-GET call to a route of a kind, successful or not, invalidates that kind,
+//! including the non-GET items of a `/batch` call.
+
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::payload::{
+    Customer, Department, LeaveType, PaginationListing, Project, TypeOfWork, WorkflowStatus,
+};
+use crate::routes::{
+    GetCustomersParameters, GetDepartmentsParameters, GetLeaveTypesParameters,
+    GetProjectsParameters, GetTypesOfWorkParameters, GetWorkflowStatusesParameters, Route,
+    get_customers, get_departments, get_leave_types, get_projects, get_types_of_work,
+    get_workflow_statuses,
+};
+
+/// Time-to-live of every kind until changed with `ClientContext::set_reference_ttl`
+pub const DEFAULT_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Kind of reference data held in the cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ReferenceDataKind {
+    /// Customers
+    Customer,
+    /// Projects
+    Project,
+    /// Types of work
+    TypeOfWork,
+    /// Workflow statuses
+    WorkflowStatus,
+    /// Leave types
+    LeaveType,
+    /// Departments
+    Department,
+}
+
+/// Payloads that can be cached as reference data
+pub trait ReferenceData: Clone + Serialize + DeserializeOwned + Send + Sync + 'static {
+    /// Kind the payload is cached under
+    const KIND: ReferenceDataKind;
+
+    /// Returns the unique identifier of the entity.
+    fn id(&self) -> i32;
+
+    /// Returns the name of the entity.
+    fn name(&self) -> &str;
+
+    /// Builds the route listing one page of entities.
+    fn list_route(offset: u32, limit: u32) -> Route<(), PaginationListing<Self>>;
+}
+
+/// Usage statistics of one kind of reference data
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups answered from the cache
+    pub hits: u64,
+    /// Lookups that had to fetch the data
+    pub misses: u64,
+    /// Explicit or automatic invalidations
+    pub invalidations: u64,
+    /// Number of entities currently cached
+    pub cached_items: usize,
+    /// Time since the cached entities were fetched, if any are cached
+    pub age: Option<Duration>,
+}
+
+/// Cached entities of every kind, with their time-to-live and statistics
+pub(crate) struct ReferenceCache {
+    kinds: Mutex<HashMap<ReferenceDataKind, KindCache>>,
+}
+
+struct KindCache {
+    ttl: Duration,
+    entry: Option<CacheEntry>,
+    stats: CacheStats,
+    /// Bumped by every invalidation, so a fetch that raced with one is not stored
+    generation: u64,
+}
+
+struct CacheEntry {
+    fetched_at: Instant,
+    len: usize,
+    items: Arc<dyn Any + Send + Sync>,
+}
+
+impl ReferenceDataKind {
+    /// Every kind of reference data.
+    pub const ALL: [ReferenceDataKind; 6] = [
+        ReferenceDataKind::Customer,
+        ReferenceDataKind::Project,
+        ReferenceDataKind::TypeOfWork,
+        ReferenceDataKind::WorkflowStatus,
+        ReferenceDataKind::LeaveType,
+        ReferenceDataKind::Department,
+    ];
+
+    /// Returns the kind whose routes start with `path`, e.g. `/projects/12` is a project route.
+    pub fn for_path(path: &str) -> Option<Self> {
+        let collection = path
+            .trim_start_matches('/')
+            .split(['/', '?'])
+            .next()
+            .unwrap_or_default();
+        match collection {
+            "customers" => Some(ReferenceDataKind::Customer),
+            "projects" => Some(ReferenceDataKind::Project),
+            "typesOfWork" => Some(ReferenceDataKind::TypeOfWork),
+            "workflowStatuses" => Some(ReferenceDataKind::WorkflowStatus),
+            "leaveTypes" => Some(ReferenceDataKind::LeaveType),
+            "departments" => Some(ReferenceDataKind::Department),
+            _ => None,
+        }
+    }
+}
+
+impl ReferenceCache {
+    pub(crate) fn new() -> Self {
+        ReferenceCache {
+            kinds: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the cached entities of `T` if they are still fresh, counting a hit or a miss.
+    ///
+    /// # Returns
+    /// The entities, or on a miss the generation to pass to `store` once they are fetched.
+    pub(crate) fn get<T: ReferenceData>(&self) -> Result<Arc<Vec<T>>, u64> {
+        let mut kinds = self.lock();
+        let kind = kinds.entry(T::KIND).or_insert_with(KindCache::new);
+        let fresh = kind
+            .entry
+            .as_ref()
+            .filter(|entry| entry.fetched_at.elapsed() < kind.ttl)
+            .and_then(|entry| Arc::clone(&entry.items).downcast::<Vec<T>>().ok());
+        match fresh {
+            Some(items) => {
+                kind.stats.hits += 1;
+                Ok(items)
+            }
+            None => {
+                kind.stats.misses += 1;
+                Err(kind.generation)
+            }
+        }
+    }
+
+    /// Stores entities fetched after a miss, unless the kind was invalidated meanwhile.
+    ///
+    /// # Arguments
+    /// * `items` - The fetched entities.
+    /// * `generation` - The generation returned by `get` before the fetch started.
+    pub(crate) fn store<T: ReferenceData>(&self, items: Arc<Vec<T>>, generation: u64) {
+        let mut kinds = self.lock();
+        let kind = kinds.entry(T::KIND).or_insert_with(KindCache::new);
+        if kind.generation != generation {
+            return;
+        }
+        kind.entry = Some(CacheEntry {
+            fetched_at: Instant::now(),
+            len: items.len(),
+            items,
+        });
+    }
+
+    pub(crate) fn invalidate(&self, kind: ReferenceDataKind) {
+        let mut kinds = self.lock();
+        let kind = kinds.entry(kind).or_insert_with(KindCache::new);
+        kind.entry = None;
+        kind.generation += 1;
+        kind.stats.invalidations += 1;
+    }
+
+    pub(crate) fn set_ttl(&self, kind: ReferenceDataKind, ttl: Duration) {
+        self.lock().entry(kind).or_insert_with(KindCache::new).ttl = ttl;
+    }
+
+    pub(crate) fn stats(&self) -> BTreeMap<ReferenceDataKind, CacheStats> {
+        let kinds = self.lock();
+        ReferenceDataKind::ALL
+            .into_iter()
+            .map(|kind| {
+                let stats = kinds
+                    .get(&kind)
+                    .map_or_else(CacheStats::default, |cache| CacheStats {
+                        cached_items: cache.entry.as_ref().map_or(0, |entry| entry.len),
+                        age: cache.entry.as_ref().map(|entry| entry.fetched_at.elapsed()),
+                        ..cache.stats
+                    });
+                (kind, stats)
+            })
+            .collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<ReferenceDataKind, KindCache>> {
+        self.kinds.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl KindCache {
+    fn new() -> Self {
+        KindCache {
+            ttl: DEFAULT_TTL,
+            entry: None,
+            stats: CacheStats::default(),
+            generation: 0,
+        }
+    }
+}
+
+impl ReferenceData for Customer {
+    const KIND: ReferenceDataKind = ReferenceDataKind::Customer;
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn list_route(offset: u32, limit: u32) -> Route<(), PaginationListing<Self>> {
+        get_customers(Some(
+            GetCustomersParameters::new()
+                .with_offset(offset)
+                .with_limit(limit),
+        ))
+    }
+}
+
+impl ReferenceData for Project {
+    const KIND: ReferenceDataKind = ReferenceDataKind::Project;
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn list_route(offset: u32, limit: u32) -> Route<(), PaginationListing<Self>> {
+        get_projects(Some(
+            GetProjectsParameters::new()
+                .with_offset(offset)
+                .with_limit(limit),
+        ))
+    }
+}
+
+impl ReferenceData for TypeOfWork {
+    const KIND: ReferenceDataKind = ReferenceDataKind::TypeOfWork;
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn list_route(offset: u32, limit: u32) -> Route<(), PaginationListing<Self>> {
+        get_types_of_work(Some(
+            GetTypesOfWorkParameters::new()
+                .with_offset(offset)
+                .with_limit(limit),
+        ))
+    }
+}
+
+impl ReferenceData for WorkflowStatus {
+    const KIND: ReferenceDataKind = ReferenceDataKind::WorkflowStatus;
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn list_route(offset: u32, limit: u32) -> Route<(), PaginationListing<Self>> {
+        get_workflow_statuses(Some(
+            GetWorkflowStatusesParameters::new()
+                .with_offset(offset)
+                .with_limit(limit),
+        ))
+    }
+}
+
+impl ReferenceData for LeaveType {
+    const KIND: ReferenceDataKind = ReferenceDataKind::LeaveType;
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn list_route(offset: u32, limit: u32) -> Route<(), PaginationListing<Self>> {
+        get_leave_types(Some(GetLeaveTypesParameters {
+            offset: Some(offset),
+            limit: Some(limit),
+            type_ids: None,
+            name_filter: None,
+            contains_words: None,
+            balance: None,
+            archived: None,
+            sort_order: None,
+        }))
+    }
+}
+
+impl ReferenceData for Department {
+    const KIND: ReferenceDataKind = ReferenceDataKind::Department;
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn list_route(offset: u32, limit: u32) -> Route<(), PaginationListing<Self>> {
+        get_departments(Some(
+            GetDepartmentsParameters::new()
+                .with_offset(offset)
+                .with_limit(limit),
+        ))
+    }
+}
+
+/// Returns the entities named `name`, ignoring case, live ones before archived ones.
+pub(crate) fn find_by_name<'a, T: 'a>(
+    items: impl IntoIterator<Item = &'a T>,
+    name: &str,
+    name_and_archived: impl Fn(&T) -> (&str, bool),
+) -> Vec<&'a T> {
+    let mut matches: Vec<&T> = items
+        .into_iter()
+        .filter(|item| name_and_archived(item).0.eq_ignore_ascii_case(name))
+        .collect();
+    matches.sort_by_key(|item| name_and_archived(item).1);
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn customers() -> Result<Arc<Vec<Customer>>, serde_json::Error> {
+        let json = r#"[{"id":1,"name":"Acme","archived":false,"created":"2024-01-01","url":"u"}]"#;
+        serde_json::from_str(json).map(Arc::new)
+    }
+
+    #[test]
+    fn stores_after_miss() -> Result<(), serde_json::Error> {
+        let cache = ReferenceCache::new();
+        let generation = cache.get::<Customer>().err();
+        assert_eq!(generation, Some(0));
+        cache.store(customers()?, 0);
+        assert_eq!(cache.get::<Customer>().map(|items| items.len()), Ok(1));
+        Ok(())
+    }
+
+    #[test]
+    fn drops_fetch_raced_by_invalidation() -> Result<(), serde_json::Error> {
+        let cache = ReferenceCache::new();
+        let generation = cache.get::<Customer>().err().unwrap_or_default();
+        cache.invalidate(ReferenceDataKind::Customer);
+        cache.store(customers()?, generation);
+        assert!(cache.get::<Customer>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn finds_live_entities_by_name_first() {
+        let items = [("Acme", true), ("ACME", false), ("Other", false)];
+        assert_eq!(
+            find_by_name(items.iter(), "acme", |item| *item),
+            vec![&("ACME", false), &("Acme", true)]
+        );
+        assert!(find_by_name(items.iter(), "missing", |item| *item).is_empty());
+    }
+}
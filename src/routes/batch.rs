@@ -1,4 +1,8 @@
-use crate::payload::BatchRequestItem;
+use serde::{Deserialize, Serialize};
+
+use crate::payload::enums::Method;
+use crate::payload::{BatchRequestItem, BatchResultItem};
+use crate::routes::Route;
 
 pub enum PostBatchRequestsIncludeResponseBody {
     Always,
@@ -6,9 +10,43 @@ pub enum PostBatchRequestsIncludeResponseBody {
     Get,
 }
 
-// pub fn post_batch_requests(
-//     requests: Vec<Box<dyn BatchRequestItem>>,
-// ) -> Route<(), Vec<BatchResultItem>> {
-//     let url = format!("/leaveTypes/{}", id);
-//     Route::new(Method::GET, &url)
-// }
+impl PostBatchRequestsIncludeResponseBody {
+    /// Returns the query parameter value of the option.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PostBatchRequestsIncludeResponseBody::Always => "always",
+            PostBatchRequestsIncludeResponseBody::Never => "never",
+            PostBatchRequestsIncludeResponseBody::Get => "get",
+        }
+    }
+}
+
+/// Builds the route sending several requests in one call.
+///
+/// The server answers with one `BatchResultItem` per request, in request order. A failed
+/// request does not fail the batch; check `BatchResultItem.status`.
+///
+/// # Arguments
+/// * `include_response_body` - Which responses carry a body; the server default applies when `None`.
+///
+/// # Example
+/// ``` ignore
+/// let items: Vec<BatchRequestItem<EntityFields, TimeTrackRecord>> = records
+///     .map(|(user_id, date, task_id, fields)| BatchRequestItem::from(patch_time_track(user_id, date, task_id)).with_body(fields))
+///     .collect();
+/// let route = post_batch_requests(Some(PostBatchRequestsIncludeResponseBody::Never));
+/// let results = client_context.call_route(&route, Some(&items))?;
+/// ```
+pub fn post_batch_requests<BodyType, ResponseType>(
+    include_response_body: Option<PostBatchRequestsIncludeResponseBody>,
+) -> Route<Vec<BatchRequestItem<BodyType, ResponseType>>, Vec<BatchResultItem>>
+where
+    BodyType: Serialize + for<'de> Deserialize<'de>,
+    ResponseType: Serialize + for<'de> Deserialize<'de>,
+{
+    let url = match include_response_body {
+        Some(include) => format!("/batch?includeResponseBody={}", include.as_str()),
+        None => "/batch".to_string(),
+    };
+    Route::new(Method::POST, &url)
+}
@@ -0,0 +1,115 @@
+use crate::payload::{
+    CustomFieldDefinition, PaginationListing,
+    enums::{CustomFieldEntity, Method},
+};
+use crate::routes::Route;
+use crate::routes::route::query_pair;
+
+/// Query parameters for `get_custom_fields`
+#[derive(Default)]
+pub struct GetCustomFieldsParameters {
+    /// Index offset of the first item to return (for pagination)
+    pub offset: Option<u32>,
+    /// Maximum number of items to return (for pagination)
+    pub limit: Option<u32>,
+    /// Custom field IDs to filter by
+    pub field_ids: Option<Vec<i32>>,
+    /// Kind of entity the fields are defined for
+    pub entity: Option<CustomFieldEntity>,
+    /// Custom field name filter (partial match)
+    pub name_filter: Option<String>,
+}
+
+impl GetCustomFieldsParameters {
+    /// Creates an empty set of parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `offset` field and returns the modified parameters.
+    pub fn with_offset(mut self, offset: u32) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Sets the `limit` field and returns the modified parameters.
+    pub fn with_limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Sets the `field_ids` field and returns the modified parameters.
+    pub fn with_field_ids(mut self, field_ids: Vec<i32>) -> Self {
+        self.field_ids = Some(field_ids);
+        self
+    }
+
+    /// Sets the `entity` field and returns the modified parameters.
+    pub fn with_entity(mut self, entity: CustomFieldEntity) -> Self {
+        self.entity = Some(entity);
+        self
+    }
+
+    /// Sets the `name_filter` field and returns the modified parameters.
+    pub fn with_name_filter(mut self, name_filter: String) -> Self {
+        self.name_filter = Some(name_filter);
+        self
+    }
+}
+
+impl From<GetCustomFieldsParameters> for String {
+    fn from(parameters: GetCustomFieldsParameters) -> String {
+        let mut query_params: Vec<(&'static str, String)> = vec![];
+
+        if let Some(offset) = parameters.offset {
+            query_params.push(("offset", offset.to_string()));
+        } else {
+            query_params.push(("offset", "0".to_string()));
+        }
+        if let Some(limit) = parameters.limit {
+            query_params.push(("limit", limit.to_string()));
+        }
+        if let Some(field_ids) = parameters.field_ids {
+            let ids = field_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            query_params.push(("ids", ids));
+        }
+        if let Some(entity) = parameters.entity {
+            query_params.push(("entity", entity.to_string()));
+        }
+        if let Some(name_filter) = parameters.name_filter {
+            query_params.push(("name", name_filter));
+        }
+
+        query_params
+            .iter()
+            .map(|(key, value)| query_pair(key, value))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+/// Builds the route listing custom field definitions, filtered by `parameters`.
+///
+/// # Example
+/// ``` ignore
+/// let parameters = GetCustomFieldsParameters::new().with_entity(CustomFieldEntity::Task);
+/// let definitions = client_context.call_route(&get_custom_fields(Some(parameters)), None)?;
+/// ```
+pub fn get_custom_fields(
+    parameters: Option<GetCustomFieldsParameters>,
+) -> Route<(), PaginationListing<CustomFieldDefinition>> {
+    let mut url: String = "/customFields?".into();
+
+    if let Some(params) = parameters {
+        let query_string: String = params.into();
+        url.push_str(&query_string);
+    } else {
+        url.push_str("offset=0&limit=100");
+    }
+
+    Route::new(Method::GET, url.as_str())
+}
@@ -0,0 +1,142 @@
+use crate::payload::{Customer, EntityFields, PaginationListing, enums::Method};
+use crate::routes::Route;
+use crate::routes::route::query_pair;
+
+/// Query parameters for `get_customers`
+#[derive(Default)]
+pub struct GetCustomersParameters {
+    /// Index offset of the first item to return (for pagination)
+    pub offset: Option<u32>,
+    /// Maximum number of items to return (for pagination)
+    pub limit: Option<u32>,
+    /// Customer IDs to filter by
+    pub customer_ids: Option<Vec<i32>>,
+    /// Name filter (partial match)
+    pub name_filter: Option<String>,
+    /// Archived status to filter by
+    pub archived: Option<bool>,
+}
+
+impl GetCustomersParameters {
+    /// Creates an empty set of parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `offset` field and returns the modified parameters.
+    pub fn with_offset(mut self, offset: u32) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Sets the `limit` field and returns the modified parameters.
+    pub fn with_limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Sets the `customer_ids` field and returns the modified parameters.
+    pub fn with_customer_ids(mut self, customer_ids: Vec<i32>) -> Self {
+        self.customer_ids = Some(customer_ids);
+        self
+    }
+
+    /// Sets the `name_filter` field and returns the modified parameters.
+    pub fn with_name_filter(mut self, name_filter: String) -> Self {
+        self.name_filter = Some(name_filter);
+        self
+    }
+
+    /// Sets the `archived` field and returns the modified parameters.
+    pub fn with_archived(mut self, archived: bool) -> Self {
+        self.archived = Some(archived);
+        self
+    }
+}
+
+impl From<GetCustomersParameters> for String {
+    fn from(parameters: GetCustomersParameters) -> String {
+        let mut query_params: Vec<(&'static str, String)> = vec![];
+
+        if let Some(offset) = parameters.offset {
+            query_params.push(("offset", offset.to_string()));
+        } else {
+            query_params.push(("offset", "0".to_string()));
+        }
+        if let Some(limit) = parameters.limit {
+            query_params.push(("limit", limit.to_string()));
+        }
+        if let Some(customer_ids) = parameters.customer_ids {
+            let ids = customer_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            query_params.push(("ids", ids));
+        }
+        if let Some(name_filter) = parameters.name_filter {
+            query_params.push(("name", name_filter));
+        }
+        if let Some(archived) = parameters.archived {
+            query_params.push(("archived", archived.to_string()));
+        }
+
+        query_params
+            .iter()
+            .map(|(key, value)| query_pair(key, value))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+/// Builds the route listing customers, filtered by `parameters`.
+pub fn get_customers(
+    parameters: Option<GetCustomersParameters>,
+) -> Route<(), PaginationListing<Customer>> {
+    let mut url: String = "/customers?".into();
+
+    if let Some(params) = parameters {
+        let query_string: String = params.into();
+        url.push_str(&query_string);
+    } else {
+        url.push_str("offset=0&limit=100");
+    }
+
+    Route::new(Method::GET, url.as_str())
+}
+
+/// Builds the route creating a customer.
+///
+/// # Example
+/// ``` ignore
+/// let customer = EntityFields::new()
+///     .with_field("name", "Acme")?
+///     .with_custom_field(CustomFieldValue::text(BILLING_CODE_FIELD, "ACME-01"));
+/// let created = client_context.call_route(&post_customer(), Some(&customer))?;
+/// ```
+pub fn post_customer() -> Route<EntityFields, Customer> {
+    Route::new(Method::POST, "/customers")
+}
+
+/// Builds the route changing some fields of a customer.
+///
+/// # Arguments
+/// * `customer_id` - The identifier of the customer (`Customer.id`).
+pub fn patch_customer(customer_id: i32) -> Route<EntityFields, Customer> {
+    let url = format!("/customers/{}", customer_id);
+    Route::new(Method::PATCH, &url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_the_name_filter() {
+        let query: String = GetCustomersParameters::new()
+            .with_limit(10)
+            .with_name_filter("Smith & Sons #1".to_string())
+            .into();
+        assert_eq!(query, "offset=0&limit=10&name=Smith+%26+Sons+%231");
+    }
+}
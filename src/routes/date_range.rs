@@ -0,0 +1,78 @@
+use std::ops::{Range, RangeInclusive};
+
+use chrono::{NaiveDate, NaiveWeek};
+
+use crate::errors::ClientError;
+
+/// Conversion into an inclusive `(first day, last day)` pair used by date-range query parameters
+pub trait IntoDateRange {
+    /// Returns the first and the last day (inclusive) of the range.
+    fn into_date_range(self) -> (NaiveDate, NaiveDate);
+}
+
+impl IntoDateRange for NaiveDate {
+    fn into_date_range(self) -> (NaiveDate, NaiveDate) {
+        (self, self)
+    }
+}
+
+impl IntoDateRange for (NaiveDate, NaiveDate) {
+    fn into_date_range(self) -> (NaiveDate, NaiveDate) {
+        self
+    }
+}
+
+impl IntoDateRange for RangeInclusive<NaiveDate> {
+    fn into_date_range(self) -> (NaiveDate, NaiveDate) {
+        self.into_inner()
+    }
+}
+
+/// The end of a half-open range is excluded, so the last day is the one before `end`. An
+/// empty range such as `d..d` gives a first day after the last one.
+impl IntoDateRange for Range<NaiveDate> {
+    fn into_date_range(self) -> (NaiveDate, NaiveDate) {
+        match self.end.pred_opt() {
+            Some(last) => (self.start, last),
+            None => (NaiveDate::MAX, NaiveDate::MIN),
+        }
+    }
+}
+
+impl IntoDateRange for NaiveWeek {
+    fn into_date_range(self) -> (NaiveDate, NaiveDate) {
+        (self.first_day(), self.last_day())
+    }
+}
+
+/// Converts `dates` into a `(first day, last day)` pair, rejecting empty ranges such as `d..d`.
+pub(crate) fn checked_date_range(
+    dates: impl IntoDateRange,
+) -> Result<(NaiveDate, NaiveDate), ClientError> {
+    let (date_from, date_to) = dates.into_date_range();
+    if date_to < date_from {
+        return Err(ClientError::Message(format!(
+            "Empty date range: {} is after {}",
+            date_from, date_to
+        )));
+    }
+    Ok((date_from, date_to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_empty_date_ranges() {
+        let day = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap_or_default();
+        assert!(checked_date_range(day..day).is_err());
+        assert!(checked_date_range(NaiveDate::MIN..NaiveDate::MIN).is_err());
+        assert_eq!(checked_date_range(day..=day).ok(), Some((day, day)));
+        assert_eq!(
+            checked_date_range(day..day.succ_opt().unwrap_or_default()).ok(),
+            Some((day, day))
+        );
+        assert!(checked_date_range((day, day.pred_opt().unwrap_or_default())).is_err());
+    }
+}
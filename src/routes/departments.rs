@@ -0,0 +1,95 @@
+use crate::payload::{Department, PaginationListing, enums::Method};
+use crate::routes::Route;
+use crate::routes::route::query_pair;
+
+/// Query parameters for `get_departments`
+#[derive(Default)]
+pub struct GetDepartmentsParameters {
+    /// Index offset of the first item to return (for pagination)
+    pub offset: Option<u32>,
+    /// Maximum number of items to return (for pagination)
+    pub limit: Option<u32>,
+    /// Department IDs to filter by
+    pub department_ids: Option<Vec<i32>>,
+    /// Name filter (partial match)
+    pub name_filter: Option<String>,
+}
+
+impl GetDepartmentsParameters {
+    /// Creates an empty set of parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `offset` field and returns the modified parameters.
+    pub fn with_offset(mut self, offset: u32) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Sets the `limit` field and returns the modified parameters.
+    pub fn with_limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Sets the `department_ids` field and returns the modified parameters.
+    pub fn with_department_ids(mut self, department_ids: Vec<i32>) -> Self {
+        self.department_ids = Some(department_ids);
+        self
+    }
+
+    /// Sets the `name_filter` field and returns the modified parameters.
+    pub fn with_name_filter(mut self, name_filter: String) -> Self {
+        self.name_filter = Some(name_filter);
+        self
+    }
+}
+
+impl From<GetDepartmentsParameters> for String {
+    fn from(parameters: GetDepartmentsParameters) -> String {
+        let mut query_params: Vec<(&'static str, String)> = vec![];
+
+        if let Some(offset) = parameters.offset {
+            query_params.push(("offset", offset.to_string()));
+        } else {
+            query_params.push(("offset", "0".to_string()));
+        }
+        if let Some(limit) = parameters.limit {
+            query_params.push(("limit", limit.to_string()));
+        }
+        if let Some(department_ids) = parameters.department_ids {
+            let ids = department_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            query_params.push(("ids", ids));
+        }
+        if let Some(name_filter) = parameters.name_filter {
+            query_params.push(("name", name_filter));
+        }
+
+        query_params
+            .iter()
+            .map(|(key, value)| query_pair(key, value))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+/// Builds the route listing departments, filtered by `parameters`.
+pub fn get_departments(
+    parameters: Option<GetDepartmentsParameters>,
+) -> Route<(), PaginationListing<Department>> {
+    let mut url: String = "/departments?".into();
+
+    if let Some(params) = parameters {
+        let query_string: String = params.into();
+        url.push_str(&query_string);
+    } else {
+        url.push_str("offset=0&limit=100");
+    }
+
+    Route::new(Method::GET, url.as_str())
+}
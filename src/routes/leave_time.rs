@@ -0,0 +1,114 @@
+use chrono::NaiveDate;
+
+use crate::payload::{LeaveTime, enums::Method};
+use crate::routes::route::query_pair;
+use crate::routes::{IntoDateRange, Route};
+
+/// Query parameters for `get_leave_time`
+#[derive(Debug, Clone, Default)]
+pub struct GetLeaveTimeParameters {
+    /// First day to return
+    pub date_from: Option<NaiveDate>,
+    /// Last day to return (inclusive)
+    pub date_to: Option<NaiveDate>,
+    /// User IDs to filter by
+    pub user_ids: Option<Vec<i32>>,
+    /// Leave type IDs to filter by
+    pub leave_type_ids: Option<Vec<i32>>,
+    /// Number of records after which the server stops and returns `nextDateFrom`
+    pub stop_after: Option<u32>,
+}
+
+impl GetLeaveTimeParameters {
+    /// Creates an empty set of parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `date_from` and `date_to` fields and returns the modified parameters.
+    ///
+    /// # Arguments
+    /// * `dates` - A single `NaiveDate`, a `(from, to)` pair, a `from..=to` or `from..to` range or a `NaiveWeek`.
+    pub fn with_dates(mut self, dates: impl IntoDateRange) -> Self {
+        let (date_from, date_to) = dates.into_date_range();
+        self.date_from = Some(date_from);
+        self.date_to = Some(date_to);
+        self
+    }
+
+    /// Sets the `user_ids` field and returns the modified parameters.
+    pub fn with_user_ids(mut self, user_ids: Vec<i32>) -> Self {
+        self.user_ids = Some(user_ids);
+        self
+    }
+
+    /// Sets the `leave_type_ids` field and returns the modified parameters.
+    pub fn with_leave_type_ids(mut self, leave_type_ids: Vec<i32>) -> Self {
+        self.leave_type_ids = Some(leave_type_ids);
+        self
+    }
+
+    /// Sets the `stop_after` field and returns the modified parameters.
+    pub fn with_stop_after(mut self, stop_after: u32) -> Self {
+        self.stop_after = Some(stop_after);
+        self
+    }
+}
+
+impl From<GetLeaveTimeParameters> for String {
+    fn from(parameters: GetLeaveTimeParameters) -> String {
+        let mut query_params: Vec<(&'static str, String)> = vec![];
+        let join_ids = |ids: Vec<i32>| {
+            ids.iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        if let Some(date_from) = parameters.date_from {
+            query_params.push(("dateFrom", date_from.format("%Y-%m-%d").to_string()));
+        }
+        if let Some(date_to) = parameters.date_to {
+            query_params.push(("dateTo", date_to.format("%Y-%m-%d").to_string()));
+        }
+        if let Some(user_ids) = parameters.user_ids {
+            query_params.push(("userIds", join_ids(user_ids)));
+        }
+        if let Some(leave_type_ids) = parameters.leave_type_ids {
+            query_params.push(("leaveTypeIds", join_ids(leave_type_ids)));
+        }
+        if let Some(stop_after) = parameters.stop_after {
+            query_params.push(("stopAfter", stop_after.to_string()));
+        }
+
+        query_params
+            .iter()
+            .map(|(key, value)| query_pair(key, value))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+/// Builds the route returning the leave time over a date range, filtered by `parameters`.
+///
+/// The server may stop early and set `LeaveTime.next_date_from`; see
+/// `ClientContext::get_complete_leave_time` to follow it.
+///
+/// # Example
+/// ``` ignore
+/// let parameters = GetLeaveTimeParameters::new().with_dates(from..=to);
+/// let leave_time = client_context.call_route(&get_leave_time(Some(parameters)), None)?;
+/// ```
+pub fn get_leave_time(parameters: Option<GetLeaveTimeParameters>) -> Route<(), LeaveTime> {
+    let mut url: String = "/leavetime".into();
+
+    if let Some(params) = parameters {
+        let query_string: String = params.into();
+        if !query_string.is_empty() {
+            url.push('?');
+            url.push_str(&query_string);
+        }
+    }
+
+    Route::new(Method::GET, url.as_str())
+}
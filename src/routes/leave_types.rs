@@ -3,6 +3,7 @@ use crate::payload::{
     enums::{LeaveTypeBalance, Method},
 };
 use crate::routes::Route;
+use crate::routes::route::query_pair;
 
 pub enum GetLeaveTypesSortOrder {
     NameAsc,
@@ -64,7 +65,7 @@ impl Into<String> for GetLeaveTypesParameters {
             query_params.push(("words", contains_words));
         }
         if let Some(balance) = self.balance {
-            query_params.push(("balance", format!("{:?}", balance)));
+            query_params.push(("balance", balance.to_string()));
         }
         if let Some(archived) = self.archived {
             query_params.push(("archived", archived.to_string()));
@@ -75,7 +76,7 @@ impl Into<String> for GetLeaveTypesParameters {
 
         let query_string = query_params
             .iter()
-            .map(|(key, value)| format!("{}={}", key, value))
+            .map(|(key, value)| query_pair(key, value))
             .collect::<Vec<_>>()
             .join("&");
         query_string
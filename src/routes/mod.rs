@@ -1,13 +1,39 @@
 mod batch;
+mod custom_fields;
+mod customers;
+mod date_range;
+mod departments;
+mod leave_time;
 mod leave_types;
+mod projects;
 mod route;
 mod tasks;
+mod time_track;
+mod types_of_work;
 mod users;
+mod workflow_statuses;
 
-pub use batch::PostBatchRequestsIncludeResponseBody;
+pub use batch::{PostBatchRequestsIncludeResponseBody, post_batch_requests};
+pub use custom_fields::{GetCustomFieldsParameters, get_custom_fields};
+pub use customers::{GetCustomersParameters, get_customers, patch_customer, post_customer};
+pub use date_range::IntoDateRange;
+pub(crate) use date_range::checked_date_range;
+pub use departments::{GetDepartmentsParameters, get_departments};
+pub use leave_time::{GetLeaveTimeParameters, get_leave_time};
 pub use leave_types::{
     GetLeaveTypesParameters, GetLeaveTypesSortOrder, get_leave_type_by_id, get_leave_types,
 };
+pub use projects::{GetProjectsParameters, get_projects, patch_project, post_project};
 pub use route::Route;
-pub use tasks::{GetTasksIncludeReferenced, GetTasksParameters, GetTasksSortOrder, get_tasks};
-pub use users::{get_users_me, get_users_schedule};
+pub use tasks::{
+    GetTasksIncludeReferenced, GetTasksParameters, GetTasksSortOrder, get_task, get_tasks,
+    patch_task, post_task,
+};
+pub use time_track::{GetTimeTrackParameters, get_time_track, patch_time_track};
+pub use types_of_work::{
+    GetTypesOfWorkParameters, get_types_of_work, patch_type_of_work, post_type_of_work,
+};
+pub use users::{
+    GetUsersParameters, GetUsersSortOrder, get_users, get_users_me, get_users_schedule,
+};
+pub use workflow_statuses::{GetWorkflowStatusesParameters, get_workflow_statuses};
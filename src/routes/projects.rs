@@ -0,0 +1,145 @@
+use crate::payload::{EntityFields, PaginationListing, Project, enums::Method};
+use crate::routes::Route;
+use crate::routes::route::query_pair;
+
+/// Query parameters for `get_projects`
+#[derive(Default)]
+pub struct GetProjectsParameters {
+    /// Index offset of the first item to return (for pagination)
+    pub offset: Option<u32>,
+    /// Maximum number of items to return (for pagination)
+    pub limit: Option<u32>,
+    /// Project IDs to filter by
+    pub project_ids: Option<Vec<i32>>,
+    /// Customer IDs to filter by
+    pub customer_ids: Option<Vec<i32>>,
+    /// Name filter (partial match)
+    pub name_filter: Option<String>,
+    /// Archived status to filter by
+    pub archived: Option<bool>,
+}
+
+impl GetProjectsParameters {
+    /// Creates an empty set of parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `offset` field and returns the modified parameters.
+    pub fn with_offset(mut self, offset: u32) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Sets the `limit` field and returns the modified parameters.
+    pub fn with_limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Sets the `project_ids` field and returns the modified parameters.
+    pub fn with_project_ids(mut self, project_ids: Vec<i32>) -> Self {
+        self.project_ids = Some(project_ids);
+        self
+    }
+
+    /// Sets the `customer_ids` field and returns the modified parameters.
+    pub fn with_customer_ids(mut self, customer_ids: Vec<i32>) -> Self {
+        self.customer_ids = Some(customer_ids);
+        self
+    }
+
+    /// Sets the `name_filter` field and returns the modified parameters.
+    pub fn with_name_filter(mut self, name_filter: String) -> Self {
+        self.name_filter = Some(name_filter);
+        self
+    }
+
+    /// Sets the `archived` field and returns the modified parameters.
+    pub fn with_archived(mut self, archived: bool) -> Self {
+        self.archived = Some(archived);
+        self
+    }
+}
+
+impl From<GetProjectsParameters> for String {
+    fn from(parameters: GetProjectsParameters) -> String {
+        let mut query_params: Vec<(&'static str, String)> = vec![];
+
+        if let Some(offset) = parameters.offset {
+            query_params.push(("offset", offset.to_string()));
+        } else {
+            query_params.push(("offset", "0".to_string()));
+        }
+        if let Some(limit) = parameters.limit {
+            query_params.push(("limit", limit.to_string()));
+        }
+        if let Some(project_ids) = parameters.project_ids {
+            let ids = project_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            query_params.push(("ids", ids));
+        }
+        if let Some(customer_ids) = parameters.customer_ids {
+            let ids = customer_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            query_params.push(("customerIds", ids));
+        }
+        if let Some(name_filter) = parameters.name_filter {
+            query_params.push(("name", name_filter));
+        }
+        if let Some(archived) = parameters.archived {
+            query_params.push(("archived", archived.to_string()));
+        }
+
+        query_params
+            .iter()
+            .map(|(key, value)| query_pair(key, value))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+/// Builds the route listing projects, filtered by `parameters`.
+pub fn get_projects(
+    parameters: Option<GetProjectsParameters>,
+) -> Route<(), PaginationListing<Project>> {
+    let mut url: String = "/projects?".into();
+
+    if let Some(params) = parameters {
+        let query_string: String = params.into();
+        url.push_str(&query_string);
+    } else {
+        url.push_str("offset=0&limit=100");
+    }
+
+    Route::new(Method::GET, url.as_str())
+}
+
+/// Builds the route creating a project.
+///
+/// # Example
+/// ``` ignore
+/// let project = EntityFields::new()
+///     .with_field("customerId", customer_id)?
+///     .with_field("name", "Website relaunch")?
+///     .with_custom_field(CustomFieldValue::text(BILLING_CODE_FIELD, "B-17"));
+/// let created = client_context.call_route(&post_project(), Some(&project))?;
+/// ```
+pub fn post_project() -> Route<EntityFields, Project> {
+    Route::new(Method::POST, "/projects")
+}
+
+/// Builds the route changing some fields of a project.
+///
+/// # Arguments
+/// * `project_id` - The identifier of the project (`Project.id`).
+pub fn patch_project(project_id: i32) -> Route<EntityFields, Project> {
+    let url = format!("/projects/{}", project_id);
+    Route::new(Method::PATCH, &url)
+}
@@ -3,6 +3,13 @@ use std::marker::PhantomData;
 
 use crate::payload::enums::Method;
 
+/// Formats one `key=value` pair of a query string, percent-encoding both sides.
+pub(crate) fn query_pair(key: &str, value: &str) -> String {
+    form_urlencoded::Serializer::new(String::new())
+        .append_pair(key, value)
+        .finish()
+}
+
 pub struct Route<PayloadType, ResponseType>
 where
     PayloadType: Serialize + for<'de> Deserialize<'de>,
@@ -27,4 +34,22 @@ where
             _response_type: PhantomData,
         }
     }
+
+    /// Returns the path without the query string, with numeric ids replaced by `{id}`.
+    ///
+    /// Unlike `relative_path`, the template contains no user data, so it can be used to
+    /// label logs and metrics, e.g. `/users/{id}/schedule`.
+    pub fn template(&self) -> String {
+        let path = self.relative_path.split('?').next().unwrap_or_default();
+        path.split('/')
+            .map(|segment| {
+                if !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit()) {
+                    "{id}"
+                } else {
+                    segment
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
 }
@@ -1,9 +1,11 @@
 use crate::payload::{
-    TaskList,
+    EntityFields, Task, TaskList,
     enums::{Method, TaskStatus},
 };
 use crate::routes::Route;
+use crate::routes::route::query_pair;
 
+#[derive(Clone)]
 pub enum GetTasksSortOrder {
     CreatedAsc,
     CreatedDesc,
@@ -26,6 +28,7 @@ impl GetTasksSortOrder {
     }
 }
 
+#[derive(Clone)]
 pub enum GetTasksIncludeReferenced {
     Customers,
     Projects,
@@ -33,6 +36,7 @@ pub enum GetTasksIncludeReferenced {
     WorkflowStatuses,
 }
 
+#[derive(Clone)]
 pub struct GetTasksParameters {
     /// Index offset of the first item to return (for pagination)
     pub offset: Option<u32>,
@@ -204,11 +208,7 @@ impl Into<String> for GetTasksParameters {
             query_params.push(("words", contains_words));
         }
         if let Some(status) = self.status {
-            let status_str = match status {
-                TaskStatus::Open => "open",
-                TaskStatus::Completed => "completed",
-            };
-            query_params.push(("status", status_str.to_string()));
+            query_params.push(("status", status.to_string()));
         }
         if let Some(include_referenced) = self.include_referenced {
             let includes = include_referenced
@@ -226,7 +226,7 @@ impl Into<String> for GetTasksParameters {
 
         let query_string = query_params
             .iter()
-            .map(|(key, value)| format!("{}={}", key, value))
+            .map(|(key, value)| query_pair(key, value))
             .collect::<Vec<_>>()
             .join("&");
         query_string
@@ -245,3 +245,34 @@ pub fn get_tasks(parameters: Option<GetTasksParameters>) -> Route<(), TaskList>
 
     Route::new(Method::GET, url.as_str())
 }
+
+/// Builds the route fetching one task.
+///
+/// # Arguments
+/// * `task_id` - The identifier of the task (`Task.id`).
+pub fn get_task(task_id: i32) -> Route<(), Task> {
+    let url = format!("/tasks/{}", task_id);
+    Route::new(Method::GET, &url)
+}
+
+/// Builds the route creating a task, custom field values included.
+///
+/// # Example
+/// ``` ignore
+/// let task = Task::new("Design review".to_string())
+///     .with_project_id(project_id)
+///     .with_custom_field(CustomFieldValue::text(BILLING_CODE_FIELD, "B-17"));
+/// let created = client_context.call_route(&post_task(), Some(&task))?;
+/// ```
+pub fn post_task() -> Route<Task, Task> {
+    Route::new(Method::POST, "/tasks")
+}
+
+/// Builds the route changing some fields of a task.
+///
+/// # Arguments
+/// * `task_id` - The identifier of the task (`Task.id`).
+pub fn patch_task(task_id: i32) -> Route<EntityFields, Task> {
+    let url = format!("/tasks/{}", task_id);
+    Route::new(Method::PATCH, &url)
+}
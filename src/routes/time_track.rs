@@ -0,0 +1,164 @@
+use chrono::NaiveDate;
+
+use crate::payload::{EntityFields, TimeTrack, TimeTrackRecord, enums::Method};
+use crate::routes::route::query_pair;
+use crate::routes::{IntoDateRange, Route};
+
+/// Query parameters for `get_time_track`
+#[derive(Debug, Clone, Default)]
+pub struct GetTimeTrackParameters {
+    /// First day to return
+    pub date_from: Option<NaiveDate>,
+    /// Last day to return (inclusive)
+    pub date_to: Option<NaiveDate>,
+    /// User IDs to filter by
+    pub user_ids: Option<Vec<i32>>,
+    /// Task IDs to filter by
+    pub task_ids: Option<Vec<i32>>,
+    /// Project IDs to filter by
+    pub project_ids: Option<Vec<i32>>,
+    /// Customer IDs to filter by
+    pub customer_ids: Option<Vec<i32>>,
+    /// Number of records after which the server stops and returns `nextDateFrom`
+    pub stop_after: Option<u32>,
+}
+
+impl GetTimeTrackParameters {
+    /// Creates an empty set of parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `date_from` and `date_to` fields and returns the modified parameters.
+    ///
+    /// # Arguments
+    /// * `dates` - A single `NaiveDate`, a `(from, to)` pair, a `from..=to` or `from..to` range or a `NaiveWeek`.
+    pub fn with_dates(mut self, dates: impl IntoDateRange) -> Self {
+        let (date_from, date_to) = dates.into_date_range();
+        self.date_from = Some(date_from);
+        self.date_to = Some(date_to);
+        self
+    }
+
+    /// Sets the `user_ids` field and returns the modified parameters.
+    pub fn with_user_ids(mut self, user_ids: Vec<i32>) -> Self {
+        self.user_ids = Some(user_ids);
+        self
+    }
+
+    /// Sets the `task_ids` field and returns the modified parameters.
+    pub fn with_task_ids(mut self, task_ids: Vec<i32>) -> Self {
+        self.task_ids = Some(task_ids);
+        self
+    }
+
+    /// Sets the `project_ids` field and returns the modified parameters.
+    pub fn with_project_ids(mut self, project_ids: Vec<i32>) -> Self {
+        self.project_ids = Some(project_ids);
+        self
+    }
+
+    /// Sets the `customer_ids` field and returns the modified parameters.
+    pub fn with_customer_ids(mut self, customer_ids: Vec<i32>) -> Self {
+        self.customer_ids = Some(customer_ids);
+        self
+    }
+
+    /// Sets the `stop_after` field and returns the modified parameters.
+    pub fn with_stop_after(mut self, stop_after: u32) -> Self {
+        self.stop_after = Some(stop_after);
+        self
+    }
+}
+
+impl From<GetTimeTrackParameters> for String {
+    fn from(parameters: GetTimeTrackParameters) -> String {
+        let mut query_params: Vec<(&'static str, String)> = vec![];
+        let join_ids = |ids: Vec<i32>| {
+            ids.iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        if let Some(date_from) = parameters.date_from {
+            query_params.push(("dateFrom", date_from.format("%Y-%m-%d").to_string()));
+        }
+        if let Some(date_to) = parameters.date_to {
+            query_params.push(("dateTo", date_to.format("%Y-%m-%d").to_string()));
+        }
+        if let Some(user_ids) = parameters.user_ids {
+            query_params.push(("userIds", join_ids(user_ids)));
+        }
+        if let Some(task_ids) = parameters.task_ids {
+            query_params.push(("taskIds", join_ids(task_ids)));
+        }
+        if let Some(project_ids) = parameters.project_ids {
+            query_params.push(("projectIds", join_ids(project_ids)));
+        }
+        if let Some(customer_ids) = parameters.customer_ids {
+            query_params.push(("customerIds", join_ids(customer_ids)));
+        }
+        if let Some(stop_after) = parameters.stop_after {
+            query_params.push(("stopAfter", stop_after.to_string()));
+        }
+
+        query_params
+            .iter()
+            .map(|(key, value)| query_pair(key, value))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+/// Builds the route returning the time tracked over a date range, filtered by `parameters`.
+///
+/// The server may stop early and set `TimeTrack.next_date_from`; see
+/// `ClientContext::get_complete_time_track` to follow it.
+///
+/// # Example
+/// ``` ignore
+/// let parameters = GetTimeTrackParameters::new().with_dates(from..=to);
+/// let time_track = client_context.call_route(&get_time_track(Some(parameters)), None)?;
+/// ```
+pub fn get_time_track(parameters: Option<GetTimeTrackParameters>) -> Route<(), TimeTrack> {
+    let mut url: String = "/timetrack".into();
+
+    if let Some(params) = parameters {
+        let query_string: String = params.into();
+        if !query_string.is_empty() {
+            url.push('?');
+            url.push_str(&query_string);
+        }
+    }
+
+    Route::new(Method::GET, url.as_str())
+}
+
+/// Builds the route setting the time a user tracked on a task and day.
+///
+/// The body replaces the `time` and `comment` of the record; a time of zero removes it.
+///
+/// # Arguments
+/// * `user_id` - The identifier of the user (`User.id`).
+/// * `date` - The day of the record.
+/// * `task_id` - The identifier of the task (`Task.id`).
+///
+/// # Example
+/// ``` ignore
+/// let fields = EntityFields::new().with_field("time", WorkDuration::from_hours(2))?;
+/// let record = client_context.call_route(&patch_time_track(user_id, date, task_id), Some(&fields))?;
+/// ```
+pub fn patch_time_track(
+    user_id: i32,
+    date: NaiveDate,
+    task_id: i32,
+) -> Route<EntityFields, TimeTrackRecord> {
+    let url = format!(
+        "/timetrack/{}/{}/{}",
+        user_id,
+        date.format("%Y-%m-%d"),
+        task_id
+    );
+    Route::new(Method::PATCH, &url)
+}
@@ -0,0 +1,128 @@
+use crate::payload::{EntityFields, PaginationListing, TypeOfWork, enums::Method};
+use crate::routes::Route;
+use crate::routes::route::query_pair;
+
+/// Query parameters for `get_types_of_work`
+#[derive(Default)]
+pub struct GetTypesOfWorkParameters {
+    /// Index offset of the first item to return (for pagination)
+    pub offset: Option<u32>,
+    /// Maximum number of items to return (for pagination)
+    pub limit: Option<u32>,
+    /// Type of work IDs to filter by
+    pub type_of_work_ids: Option<Vec<i32>>,
+    /// Name filter (partial match)
+    pub name_filter: Option<String>,
+    /// Archived status to filter by
+    pub archived: Option<bool>,
+}
+
+impl GetTypesOfWorkParameters {
+    /// Creates an empty set of parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `offset` field and returns the modified parameters.
+    pub fn with_offset(mut self, offset: u32) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Sets the `limit` field and returns the modified parameters.
+    pub fn with_limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Sets the `type_of_work_ids` field and returns the modified parameters.
+    pub fn with_type_of_work_ids(mut self, type_of_work_ids: Vec<i32>) -> Self {
+        self.type_of_work_ids = Some(type_of_work_ids);
+        self
+    }
+
+    /// Sets the `name_filter` field and returns the modified parameters.
+    pub fn with_name_filter(mut self, name_filter: String) -> Self {
+        self.name_filter = Some(name_filter);
+        self
+    }
+
+    /// Sets the `archived` field and returns the modified parameters.
+    pub fn with_archived(mut self, archived: bool) -> Self {
+        self.archived = Some(archived);
+        self
+    }
+}
+
+impl From<GetTypesOfWorkParameters> for String {
+    fn from(parameters: GetTypesOfWorkParameters) -> String {
+        let mut query_params: Vec<(&'static str, String)> = vec![];
+
+        if let Some(offset) = parameters.offset {
+            query_params.push(("offset", offset.to_string()));
+        } else {
+            query_params.push(("offset", "0".to_string()));
+        }
+        if let Some(limit) = parameters.limit {
+            query_params.push(("limit", limit.to_string()));
+        }
+        if let Some(type_of_work_ids) = parameters.type_of_work_ids {
+            let ids = type_of_work_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            query_params.push(("ids", ids));
+        }
+        if let Some(name_filter) = parameters.name_filter {
+            query_params.push(("name", name_filter));
+        }
+        if let Some(archived) = parameters.archived {
+            query_params.push(("archived", archived.to_string()));
+        }
+
+        query_params
+            .iter()
+            .map(|(key, value)| query_pair(key, value))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+/// Builds the route listing types of work, filtered by `parameters`.
+pub fn get_types_of_work(
+    parameters: Option<GetTypesOfWorkParameters>,
+) -> Route<(), PaginationListing<TypeOfWork>> {
+    let mut url: String = "/typesOfWork?".into();
+
+    if let Some(params) = parameters {
+        let query_string: String = params.into();
+        url.push_str(&query_string);
+    } else {
+        url.push_str("offset=0&limit=100");
+    }
+
+    Route::new(Method::GET, url.as_str())
+}
+
+/// Builds the route creating a type of work.
+///
+/// # Example
+/// ``` ignore
+/// let type_of_work = EntityFields::new()
+///     .with_field("name", "Consulting")?
+///     .with_field("billable", true)?;
+/// let created = client_context.call_route(&post_type_of_work(), Some(&type_of_work))?;
+/// ```
+pub fn post_type_of_work() -> Route<EntityFields, TypeOfWork> {
+    Route::new(Method::POST, "/typesOfWork")
+}
+
+/// Builds the route changing some fields of a type of work.
+///
+/// # Arguments
+/// * `type_of_work_id` - The identifier of the type of work (`TypeOfWork.id`).
+pub fn patch_type_of_work(type_of_work_id: i32) -> Route<EntityFields, TypeOfWork> {
+    let url = format!("/typesOfWork/{}", type_of_work_id);
+    Route::new(Method::PATCH, &url)
+}
@@ -1,17 +1,190 @@
-use chrono::NaiveDateTime;
+use crate::errors::ClientError;
+use crate::payload::{PaginationListing, Schedule, User, UserWithAllowedActions, enums::Method};
+use crate::routes::route::query_pair;
+use crate::routes::{IntoDateRange, Route, checked_date_range};
 
-use crate::payload::{Schedule, UserWithAllowedActions, enums::Method};
-use crate::routes::Route;
+/// Sort order for `get_users`
+#[derive(Clone)]
+pub enum GetUsersSortOrder {
+    /// Username, ascending
+    UsernameAsc,
+    /// Username, descending
+    UsernameDesc,
+    /// First name, ascending
+    FirstNameAsc,
+    /// First name, descending
+    FirstNameDesc,
+    /// Last name, ascending
+    LastNameAsc,
+    /// Last name, descending
+    LastNameDesc,
+}
+
+impl GetUsersSortOrder {
+    /// Returns the value of the `sort` query parameter.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GetUsersSortOrder::UsernameAsc => "+username",
+            GetUsersSortOrder::UsernameDesc => "-username",
+            GetUsersSortOrder::FirstNameAsc => "+firstName",
+            GetUsersSortOrder::FirstNameDesc => "-firstName",
+            GetUsersSortOrder::LastNameAsc => "+lastName",
+            GetUsersSortOrder::LastNameDesc => "-lastName",
+        }
+    }
+}
+
+/// Query parameters for `get_users`
+#[derive(Clone, Default)]
+pub struct GetUsersParameters {
+    /// Index offset of the first item to return (for pagination)
+    pub offset: Option<u32>,
+    /// Maximum number of items to return (for pagination)
+    pub limit: Option<u32>,
+    /// User IDs to filter by
+    pub user_ids: Option<Vec<i32>>,
+    /// Department IDs to filter by
+    pub department_ids: Option<Vec<i32>>,
+    /// User name filter (partial match)
+    pub name_filter: Option<String>,
+    /// Active status to filter by
+    pub active: Option<bool>,
+    /// Sort order for the returned users
+    pub sort_order: Option<GetUsersSortOrder>,
+}
+
+impl GetUsersParameters {
+    /// Creates an empty set of parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `offset` field and returns the modified parameters.
+    pub fn with_offset(mut self, offset: u32) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Sets the `limit` field and returns the modified parameters.
+    pub fn with_limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Sets the `user_ids` field and returns the modified parameters.
+    pub fn with_user_ids(mut self, user_ids: Vec<i32>) -> Self {
+        self.user_ids = Some(user_ids);
+        self
+    }
+
+    /// Sets the `department_ids` field and returns the modified parameters.
+    pub fn with_department_ids(mut self, department_ids: Vec<i32>) -> Self {
+        self.department_ids = Some(department_ids);
+        self
+    }
+
+    /// Sets the `name_filter` field and returns the modified parameters.
+    pub fn with_name_filter(mut self, name_filter: String) -> Self {
+        self.name_filter = Some(name_filter);
+        self
+    }
+
+    /// Sets the `active` field and returns the modified parameters.
+    pub fn with_active(mut self, active: bool) -> Self {
+        self.active = Some(active);
+        self
+    }
+
+    /// Sets the `sort_order` field and returns the modified parameters.
+    pub fn with_sort_order(mut self, sort_order: GetUsersSortOrder) -> Self {
+        self.sort_order = Some(sort_order);
+        self
+    }
+}
+
+impl From<GetUsersParameters> for String {
+    fn from(parameters: GetUsersParameters) -> String {
+        let mut query_params: Vec<(&'static str, String)> = vec![];
+
+        if let Some(offset) = parameters.offset {
+            query_params.push(("offset", offset.to_string()));
+        } else {
+            query_params.push(("offset", "0".to_string()));
+        }
+        if let Some(limit) = parameters.limit {
+            query_params.push(("limit", limit.to_string()));
+        }
+        if let Some(user_ids) = parameters.user_ids {
+            let ids = user_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            query_params.push(("ids", ids));
+        }
+        if let Some(department_ids) = parameters.department_ids {
+            let ids = department_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            query_params.push(("departmentIds", ids));
+        }
+        if let Some(name_filter) = parameters.name_filter {
+            query_params.push(("name", name_filter));
+        }
+        if let Some(active) = parameters.active {
+            query_params.push(("active", active.to_string()));
+        }
+        if let Some(sort_order) = parameters.sort_order {
+            query_params.push(("sort", sort_order.as_str().to_string()));
+        }
+
+        query_params
+            .iter()
+            .map(|(key, value)| query_pair(key, value))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+/// Builds the route listing users, filtered by `parameters`.
+pub fn get_users(parameters: Option<GetUsersParameters>) -> Route<(), PaginationListing<User>> {
+    let mut url: String = "/users?".into();
+
+    if let Some(params) = parameters {
+        let query_string: String = params.into();
+        url.push_str(&query_string);
+    } else {
+        url.push_str("offset=0&limit=100");
+    }
+
+    Route::new(Method::GET, url.as_str())
+}
 
 pub fn get_users_me() -> Route<(), UserWithAllowedActions> {
     Route::new(Method::GET, "/users/me")
 }
 
+/// Builds the route returning the work schedule of a user.
+///
+/// # Arguments
+/// * `user_id` - The identifier of the user (`User.id`).
+/// * `dates` - A single `NaiveDate`, a `(from, to)` pair, a `from..=to` or `from..to` range or a `NaiveWeek`.
+///
+/// # Returns
+/// The route, or a `ClientError::Message` if the range is empty, e.g. `d..d`.
+///
+/// # Example
+/// ``` ignore
+/// let route = get_users_schedule(user.id, from..=to)?;
+/// let schedule = client_context.call_route(&route, None)?;
+/// ```
 pub fn get_users_schedule(
-    user_id: u64,
-    start_date: NaiveDateTime,
-    end_date: NaiveDateTime,
-) -> Route<(), Schedule> {
+    user_id: i32,
+    dates: impl IntoDateRange,
+) -> Result<Route<(), Schedule>, ClientError> {
+    let (start_date, end_date) = checked_date_range(dates)?;
     let url = format!(
         "/users/{}/schedule?start={}&end={}",
         user_id,
@@ -19,5 +192,5 @@ pub fn get_users_schedule(
         end_date.format("%Y-%m-%d")
     );
 
-    Route::new(Method::GET, &url)
+    Ok(Route::new(Method::GET, &url))
 }
@@ -0,0 +1,95 @@
+use crate::payload::{PaginationListing, WorkflowStatus, enums::Method};
+use crate::routes::Route;
+use crate::routes::route::query_pair;
+
+/// Query parameters for `get_workflow_statuses`
+#[derive(Default)]
+pub struct GetWorkflowStatusesParameters {
+    /// Index offset of the first item to return (for pagination)
+    pub offset: Option<u32>,
+    /// Maximum number of items to return (for pagination)
+    pub limit: Option<u32>,
+    /// Workflow status IDs to filter by
+    pub workflow_status_ids: Option<Vec<i32>>,
+    /// Name filter (partial match)
+    pub name_filter: Option<String>,
+}
+
+impl GetWorkflowStatusesParameters {
+    /// Creates an empty set of parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `offset` field and returns the modified parameters.
+    pub fn with_offset(mut self, offset: u32) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Sets the `limit` field and returns the modified parameters.
+    pub fn with_limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Sets the `workflow_status_ids` field and returns the modified parameters.
+    pub fn with_workflow_status_ids(mut self, workflow_status_ids: Vec<i32>) -> Self {
+        self.workflow_status_ids = Some(workflow_status_ids);
+        self
+    }
+
+    /// Sets the `name_filter` field and returns the modified parameters.
+    pub fn with_name_filter(mut self, name_filter: String) -> Self {
+        self.name_filter = Some(name_filter);
+        self
+    }
+}
+
+impl From<GetWorkflowStatusesParameters> for String {
+    fn from(parameters: GetWorkflowStatusesParameters) -> String {
+        let mut query_params: Vec<(&'static str, String)> = vec![];
+
+        if let Some(offset) = parameters.offset {
+            query_params.push(("offset", offset.to_string()));
+        } else {
+            query_params.push(("offset", "0".to_string()));
+        }
+        if let Some(limit) = parameters.limit {
+            query_params.push(("limit", limit.to_string()));
+        }
+        if let Some(workflow_status_ids) = parameters.workflow_status_ids {
+            let ids = workflow_status_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            query_params.push(("ids", ids));
+        }
+        if let Some(name_filter) = parameters.name_filter {
+            query_params.push(("name", name_filter));
+        }
+
+        query_params
+            .iter()
+            .map(|(key, value)| query_pair(key, value))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+/// Builds the route listing workflow statuses, filtered by `parameters`.
+pub fn get_workflow_statuses(
+    parameters: Option<GetWorkflowStatusesParameters>,
+) -> Route<(), PaginationListing<WorkflowStatus>> {
+    let mut url: String = "/workflowStatuses?".into();
+
+    if let Some(params) = parameters {
+        let query_string: String = params.into();
+        url.push_str(&query_string);
+    } else {
+        url.push_str("offset=0&limit=100");
+    }
+
+    Route::new(Method::GET, url.as_str())
+}
@@ -0,0 +1,71 @@
+//! Wrapper keeping credentials out of logs and memory dumps.
+
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// A credential that never shows up in `Debug` or `Display` output
+///
+/// The value is wiped from memory when the `Secret` is dropped. It is only readable through
+/// `expose_secret` and when serialized, so it can still be sent to the API.
+#[derive(Clone, Default, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct Secret(String);
+
+impl Secret {
+    /// Wraps a value as a secret.
+    pub fn new(value: String) -> Self {
+        Secret(value)
+    }
+
+    /// Returns the secret value.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let header = format!("Basic {}", token.expose_secret());
+    /// ```
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns `true` if the secret value is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret([REDACTED])")
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Secret(value)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Secret(value.to_string())
+    }
+}
+
+impl Serialize for Secret {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Secret {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Secret)
+    }
+}
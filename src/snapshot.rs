@@ -0,0 +1,680 @@
+//! Versioned on-disk snapshot of an organization, for offline analysis.
+//!
+//! A snapshot is a directory of JSON files holding the same payload types the routes return:
+//!
+//! ```text
+//! manifest.json                  format version, organization, revision, fetched months
+//! users.json customers.json projects.json tasks.json leave_types.json
+//! schedules/2024-05.json         schedules of every user for the month, keyed by user id
+//! time_track/2024-05.json        TimeTrack of the month
+//! ```
+//!
+//! Entities are fetched in full on every refresh unless `SnapshotOptions::with_entities` turns
+//! them off. Schedules and time-track are fetched by calendar month; a month is fetched again
+//! in full until it is settled, i.e. until it was fetched more than
+//! `SnapshotOptions::settle_period_days` after it ended. Settled months are not requested
+//! again, so later changes to them are only picked up with `SnapshotOptions::with_refetch_all`.
+//!
+//! A refresh writes the whole snapshot to a sibling `<directory>.staging` directory and then
+//! swaps it in, so readers see either the previous revision or the new one.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Datelike, Months, NaiveDate, NaiveTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::client_context::{ClientContext, PAGE_SIZE};
+use crate::errors::{ClientError, DecodeError};
+use crate::payload::{
+    Customer, LeaveType, Project, Schedule, Task, TimeTrack, TimeTrackEntry, User, WorkDuration,
+};
+use crate::reference_cache::ReferenceData;
+use crate::routes::{
+    GetTasksParameters, GetTimeTrackParameters, GetUsersParameters, IntoDateRange,
+    get_users_schedule,
+};
+
+/// Version of the snapshot layout written by this crate
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// Days after the end of a month during which it is still fetched again by default
+pub const DEFAULT_SETTLE_PERIOD_DAYS: u32 = 31;
+
+/// Schedule requests in flight at once during a refresh, by default
+pub const DEFAULT_MAX_CONCURRENCY: usize = 8;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const USERS_FILE: &str = "users.json";
+const CUSTOMERS_FILE: &str = "customers.json";
+const PROJECTS_FILE: &str = "projects.json";
+const TASKS_FILE: &str = "tasks.json";
+const LEAVE_TYPES_FILE: &str = "leave_types.json";
+const SCHEDULES_DIRECTORY: &str = "schedules";
+const TIME_TRACK_DIRECTORY: &str = "time_track";
+const STAGING_SUFFIX: &str = "staging";
+const PREVIOUS_SUFFIX: &str = "previous";
+
+/// Description of a snapshot, stored in `manifest.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotManifest {
+    /// Layout version, see `SNAPSHOT_FORMAT_VERSION`
+    pub format_version: u32,
+    /// Organization the snapshot was taken from
+    pub organization: String,
+    /// Number of refreshes since the snapshot was created, starting at 1
+    pub revision: u64,
+    /// Time the snapshot was created
+    pub created_at: DateTime<Utc>,
+    /// Time of the last refresh
+    pub refreshed_at: DateTime<Utc>,
+    /// Months with schedules and time-track, keyed by their first day, with the time they
+    /// were last fetched
+    pub months: BTreeMap<NaiveDate, DateTime<Utc>>,
+}
+
+/// What `Snapshot::refresh` fetches
+#[derive(Debug, Clone)]
+pub struct SnapshotOptions {
+    /// Days whose schedules and time-track are fetched, extended to whole months
+    pub dates: Option<(NaiveDate, NaiveDate)>,
+    /// Days after the end of a month during which it is still fetched again
+    pub settle_period_days: u32,
+    /// Fetch every month of `dates` again, settled or not
+    pub refetch_all: bool,
+    /// Fetch the users, customers, projects, tasks and leave types again
+    pub entities: bool,
+    /// Maximum number of schedule requests in flight at once
+    pub max_concurrency: usize,
+}
+
+/// Months fetched or skipped by a refresh
+#[derive(Debug, Clone, Default)]
+pub struct RefreshSummary {
+    /// Revision of the snapshot after the refresh
+    pub revision: u64,
+    /// Months whose schedules and time-track were fetched
+    pub fetched_months: Vec<NaiveDate>,
+    /// Months of the requested dates that were settled and kept as they were
+    pub kept_months: Vec<NaiveDate>,
+    /// Schedules that could not be fetched; their month is fetched again on the next refresh
+    pub failed_schedules: Vec<ScheduleFailure>,
+}
+
+/// Schedule of one user and month that a refresh could not fetch
+#[derive(Debug, Clone)]
+pub struct ScheduleFailure {
+    /// First day of the month
+    pub month: NaiveDate,
+    /// User whose schedule failed
+    pub user_id: i32,
+    /// Error returned for the request
+    pub message: String,
+}
+
+/// Organization data loaded from a snapshot directory
+///
+/// # Example
+/// ``` ignore
+/// let mut snapshot = Snapshot::open_or_create("snapshots/acme", &client_context)?;
+/// snapshot.refresh(&client_context, &SnapshotOptions::new().with_dates(year_start..=today))?;
+///
+/// // Later, offline:
+/// let snapshot = Snapshot::open("snapshots/acme")?;
+/// let tracked: WorkDuration = snapshot
+///     .time_track(march_start..=march_end)
+///     .filter(|entry| entry.user_id == user_id)
+///     .map(|entry| entry.record.time)
+///     .sum();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    directory: PathBuf,
+    manifest: SnapshotManifest,
+    users: Vec<User>,
+    customers: Vec<Customer>,
+    projects: Vec<Project>,
+    tasks: Vec<Task>,
+    leave_types: Vec<LeaveType>,
+    schedules: BTreeMap<NaiveDate, BTreeMap<i32, Schedule>>,
+    time_track: BTreeMap<NaiveDate, TimeTrack>,
+}
+
+impl SnapshotOptions {
+    /// Creates options refreshing the entities only.
+    pub fn new() -> Self {
+        SnapshotOptions {
+            dates: None,
+            settle_period_days: DEFAULT_SETTLE_PERIOD_DAYS,
+            refetch_all: false,
+            entities: true,
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+        }
+    }
+
+    /// Sets the `dates` field and returns the modified options.
+    ///
+    /// # Arguments
+    /// * `dates` - A single `NaiveDate`, a `(from, to)` pair, a `from..=to` or `from..to` range or a `NaiveWeek`.
+    pub fn with_dates(mut self, dates: impl IntoDateRange) -> Self {
+        self.dates = Some(dates.into_date_range());
+        self
+    }
+
+    /// Sets the `settle_period_days` field and returns the modified options.
+    pub fn with_settle_period_days(mut self, settle_period_days: u32) -> Self {
+        self.settle_period_days = settle_period_days;
+        self
+    }
+
+    /// Sets the `refetch_all` field and returns the modified options.
+    pub fn with_refetch_all(mut self, refetch_all: bool) -> Self {
+        self.refetch_all = refetch_all;
+        self
+    }
+
+    /// Sets the `entities` field and returns the modified options.
+    ///
+    /// The entities are fetched anyway when the snapshot has none yet.
+    pub fn with_entities(mut self, entities: bool) -> Self {
+        self.entities = entities;
+        self
+    }
+
+    /// Sets the `max_concurrency` field and returns the modified options.
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency;
+        self
+    }
+}
+
+impl Default for SnapshotOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Snapshot {
+    /// Loads a snapshot directory, without any network access.
+    ///
+    /// # Returns
+    /// The snapshot, or an error if a file is missing or unreadable, or if it was written by a
+    /// newer layout version.
+    pub fn open(directory: impl AsRef<Path>) -> Result<Self, ClientError> {
+        let directory = directory.as_ref().to_path_buf();
+        recover_interrupted_write(&directory)?;
+        let manifest: SnapshotManifest = read_json(&directory.join(MANIFEST_FILE))?;
+        if manifest.format_version > SNAPSHOT_FORMAT_VERSION {
+            return Err(ClientError::Message(format!(
+                "Snapshot {} has format version {}, this version reads up to {}",
+                directory.display(),
+                manifest.format_version,
+                SNAPSHOT_FORMAT_VERSION
+            )));
+        }
+
+        let mut schedules = BTreeMap::new();
+        let mut time_track = BTreeMap::new();
+        for month in manifest.months.keys() {
+            let file_name = month_file_name(*month);
+            schedules.insert(
+                *month,
+                read_json(&directory.join(SCHEDULES_DIRECTORY).join(&file_name))?,
+            );
+            time_track.insert(
+                *month,
+                read_json(&directory.join(TIME_TRACK_DIRECTORY).join(&file_name))?,
+            );
+        }
+
+        Ok(Snapshot {
+            users: read_json(&directory.join(USERS_FILE))?,
+            customers: read_json(&directory.join(CUSTOMERS_FILE))?,
+            projects: read_json(&directory.join(PROJECTS_FILE))?,
+            tasks: read_json(&directory.join(TASKS_FILE))?,
+            leave_types: read_json(&directory.join(LEAVE_TYPES_FILE))?,
+            schedules,
+            time_track,
+            directory,
+            manifest,
+        })
+    }
+
+    /// Loads a snapshot directory, or starts an empty snapshot of the organization of
+    /// `client_context` if it has no manifest yet. Nothing is written until `refresh`.
+    ///
+    /// # Returns
+    /// The snapshot, or a `ClientError::Message` if the directory holds a snapshot of another
+    /// organization.
+    pub fn open_or_create(
+        directory: impl AsRef<Path>,
+        client_context: &ClientContext,
+    ) -> Result<Self, ClientError> {
+        let directory = directory.as_ref();
+        recover_interrupted_write(directory)?;
+        if !directory.join(MANIFEST_FILE).exists() {
+            let now = Utc::now();
+            return Ok(Snapshot {
+                directory: directory.to_path_buf(),
+                manifest: SnapshotManifest {
+                    format_version: SNAPSHOT_FORMAT_VERSION,
+                    organization: client_context.organization().to_string(),
+                    revision: 0,
+                    created_at: now,
+                    refreshed_at: now,
+                    months: BTreeMap::new(),
+                },
+                users: vec![],
+                customers: vec![],
+                projects: vec![],
+                tasks: vec![],
+                leave_types: vec![],
+                schedules: BTreeMap::new(),
+                time_track: BTreeMap::new(),
+            });
+        }
+
+        let snapshot = Snapshot::open(directory)?;
+        if snapshot.manifest.organization != client_context.organization() {
+            return Err(ClientError::Message(format!(
+                "Snapshot {} belongs to organization '{}', not '{}'",
+                directory.display(),
+                snapshot.manifest.organization,
+                client_context.organization()
+            )));
+        }
+        Ok(snapshot)
+    }
+
+    /// Fetches the entities again, then the schedules and time-track of the months of
+    /// `options.dates` that are missing or not settled yet, and writes the snapshot.
+    ///
+    /// A schedule that cannot be fetched does not stop the refresh: the previous one is kept,
+    /// the failure is listed in the summary and its month is fetched again next time.
+    pub fn refresh(
+        &mut self,
+        client_context: &ClientContext,
+        options: &SnapshotOptions,
+    ) -> Result<RefreshSummary, ClientError> {
+        let now = Utc::now();
+        let mut summary = RefreshSummary::default();
+
+        if options.entities || self.manifest.revision == 0 {
+            self.users = client_context.get_all_users(GetUsersParameters::new())?;
+            self.customers = fetch_reference_data(client_context)?;
+            self.projects = fetch_reference_data(client_context)?;
+            self.tasks = client_context.get_all_tasks(GetTasksParameters::new())?;
+            self.leave_types = fetch_reference_data(client_context)?;
+        }
+
+        let months = match options.dates {
+            Some((from, to)) => months_between(from, to)?,
+            None => vec![],
+        };
+        for month in months {
+            let month_end = month_end(month)?;
+            let settled = self.manifest.months.get(&month).is_some_and(|fetched_at| {
+                is_settled(*fetched_at, month_end, options.settle_period_days)
+            });
+            if settled && !options.refetch_all {
+                summary.kept_months.push(month);
+                continue;
+            }
+
+            let time_track = client_context.get_complete_time_track(
+                GetTimeTrackParameters::new().with_dates(month..=month_end),
+            )?;
+            let failures =
+                self.fetch_schedules(client_context, month, month_end, options.max_concurrency)?;
+            let fetched_at = if failures.is_empty() {
+                now
+            } else {
+                // Never settled, so the month is fetched again on the next refresh.
+                month.and_time(NaiveTime::MIN).and_utc()
+            };
+            summary.failed_schedules.extend(failures);
+            self.time_track.insert(month, time_track);
+            self.manifest.months.insert(month, fetched_at);
+            summary.fetched_months.push(month);
+        }
+
+        self.manifest.revision += 1;
+        self.manifest.refreshed_at = now;
+        self.write()?;
+        summary.revision = self.manifest.revision;
+        Ok(summary)
+    }
+
+    /// Fetches the schedule of every user for a month, keeping the previous schedule of the
+    /// users whose request fails.
+    fn fetch_schedules(
+        &mut self,
+        client_context: &ClientContext,
+        month: NaiveDate,
+        month_end: NaiveDate,
+        max_concurrency: usize,
+    ) -> Result<Vec<ScheduleFailure>, ClientError> {
+        let requests = self
+            .users
+            .iter()
+            .map(|user| Ok((get_users_schedule(user.id, month..=month_end)?, None)))
+            .collect::<Result<Vec<_>, ClientError>>()?;
+        let results = client_context.call_routes_concurrently(&requests, max_concurrency);
+
+        let mut previous = self.schedules.remove(&month).unwrap_or_default();
+        let mut schedules = BTreeMap::new();
+        let mut failures = vec![];
+        for (user, result) in self.users.iter().zip(results) {
+            match result {
+                Ok(schedule) => {
+                    schedules.insert(user.id, schedule);
+                }
+                Err(e) => {
+                    if let Some(schedule) = previous.remove(&user.id) {
+                        schedules.insert(user.id, schedule);
+                    }
+                    failures.push(ScheduleFailure {
+                        month,
+                        user_id: user.id,
+                        message: e.to_string(),
+                    });
+                }
+            }
+        }
+        self.schedules.insert(month, schedules);
+        Ok(failures)
+    }
+
+    /// Returns the manifest of the snapshot.
+    pub fn manifest(&self) -> &SnapshotManifest {
+        &self.manifest
+    }
+
+    /// Returns the directory of the snapshot.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Returns every user.
+    pub fn users(&self) -> &[User] {
+        &self.users
+    }
+
+    /// Returns every customer.
+    pub fn customers(&self) -> &[Customer] {
+        &self.customers
+    }
+
+    /// Returns every project.
+    pub fn projects(&self) -> &[Project] {
+        &self.projects
+    }
+
+    /// Returns every task.
+    pub fn tasks(&self) -> &[Task] {
+        &self.tasks
+    }
+
+    /// Returns every leave type.
+    pub fn leave_types(&self) -> &[LeaveType] {
+        &self.leave_types
+    }
+
+    /// Returns the user with the given id.
+    pub fn user(&self, user_id: i32) -> Option<&User> {
+        self.users.iter().find(|user| user.id == user_id)
+    }
+
+    /// Returns the task with the given id.
+    pub fn task(&self, task_id: i32) -> Option<&Task> {
+        self.tasks.iter().find(|task| task.id == Some(task_id))
+    }
+
+    /// Returns the scheduled time of a user on a day, or `None` if the month is not in the
+    /// snapshot.
+    pub fn scheduled_time(&self, user_id: i32, date: NaiveDate) -> Option<WorkDuration> {
+        let month = first_of_month(date)?;
+        self.schedules
+            .get(&month)?
+            .get(&user_id)?
+            .scheduled_time(date)
+    }
+
+    /// Iterates over the time-track records of a date range, month by month; within a month
+    /// they come in the order the server returned them.
+    ///
+    /// Months missing from the snapshot yield no records; see `SnapshotManifest::months`.
+    ///
+    /// # Arguments
+    /// * `dates` - A single `NaiveDate`, a `(from, to)` pair, a `from..=to` or `from..to` range or a `NaiveWeek`.
+    pub fn time_track(
+        &self,
+        dates: impl IntoDateRange,
+    ) -> impl Iterator<Item = TimeTrackEntry<'_>> + '_ {
+        let (from, to) = dates.into_date_range();
+        self.time_track
+            .values()
+            .flat_map(TimeTrack::entries)
+            .filter(move |entry| from <= entry.date && entry.date <= to)
+    }
+
+    /// Writes the whole snapshot to a staging directory, then swaps it with the current one.
+    ///
+    /// The previous directory is renamed aside before the staging one takes its place and is
+    /// removed afterwards; `recover_interrupted_write` puts it back if the swap was cut short.
+    fn write(&self) -> Result<(), ClientError> {
+        let directory = &self.directory;
+        let staging = sibling(directory, STAGING_SUFFIX)?;
+        let previous = sibling(directory, PREVIOUS_SUFFIX)?;
+        if staging.exists() {
+            fs::remove_dir_all(&staging).map_err(|e| io_error(&staging, e))?;
+        }
+        fs::create_dir_all(staging.join(SCHEDULES_DIRECTORY)).map_err(|e| io_error(&staging, e))?;
+        fs::create_dir_all(staging.join(TIME_TRACK_DIRECTORY))
+            .map_err(|e| io_error(&staging, e))?;
+
+        write_json(&staging.join(USERS_FILE), &self.users)?;
+        write_json(&staging.join(CUSTOMERS_FILE), &self.customers)?;
+        write_json(&staging.join(PROJECTS_FILE), &self.projects)?;
+        write_json(&staging.join(TASKS_FILE), &self.tasks)?;
+        write_json(&staging.join(LEAVE_TYPES_FILE), &self.leave_types)?;
+        for month in self.manifest.months.keys() {
+            let file_name = month_file_name(*month);
+            if let Some(schedules) = self.schedules.get(month) {
+                write_json(
+                    &staging.join(SCHEDULES_DIRECTORY).join(&file_name),
+                    schedules,
+                )?;
+            }
+            if let Some(time_track) = self.time_track.get(month) {
+                write_json(
+                    &staging.join(TIME_TRACK_DIRECTORY).join(&file_name),
+                    time_track,
+                )?;
+            }
+        }
+        write_json(&staging.join(MANIFEST_FILE), &self.manifest)?;
+
+        if previous.exists() {
+            fs::remove_dir_all(&previous).map_err(|e| io_error(&previous, e))?;
+        }
+        if directory.exists() {
+            fs::rename(directory, &previous).map_err(|e| io_error(directory, e))?;
+        }
+        fs::rename(&staging, directory).map_err(|e| io_error(directory, e))?;
+        if previous.exists() {
+            fs::remove_dir_all(&previous).map_err(|e| io_error(&previous, e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Puts back the previous revision of a snapshot if a write stopped between renaming it aside
+/// and moving the new revision in place.
+fn recover_interrupted_write(directory: &Path) -> Result<(), ClientError> {
+    let previous = sibling(directory, PREVIOUS_SUFFIX)?;
+    if !directory.exists() && previous.join(MANIFEST_FILE).exists() {
+        fs::rename(&previous, directory).map_err(|e| io_error(directory, e))?;
+    }
+    Ok(())
+}
+
+/// Returns `<directory>.<suffix>`, next to the snapshot directory.
+fn sibling(directory: &Path, suffix: &str) -> Result<PathBuf, ClientError> {
+    let name = directory.file_name().ok_or_else(|| {
+        ClientError::Message(format!(
+            "Snapshot directory {} has no name",
+            directory.display()
+        ))
+    })?;
+    let mut name = name.to_os_string();
+    name.push(format!(".{}", suffix));
+    Ok(directory.with_file_name(name))
+}
+
+/// Fetches every page of a reference data kind, bypassing the reference cache.
+fn fetch_reference_data<T: ReferenceData>(
+    client_context: &ClientContext,
+) -> Result<Vec<T>, ClientError> {
+    let mut items = vec![];
+    let mut offset = 0;
+    loop {
+        let page = client_context.call_route(&T::list_route(offset, PAGE_SIZE), None)?;
+        let page_len = page.items.len() as u32;
+        items.extend(page.items);
+        if page_len < PAGE_SIZE {
+            return Ok(items);
+        }
+        offset += PAGE_SIZE;
+    }
+}
+
+fn first_of_month(date: NaiveDate) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(date.year(), date.month(), 1)
+}
+
+fn month_end(month: NaiveDate) -> Result<NaiveDate, ClientError> {
+    month
+        .checked_add_months(Months::new(1))
+        .and_then(|next_month| next_month.pred_opt())
+        .ok_or_else(|| ClientError::Message(format!("No end of month for {}", month)))
+}
+
+/// Returns the first day of every month from the one of `from` to the one of `to`.
+fn months_between(from: NaiveDate, to: NaiveDate) -> Result<Vec<NaiveDate>, ClientError> {
+    let invalid = || ClientError::Message(format!("Invalid date range {} to {}", from, to));
+    let mut month = first_of_month(from).ok_or_else(invalid)?;
+    let mut months = vec![];
+    while month <= to {
+        months.push(month);
+        month = month
+            .checked_add_months(Months::new(1))
+            .ok_or_else(invalid)?;
+    }
+    Ok(months)
+}
+
+fn month_file_name(month: NaiveDate) -> String {
+    format!("{}.json", month.format("%Y-%m"))
+}
+
+fn io_error(path: &Path, error: std::io::Error) -> ClientError {
+    ClientError::Message(format!(
+        "Cannot access snapshot file {}: {}",
+        path.display(),
+        error
+    ))
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, ClientError> {
+    let bytes = fs::read(path).map_err(|e| io_error(path, e))?;
+    DecodeError::decode(&bytes, false).map_err(ClientError::Decode)
+}
+
+fn write_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> Result<(), ClientError> {
+    let bytes = serde_json::to_vec(value).map_err(ClientError::SerdeJson)?;
+    fs::write(path, bytes).map_err(|e| io_error(path, e))
+}
+
+/// Returns `true` if a month ending on `month_end` was fetched more than `settle_period_days`
+/// after it ended.
+fn is_settled(fetched_at: DateTime<Utc>, month_end: NaiveDate, settle_period_days: u32) -> bool {
+    (fetched_at.date_naive() - month_end).num_days() > i64::from(settle_period_days)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_snapshot(directory: PathBuf) -> Snapshot {
+        let now = Utc::now();
+        Snapshot {
+            directory,
+            manifest: SnapshotManifest {
+                format_version: SNAPSHOT_FORMAT_VERSION,
+                organization: "acme".to_string(),
+                revision: 1,
+                created_at: now,
+                refreshed_at: now,
+                months: BTreeMap::new(),
+            },
+            users: vec![],
+            customers: vec![],
+            projects: vec![],
+            tasks: vec![],
+            leave_types: vec![],
+            schedules: BTreeMap::new(),
+            time_track: BTreeMap::new(),
+        }
+    }
+
+    fn scratch_directory(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("snapshot-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        root.join("acme")
+    }
+
+    #[test]
+    fn writes_and_reopens_a_snapshot() -> Result<(), ClientError> {
+        let directory = scratch_directory("write");
+        let mut snapshot = empty_snapshot(directory.clone());
+        snapshot.write()?;
+        snapshot.manifest.revision = 2;
+        snapshot.write()?;
+
+        let reopened = Snapshot::open(&directory)?;
+        assert_eq!(reopened.manifest().revision, 2);
+        assert!(!sibling(&directory, STAGING_SUFFIX)?.exists());
+        assert!(!sibling(&directory, PREVIOUS_SUFFIX)?.exists());
+        let _ = fs::remove_dir_all(directory.parent().unwrap_or(&directory));
+        Ok(())
+    }
+
+    #[test]
+    fn recovers_the_previous_revision_after_an_interrupted_swap() -> Result<(), ClientError> {
+        let directory = scratch_directory("recover");
+        empty_snapshot(directory.clone()).write()?;
+        let previous = sibling(&directory, PREVIOUS_SUFFIX)?;
+        fs::rename(&directory, &previous).map_err(|e| io_error(&directory, e))?;
+
+        let reopened = Snapshot::open(&directory)?;
+        assert_eq!(reopened.manifest().revision, 1);
+        assert!(!previous.exists());
+        let _ = fs::remove_dir_all(directory.parent().unwrap_or(&directory));
+        Ok(())
+    }
+
+    #[test]
+    fn settles_months_after_the_settle_period() {
+        let month_end = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap_or_default();
+        let fetched_on = |date: NaiveDate| date.and_time(NaiveTime::MIN).and_utc();
+        let days_later = |days| month_end + chrono::Days::new(days);
+        assert!(!is_settled(fetched_on(month_end), month_end, 31));
+        assert!(!is_settled(fetched_on(days_later(31)), month_end, 31));
+        assert!(is_settled(fetched_on(days_later(32)), month_end, 31));
+        assert!(is_settled(fetched_on(days_later(1)), month_end, 0));
+    }
+}
@@ -0,0 +1,1248 @@
+//! Project structure as code: types of work, customers, projects and tasks described in a
+//! TOML or YAML file, planned against the live organization and applied in dependency order.
+//!
+//! ```toml
+//! [[types_of_work]]
+//! name = "Consulting"
+//! billable = true
+//!
+//! [[customers]]
+//! name = "Acme"
+//!
+//! [[customers.projects]]
+//! name = "Website relaunch"
+//!
+//! [[customers.projects.tasks]]
+//! name = "Design review"
+//! type_of_work = "Consulting"
+//! estimated_time = "4h"
+//! deadline = "2024-06-30"
+//! ```
+//!
+//! The file manages the types of work and customers it names, and every project and task
+//! under those customers: live projects and tasks missing from the file are planned for
+//! archiving (tasks are completed). Other customers and types of work are left alone.
+//! Entities are matched by name, ignoring case.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::client_context::{ClientContext, PAGE_SIZE};
+use crate::errors::ClientError;
+use crate::payload::{
+    Customer, EntityFields, Project, Task, TypeOfWork, WorkDuration, enums::TaskStatus,
+};
+use crate::reference_cache::{ReferenceDataKind, find_by_name};
+use crate::routes::{
+    GetTasksParameters, patch_customer, patch_project, patch_task, patch_type_of_work,
+    post_customer, post_project, post_task, post_type_of_work,
+};
+
+/// Desired state of part of an organization
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StructureSpec {
+    /// Types of work to manage
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub types_of_work: Vec<TypeOfWorkSpec>,
+    /// Customers to manage, with all their projects and tasks
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub customers: Vec<CustomerSpec>,
+}
+
+/// Desired state of a type of work
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TypeOfWorkSpec {
+    /// Name, used to match the live type of work
+    pub name: String,
+    /// Work unit cost, left unchanged if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate: Option<f64>,
+    /// Billable status, left unchanged if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub billable: Option<bool>,
+    /// Archived status
+    #[serde(default)]
+    pub archived: bool,
+}
+
+/// Desired state of a customer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomerSpec {
+    /// Name, used to match the live customer
+    pub name: String,
+    /// Description, left unchanged if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Archived status
+    #[serde(default)]
+    pub archived: bool,
+    /// Every project of the customer
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub projects: Vec<ProjectSpec>,
+}
+
+/// Desired state of a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectSpec {
+    /// Name, used to match the live project of the customer
+    pub name: String,
+    /// Description, left unchanged if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Archived status
+    #[serde(default)]
+    pub archived: bool,
+    /// Every open task of the project
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tasks: Vec<TaskSpec>,
+}
+
+/// Desired state of a task
+///
+/// Fields that are not set are left unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaskSpec {
+    /// Name, used to match the live task of the project
+    pub name: String,
+    /// Description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Name of the type of work, live or declared in the same file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_of_work: Option<String>,
+    /// Status, `open` or `completed`
+    #[serde(
+        default,
+        deserialize_with = "known_status",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub status: Option<TaskStatus>,
+    /// Deadline, as a `YYYY-MM-DD` string
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<NaiveDate>,
+    /// Estimated time, as minutes or text such as `"4h"`, `"1h 30m"` or `"2:15"`
+    #[serde(
+        default,
+        with = "duration_text",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub estimated_time: Option<WorkDuration>,
+}
+
+/// Kind of entity managed by a `StructureSpec`, in dependency order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StructureKind {
+    /// Type of work
+    TypeOfWork,
+    /// Customer
+    Customer,
+    /// Project
+    Project,
+    /// Task
+    Task,
+}
+
+/// Entity of a `StructureSpec`, identified by its kind and the names leading to it
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub struct StructureAddress {
+    /// Kind of entity
+    pub kind: StructureKind,
+    /// Names from the customer down to the entity, e.g. `["Acme", "Website", "Design"]`
+    pub path: Vec<String>,
+}
+
+/// What a planned change does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeAction {
+    /// Create a missing entity
+    Create,
+    /// Patch fields of a live entity
+    Update,
+    /// Archive a live entity, or complete a live task
+    Archive,
+}
+
+/// One change of a `Plan`
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedChange {
+    /// What the change does
+    pub action: ChangeAction,
+    /// Entity changed
+    pub address: StructureAddress,
+    /// Identifier of the live entity, `None` for creates
+    pub id: Option<i32>,
+    /// Fields sent to the create or patch route
+    pub fields: EntityFields,
+    /// Fields set at apply time to the id of an entity created earlier by the plan
+    pub references: BTreeMap<String, StructureAddress>,
+}
+
+/// Changes bringing the live organization to a `StructureSpec`, in the order they are applied
+///
+/// Creates and updates come first, parents before children; archives come last, children
+/// before parents.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Plan {
+    /// Changes, in application order
+    pub changes: Vec<PlannedChange>,
+}
+
+/// How `Plan::apply` runs
+#[derive(Debug, Clone)]
+pub struct ApplyOptions {
+    /// Only check the plan and report the changes, without calling the API
+    pub dry_run: bool,
+    /// Refuse plans that archive anything
+    pub destroy_protection: bool,
+}
+
+/// Change carried out (or, in a dry run, that would be carried out) by `Plan::apply`
+#[derive(Debug, Clone, Serialize)]
+pub struct AppliedChange {
+    /// What the change did
+    pub action: ChangeAction,
+    /// Entity changed
+    pub address: StructureAddress,
+    /// Identifier of the entity; `None` for creates in a dry run
+    pub id: Option<i32>,
+}
+
+impl StructureSpec {
+    /// Parses a TOML description.
+    #[cfg(feature = "config")]
+    pub fn from_toml(s: &str) -> Result<Self, ClientError> {
+        toml::from_str(s).map_err(|e| ClientError::Other(Box::new(e)))
+    }
+
+    /// Parses a YAML description.
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(s: &str) -> Result<Self, ClientError> {
+        serde_yaml::from_str(s).map_err(|e| ClientError::Other(Box::new(e)))
+    }
+
+    /// Loads a description, as TOML or YAML depending on the extension of `path`.
+    ///
+    /// # Returns
+    /// The description, or a `ClientError::Message` if the file cannot be read or its format
+    /// is not enabled (`config` feature for TOML, `yaml` feature for YAML).
+    #[cfg(any(feature = "config", feature = "yaml"))]
+    pub fn from_path(path: &std::path::Path) -> Result<Self, ClientError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| ClientError::Message(format!("Cannot read {}: {}", path.display(), e)))?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            #[cfg(feature = "config")]
+            Some("toml") => Self::from_toml(&contents),
+            #[cfg(feature = "yaml")]
+            Some("yaml" | "yml") => Self::from_yaml(&contents),
+            _ => Err(ClientError::Message(format!(
+                "Unsupported structure file {}",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Compares the description with the live organization.
+    ///
+    /// # Returns
+    /// The plan, or a `ClientError::Message` if the description declares an entity twice,
+    /// refers to an unknown type of work or matches several live entities by name.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let spec = StructureSpec::from_path(Path::new("clients/acme.toml"))?;
+    /// let plan = spec.plan(&client_context)?;
+    /// for change in &plan.changes {
+    ///     println!("{}", change);
+    /// }
+    /// ```
+    pub fn plan(&self, client_context: &ClientContext) -> Result<Plan, ClientError> {
+        self.check_duplicates()?;
+        for kind in [
+            ReferenceDataKind::TypeOfWork,
+            ReferenceDataKind::Customer,
+            ReferenceDataKind::Project,
+        ] {
+            client_context.invalidate_reference_data(kind);
+        }
+        let live_types_of_work = client_context.reference_data::<TypeOfWork>()?;
+        let live_customers = client_context.reference_data::<Customer>()?;
+        let live_projects = client_context.reference_data::<Project>()?;
+        let managed_project_ids: Vec<u32> = live_projects
+            .iter()
+            .filter(|project| {
+                live_customers.iter().any(|customer| {
+                    customer.id == project.customer_id
+                        && self
+                            .customers
+                            .iter()
+                            .any(|spec| spec.name.eq_ignore_ascii_case(&customer.name))
+                })
+            })
+            .map(|project| project.id as u32)
+            .collect();
+        let mut live_tasks = vec![];
+        for chunk in managed_project_ids.chunks(PAGE_SIZE as usize) {
+            let parameters = GetTasksParameters::new().with_project_ids(chunk.to_vec());
+            live_tasks.extend(client_context.get_all_tasks(parameters)?);
+        }
+        self.plan_live(
+            &live_types_of_work,
+            &live_customers,
+            &live_projects,
+            &live_tasks,
+        )
+    }
+
+    /// Compares the description with the given live entities; `live_tasks` holds the tasks
+    /// of the projects of the managed customers.
+    fn plan_live(
+        &self,
+        live_types_of_work: &[TypeOfWork],
+        live_customers: &[Customer],
+        live_projects: &[Project],
+        live_tasks: &[Task],
+    ) -> Result<Plan, ClientError> {
+        let mut planner = Planner::default();
+        let mut type_of_work_ids = HashMap::new();
+        for spec in &self.types_of_work {
+            let address = StructureAddress::new(StructureKind::TypeOfWork, vec![&spec.name]);
+            let live = find_live(live_types_of_work, &address, |t| (&t.name, t.archived))?;
+            if let Some(live) = live {
+                type_of_work_ids.insert(spec.name.as_str(), live.id);
+            }
+            planner.type_of_work(spec, live, address)?;
+        }
+
+        let mut customers = vec![];
+        for spec in &self.customers {
+            let address = StructureAddress::new(StructureKind::Customer, vec![&spec.name]);
+            let live = find_live(live_customers, &address, |c| (&c.name, c.archived))?;
+            customers.push((spec, live, address));
+        }
+
+        for (spec, live, address) in customers {
+            planner.customer(spec, live, address.clone())?;
+            let customer_projects: Vec<&Project> = live_projects
+                .iter()
+                .filter(|project| live.is_some_and(|c| c.id == project.customer_id))
+                .collect();
+
+            for project_spec in &spec.projects {
+                let project_address = address.child(StructureKind::Project, &project_spec.name);
+                let live_project =
+                    find_live(customer_projects.iter().copied(), &project_address, |p| {
+                        (&p.name, p.archived)
+                    })?;
+                planner.project(
+                    project_spec,
+                    live_project,
+                    live.map(|c| c.id),
+                    &address,
+                    project_address.clone(),
+                )?;
+
+                let project_tasks: Vec<&Task> = live_tasks
+                    .iter()
+                    .filter(|task| live_project.is_some_and(|p| task.project_id == Some(p.id)))
+                    .collect();
+                for task_spec in &project_spec.tasks {
+                    let task_address = project_address.child(StructureKind::Task, &task_spec.name);
+                    let live_task = find_live(project_tasks.iter().copied(), &task_address, |t| {
+                        (&t.name, t.status == Some(TaskStatus::Completed))
+                    })?;
+                    let type_of_work = match &task_spec.type_of_work {
+                        Some(name) => Some(self.type_of_work_reference(
+                            name,
+                            &type_of_work_ids,
+                            live_types_of_work,
+                            &task_address,
+                        )?),
+                        None => None,
+                    };
+                    planner.task(
+                        task_spec,
+                        live_task,
+                        type_of_work,
+                        live_project.map(|p| p.id),
+                        &project_address,
+                        task_address,
+                    )?;
+                }
+                for task in project_tasks {
+                    let declared = project_spec
+                        .tasks
+                        .iter()
+                        .any(|t| t.name.eq_ignore_ascii_case(&task.name));
+                    if !declared && task.status != Some(TaskStatus::Completed) {
+                        let task_address = project_address.child(StructureKind::Task, &task.name);
+                        planner.archive(task_address, task.id)?;
+                    }
+                }
+            }
+            for project in customer_projects {
+                let declared = spec
+                    .projects
+                    .iter()
+                    .any(|p| p.name.eq_ignore_ascii_case(&project.name));
+                if !declared && !project.archived {
+                    let project_address = address.child(StructureKind::Project, &project.name);
+                    planner.archive(project_address, Some(project.id))?;
+                }
+            }
+        }
+
+        Ok(planner.into_plan())
+    }
+
+    /// Returns the live id of a type of work, or the address of the declared one.
+    fn type_of_work_reference(
+        &self,
+        name: &str,
+        type_of_work_ids: &HashMap<&str, i32>,
+        live_types_of_work: &[TypeOfWork],
+        task_address: &StructureAddress,
+    ) -> Result<Reference, ClientError> {
+        let declared = self
+            .types_of_work
+            .iter()
+            .find(|t| t.name.eq_ignore_ascii_case(name));
+        if let Some(spec) = declared {
+            return Ok(match type_of_work_ids.get(spec.name.as_str()) {
+                Some(id) => Reference::Live(*id),
+                None => Reference::Planned(StructureAddress::new(
+                    StructureKind::TypeOfWork,
+                    vec![&spec.name],
+                )),
+            });
+        }
+        let address = StructureAddress::new(StructureKind::TypeOfWork, vec![name]);
+        match find_live(live_types_of_work, &address, |t| (&t.name, t.archived))? {
+            Some(live) => Ok(Reference::Live(live.id)),
+            None => Err(ClientError::Message(format!(
+                "{} refers to unknown type of work '{}'",
+                task_address, name
+            ))),
+        }
+    }
+
+    fn check_duplicates(&self) -> Result<(), ClientError> {
+        let mut seen = BTreeSet::new();
+        let mut check = |address: StructureAddress| {
+            let key: Vec<String> = address
+                .path
+                .iter()
+                .map(|n| n.to_ascii_lowercase())
+                .collect();
+            if seen.insert((address.kind, key)) {
+                Ok(())
+            } else {
+                Err(ClientError::Message(format!(
+                    "{} is declared twice",
+                    address
+                )))
+            }
+        };
+        for spec in &self.types_of_work {
+            check(StructureAddress::new(
+                StructureKind::TypeOfWork,
+                vec![&spec.name],
+            ))?;
+        }
+        for customer in &self.customers {
+            let address = StructureAddress::new(StructureKind::Customer, vec![&customer.name]);
+            check(address.clone())?;
+            for project in &customer.projects {
+                let project_address = address.child(StructureKind::Project, &project.name);
+                check(project_address.clone())?;
+                for task in &project.tasks {
+                    check(project_address.child(StructureKind::Task, &task.name))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl PlannedChange {
+    /// Returns the wire names of the fields the change sends, references included.
+    pub fn field_names(&self) -> Vec<&str> {
+        self.fields
+            .fields
+            .keys()
+            .chain(self.references.keys())
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+impl Plan {
+    /// Returns `true` if the live organization already matches the description.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns the number of changes with the given action.
+    pub fn count(&self, action: ChangeAction) -> usize {
+        self.changes
+            .iter()
+            .filter(|change| change.action == action)
+            .count()
+    }
+
+    /// Carries out the changes in order.
+    ///
+    /// The first failing change stops the run; the changes before it stay applied, and planning
+    /// again picks up from there.
+    ///
+    /// # Returns
+    /// The changes carried out, or a `ClientError::Message` if destroy protection is on and the
+    /// plan archives anything. Nothing is changed in that case.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let applied = plan.apply(&client_context, &ApplyOptions::new().with_dry_run(true))?;
+    /// ```
+    pub fn apply(
+        &self,
+        client_context: &ClientContext,
+        options: &ApplyOptions,
+    ) -> Result<Vec<AppliedChange>, ClientError> {
+        if options.destroy_protection {
+            let archives: Vec<String> = self
+                .changes
+                .iter()
+                .filter(|change| change.action == ChangeAction::Archive)
+                .map(|change| change.address.to_string())
+                .collect();
+            if !archives.is_empty() {
+                return Err(ClientError::Message(format!(
+                    "Destroy protection refuses to archive {}",
+                    archives.join(", ")
+                )));
+            }
+        }
+
+        let mut created: HashMap<&StructureAddress, i32> = HashMap::new();
+        let mut applied = vec![];
+        for change in &self.changes {
+            if options.dry_run {
+                applied.push(AppliedChange {
+                    action: change.action,
+                    address: change.address.clone(),
+                    id: change.id,
+                });
+                continue;
+            }
+
+            let mut fields = change.fields.clone();
+            for (name, address) in &change.references {
+                let id = created.get(address).ok_or_else(|| {
+                    ClientError::Message(format!(
+                        "{} depends on {}, which was not created",
+                        change.address, address
+                    ))
+                })?;
+                fields.fields.insert(name.clone(), Value::from(*id));
+            }
+            let id = match change.action {
+                ChangeAction::Create => {
+                    let id = create(client_context, change.address.kind, fields)?;
+                    created.insert(&change.address, id);
+                    id
+                }
+                ChangeAction::Update | ChangeAction::Archive => {
+                    let id = change.id.ok_or_else(|| {
+                        ClientError::Message(format!("{} has no id to patch", change.address))
+                    })?;
+                    patch(client_context, change.address.kind, id, &fields)?;
+                    id
+                }
+            };
+            applied.push(AppliedChange {
+                action: change.action,
+                address: change.address.clone(),
+                id: Some(id),
+            });
+        }
+        Ok(applied)
+    }
+}
+
+impl ApplyOptions {
+    /// Creates options applying the plan, with destroy protection on.
+    pub fn new() -> Self {
+        ApplyOptions {
+            dry_run: false,
+            destroy_protection: true,
+        }
+    }
+
+    /// Sets the `dry_run` field and returns the modified options.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Sets the `destroy_protection` field and returns the modified options.
+    pub fn with_destroy_protection(mut self, destroy_protection: bool) -> Self {
+        self.destroy_protection = destroy_protection;
+        self
+    }
+}
+
+impl Default for ApplyOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StructureAddress {
+    fn new(kind: StructureKind, path: Vec<&str>) -> Self {
+        StructureAddress {
+            kind,
+            path: path.into_iter().map(str::to_string).collect(),
+        }
+    }
+
+    fn child(&self, kind: StructureKind, name: &str) -> Self {
+        let mut path = self.path.clone();
+        path.push(name.to_string());
+        StructureAddress { kind, path }
+    }
+}
+
+impl ChangeAction {
+    /// Returns the symbol of the action in plan listings: `+`, `~` or `-`.
+    pub fn symbol(&self) -> char {
+        match self {
+            ChangeAction::Create => '+',
+            ChangeAction::Update => '~',
+            ChangeAction::Archive => '-',
+        }
+    }
+}
+
+impl fmt::Display for StructureKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            StructureKind::TypeOfWork => "type of work",
+            StructureKind::Customer => "customer",
+            StructureKind::Project => "project",
+            StructureKind::Task => "task",
+        };
+        write!(f, "{}", kind)
+    }
+}
+
+impl fmt::Display for StructureAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} '{}'", self.kind, self.path.join(" / "))
+    }
+}
+
+impl fmt::Display for ChangeAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match self {
+            ChangeAction::Create => "create",
+            ChangeAction::Update => "update",
+            ChangeAction::Archive => "archive",
+        };
+        write!(f, "{}", action)
+    }
+}
+
+/// Formats the change as `~ update project 'Acme / Website' (description)`.
+impl fmt::Display for PlannedChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} ({})",
+            self.action.symbol(),
+            self.action,
+            self.address,
+            self.field_names().join(", ")
+        )
+    }
+}
+
+/// Formats the counts as `2 to create, 1 to update, 0 to archive`.
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} to create, {} to update, {} to archive",
+            self.count(ChangeAction::Create),
+            self.count(ChangeAction::Update),
+            self.count(ChangeAction::Archive)
+        )
+    }
+}
+
+/// Id of a live entity, or address of an entity the plan creates
+enum Reference {
+    Live(i32),
+    Planned(StructureAddress),
+}
+
+/// Collects the changes of a plan: creates and updates in traversal order, archives apart
+#[derive(Default)]
+struct Planner {
+    changes: Vec<PlannedChange>,
+    archives: Vec<PlannedChange>,
+}
+
+impl Planner {
+    fn type_of_work(
+        &mut self,
+        spec: &TypeOfWorkSpec,
+        live: Option<&TypeOfWork>,
+        address: StructureAddress,
+    ) -> Result<(), ClientError> {
+        let mut fields = EntityFields::new();
+        let Some(live) = live else {
+            set(&mut fields, "name", &spec.name)?;
+            set_some(&mut fields, "rate", spec.rate)?;
+            set_some(&mut fields, "billable", spec.billable)?;
+            if spec.archived {
+                set(&mut fields, "archived", true)?;
+            }
+            self.create(address, fields, BTreeMap::new());
+            return Ok(());
+        };
+        if spec.rate.is_some() && spec.rate != live.rate {
+            set_some(&mut fields, "rate", spec.rate)?;
+        }
+        if spec
+            .billable
+            .is_some_and(|billable| billable != live.billable)
+        {
+            set_some(&mut fields, "billable", spec.billable)?;
+        }
+        self.update_and_archive(address, live.id, fields, spec.archived, live.archived)
+    }
+
+    fn customer(
+        &mut self,
+        spec: &CustomerSpec,
+        live: Option<&Customer>,
+        address: StructureAddress,
+    ) -> Result<(), ClientError> {
+        let mut fields = EntityFields::new();
+        let Some(live) = live else {
+            set(&mut fields, "name", &spec.name)?;
+            set_some(&mut fields, "description", spec.description.as_ref())?;
+            if spec.archived {
+                set(&mut fields, "archived", true)?;
+            }
+            self.create(address, fields, BTreeMap::new());
+            return Ok(());
+        };
+        if spec.description.is_some() && spec.description != live.description {
+            set_some(&mut fields, "description", spec.description.as_ref())?;
+        }
+        self.update_and_archive(address, live.id, fields, spec.archived, live.archived)
+    }
+
+    fn project(
+        &mut self,
+        spec: &ProjectSpec,
+        live: Option<&Project>,
+        customer_id: Option<i32>,
+        customer_address: &StructureAddress,
+        address: StructureAddress,
+    ) -> Result<(), ClientError> {
+        let mut fields = EntityFields::new();
+        let Some(live) = live else {
+            let mut references = BTreeMap::new();
+            match customer_id {
+                Some(customer_id) => set(&mut fields, "customerId", customer_id)?,
+                None => {
+                    references.insert("customerId".to_string(), customer_address.clone());
+                }
+            }
+            set(&mut fields, "name", &spec.name)?;
+            set_some(&mut fields, "description", spec.description.as_ref())?;
+            if spec.archived {
+                set(&mut fields, "archived", true)?;
+            }
+            self.create(address, fields, references);
+            return Ok(());
+        };
+        if spec.description.is_some() && spec.description != live.description {
+            set_some(&mut fields, "description", spec.description.as_ref())?;
+        }
+        self.update_and_archive(address, live.id, fields, spec.archived, live.archived)
+    }
+
+    fn task(
+        &mut self,
+        spec: &TaskSpec,
+        live: Option<&Task>,
+        type_of_work: Option<Reference>,
+        project_id: Option<i32>,
+        project_address: &StructureAddress,
+        address: StructureAddress,
+    ) -> Result<(), ClientError> {
+        let mut fields = EntityFields::new();
+        let mut references = BTreeMap::new();
+        let live = match live {
+            Some(live) => live,
+            None => {
+                match project_id {
+                    Some(project_id) => set(&mut fields, "projectId", project_id)?,
+                    None => {
+                        references.insert("projectId".to_string(), project_address.clone());
+                    }
+                }
+                set(&mut fields, "name", &spec.name)?;
+                set_some(&mut fields, "description", spec.description.as_ref())?;
+                set_some(&mut fields, "status", spec.status.as_ref())?;
+                set_some(&mut fields, "deadline", spec.deadline)?;
+                set_some(&mut fields, "estimatedTime", spec.estimated_time)?;
+                match type_of_work {
+                    Some(Reference::Live(id)) => set(&mut fields, "typeOfWorkId", id)?,
+                    Some(Reference::Planned(address)) => {
+                        references.insert("typeOfWorkId".to_string(), address);
+                    }
+                    None => {}
+                }
+                self.create(address, fields, references);
+                return Ok(());
+            }
+        };
+        if spec.description.is_some() && spec.description != live.description {
+            set_some(&mut fields, "description", spec.description.as_ref())?;
+        }
+        if spec.status.is_some() && spec.status != live.status {
+            set_some(&mut fields, "status", spec.status.as_ref())?;
+        }
+        if spec.deadline.is_some() && spec.deadline != live.deadline {
+            set_some(&mut fields, "deadline", spec.deadline)?;
+        }
+        if spec.estimated_time.is_some() && spec.estimated_time != live.estimated_time {
+            set_some(&mut fields, "estimatedTime", spec.estimated_time)?;
+        }
+        match type_of_work {
+            Some(Reference::Live(id)) if live.type_of_work_id != Some(id) => {
+                set(&mut fields, "typeOfWorkId", id)?;
+            }
+            Some(Reference::Planned(address)) => {
+                references.insert("typeOfWorkId".to_string(), address);
+            }
+            _ => {}
+        }
+        if !fields.fields.is_empty() || !references.is_empty() {
+            self.changes.push(PlannedChange {
+                action: ChangeAction::Update,
+                address,
+                id: live.id,
+                fields,
+                references,
+            });
+        }
+        Ok(())
+    }
+
+    fn create(
+        &mut self,
+        address: StructureAddress,
+        fields: EntityFields,
+        references: BTreeMap<String, StructureAddress>,
+    ) {
+        self.changes.push(PlannedChange {
+            action: ChangeAction::Create,
+            address,
+            id: None,
+            fields,
+            references,
+        });
+    }
+
+    /// Plans the field changes of a live entity; archiving is planned apart, restoring from
+    /// the archive is part of the update.
+    fn update_and_archive(
+        &mut self,
+        address: StructureAddress,
+        id: i32,
+        mut fields: EntityFields,
+        archived: bool,
+        live_archived: bool,
+    ) -> Result<(), ClientError> {
+        if live_archived && !archived {
+            set(&mut fields, "archived", false)?;
+        }
+        if !fields.fields.is_empty() {
+            self.changes.push(PlannedChange {
+                action: ChangeAction::Update,
+                address: address.clone(),
+                id: Some(id),
+                fields,
+                references: BTreeMap::new(),
+            });
+        }
+        if archived && !live_archived {
+            self.archive(address, Some(id))?;
+        }
+        Ok(())
+    }
+
+    /// Plans archiving a live entity; tasks are completed instead.
+    fn archive(&mut self, address: StructureAddress, id: Option<i32>) -> Result<(), ClientError> {
+        let mut fields = EntityFields::new();
+        match address.kind {
+            StructureKind::Task => set(&mut fields, "status", TaskStatus::Completed)?,
+            _ => set(&mut fields, "archived", true)?,
+        }
+        self.archives.push(PlannedChange {
+            action: ChangeAction::Archive,
+            address,
+            id,
+            fields,
+            references: BTreeMap::new(),
+        });
+        Ok(())
+    }
+
+    fn into_plan(mut self) -> Plan {
+        self.archives
+            .sort_by_key(|change| std::cmp::Reverse(change.address.kind));
+        self.changes.extend(self.archives);
+        Plan {
+            changes: self.changes,
+        }
+    }
+}
+
+fn set(fields: &mut EntityFields, name: &str, value: impl Serialize) -> Result<(), ClientError> {
+    let value = serde_json::to_value(value).map_err(ClientError::SerdeJson)?;
+    fields.fields.insert(name.to_string(), value);
+    Ok(())
+}
+
+fn set_some(
+    fields: &mut EntityFields,
+    name: &str,
+    value: Option<impl Serialize>,
+) -> Result<(), ClientError> {
+    match value {
+        Some(value) => set(fields, name, value),
+        None => Ok(()),
+    }
+}
+
+/// Returns the only live entity named like the last name of `address`, or an error if several
+/// have that name.
+fn find_live<'a, T: 'a>(
+    entities: impl IntoIterator<Item = &'a T>,
+    address: &StructureAddress,
+    name_and_archived: impl Fn(&T) -> (&str, bool),
+) -> Result<Option<&'a T>, ClientError> {
+    let name = address.path.last().map(String::as_str).unwrap_or_default();
+    match find_by_name(entities, name, name_and_archived).as_slice() {
+        [] => Ok(None),
+        [entity] => Ok(Some(entity)),
+        _ => Err(ClientError::Message(format!(
+            "Several live entities match {}",
+            address
+        ))),
+    }
+}
+
+fn create(
+    client_context: &ClientContext,
+    kind: StructureKind,
+    fields: EntityFields,
+) -> Result<i32, ClientError> {
+    match kind {
+        StructureKind::TypeOfWork => Ok(client_context
+            .call_route(&post_type_of_work(), Some(&fields))?
+            .id),
+        StructureKind::Customer => Ok(client_context
+            .call_route(&post_customer(), Some(&fields))?
+            .id),
+        StructureKind::Project => Ok(client_context
+            .call_route(&post_project(), Some(&fields))?
+            .id),
+        StructureKind::Task => {
+            let task: Task = serde_json::from_value(Value::Object(fields.fields))
+                .map_err(ClientError::SerdeJson)?;
+            client_context
+                .call_route(&post_task(), Some(&task))?
+                .id
+                .ok_or_else(|| ClientError::Message("Created task has no id".to_string()))
+        }
+    }
+}
+
+fn patch(
+    client_context: &ClientContext,
+    kind: StructureKind,
+    id: i32,
+    fields: &EntityFields,
+) -> Result<(), ClientError> {
+    match kind {
+        StructureKind::TypeOfWork => client_context
+            .call_route(&patch_type_of_work(id), Some(fields))
+            .map(|_| ()),
+        StructureKind::Customer => client_context
+            .call_route(&patch_customer(id), Some(fields))
+            .map(|_| ()),
+        StructureKind::Project => client_context
+            .call_route(&patch_project(id), Some(fields))
+            .map(|_| ()),
+        StructureKind::Task => client_context
+            .call_route(&patch_task(id), Some(fields))
+            .map(|_| ()),
+    }
+}
+
+/// Reads a task status, refusing the ones `TaskStatus` does not know.
+fn known_status<'de, D>(deserializer: D) -> Result<Option<TaskStatus>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match Option::<TaskStatus>::deserialize(deserializer)? {
+        Some(TaskStatus::Unknown(status)) => Err(serde::de::Error::custom(format!(
+            "unknown task status '{}', expected 'open' or 'completed'",
+            status
+        ))),
+        status => Ok(status),
+    }
+}
+
+/// Reads a `WorkDuration` from minutes or any text `WorkDuration::from_str` accepts.
+mod duration_text {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::payload::WorkDuration;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum DurationInput {
+        Minutes(i64),
+        Text(String),
+    }
+
+    pub fn serialize<S>(duration: &Option<WorkDuration>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match duration {
+            Some(duration) => serializer.collect_str(duration),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<WorkDuration>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Option::<DurationInput>::deserialize(deserializer)? {
+            Some(DurationInput::Minutes(minutes)) => Ok(Some(WorkDuration::from_minutes(minutes))),
+            Some(DurationInput::Text(text)) => text
+                .parse()
+                .map(Some)
+                .map_err(|_| serde::de::Error::custom(format!("invalid duration '{}'", text))),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    struct Live {
+        types_of_work: Vec<TypeOfWork>,
+        customers: Vec<Customer>,
+        projects: Vec<Project>,
+        tasks: Vec<Task>,
+    }
+
+    fn live() -> Result<Live, serde_json::Error> {
+        let project = |id: i32, name: &str| {
+            json!({"id": id, "customerId": 10, "name": name, "archived": false,
+                "created": "2024-01-01", "url": "u", "customerName": "Acme"})
+        };
+        Ok(Live {
+            types_of_work: serde_json::from_value(json!([
+                {"id": 1, "name": "Consulting", "archived": false, "billable": false, "default": false}
+            ]))?,
+            customers: serde_json::from_value(json!([
+                {"id": 10, "name": "Acme", "archived": false, "created": "2024-01-01", "url": "u"}
+            ]))?,
+            projects: serde_json::from_value(json!([
+                project(20, "Website"),
+                project(21, "Legacy")
+            ]))?,
+            tasks: serde_json::from_value(json!([
+                {"id": 30, "name": "Design", "status": "open", "projectId": 20, "estimatedTime": 60},
+                {"id": 31, "name": "Old task", "status": "open", "projectId": 20},
+                {"id": 32, "name": "Done task", "status": "completed", "projectId": 20}
+            ]))?,
+        })
+    }
+
+    fn plan(spec: serde_json::Value) -> Result<Plan, ClientError> {
+        let spec: StructureSpec = serde_json::from_value(spec).map_err(ClientError::SerdeJson)?;
+        let live = live().map_err(ClientError::SerdeJson)?;
+        spec.check_duplicates()?;
+        spec.plan_live(
+            &live.types_of_work,
+            &live.customers,
+            &live.projects,
+            &live.tasks,
+        )
+    }
+
+    fn changes(plan: &Plan) -> Vec<String> {
+        plan.changes
+            .iter()
+            .map(|change| change.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn plans_creates_updates_and_archives_in_order() -> Result<(), ClientError> {
+        let plan = plan(json!({
+            "types_of_work": [{"name": "Consulting", "billable": true}],
+            "customers": [{"name": "Acme", "projects": [
+                {"name": "Website", "tasks": [
+                    {"name": "Design", "estimated_time": "4h"},
+                    {"name": "Launch", "type_of_work": "Consulting"}
+                ]},
+                {"name": "Mobile", "tasks": [{"name": "Specs"}]}
+            ]}]
+        }))?;
+
+        assert_eq!(
+            changes(&plan),
+            [
+                "~ update type of work 'Consulting' (billable)",
+                "~ update task 'Acme / Website / Design' (estimatedTime)",
+                "+ create task 'Acme / Website / Launch' (name, projectId, typeOfWorkId)",
+                "+ create project 'Acme / Mobile' (customerId, name)",
+                "+ create task 'Acme / Mobile / Specs' (name, projectId)",
+                "- archive task 'Acme / Website / Old task' (status)",
+                "- archive project 'Acme / Legacy' (archived)",
+            ]
+        );
+        assert_eq!(plan.changes[1].id, Some(30));
+        assert_eq!(plan.changes[2].fields.fields["projectId"], json!(20));
+        assert_eq!(plan.changes[2].fields.fields["typeOfWorkId"], json!(1));
+        assert_eq!(
+            plan.changes[4].references["projectId"].to_string(),
+            "project 'Acme / Mobile'"
+        );
+        assert_eq!(plan.changes[5].fields.fields["status"], json!("completed"));
+        assert_eq!(plan.to_string(), "3 to create, 2 to update, 2 to archive");
+        Ok(())
+    }
+
+    #[test]
+    fn matches_live_entities_ignoring_case() -> Result<(), ClientError> {
+        let plan = plan(json!({
+            "customers": [{"name": "acme", "projects": [
+                {"name": "WEBSITE", "tasks": [{"name": "design"}, {"name": "old task"}]},
+                {"name": "legacy"}
+            ]}]
+        }))?;
+        assert!(plan.is_empty(), "{:?}", changes(&plan));
+        Ok(())
+    }
+
+    #[test]
+    fn archives_children_before_parents() -> Result<(), ClientError> {
+        let plan = plan(json!({
+            "types_of_work": [{"name": "Consulting", "archived": true}],
+            "customers": [{"name": "Acme", "archived": true, "projects": [
+                {"name": "Website", "tasks": [{"name": "Design"}, {"name": "Old task"}]},
+                {"name": "Legacy"}
+            ]}]
+        }))?;
+        assert_eq!(
+            changes(&plan),
+            [
+                "- archive customer 'Acme' (archived)",
+                "- archive type of work 'Consulting' (archived)",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn refuses_archives_under_destroy_protection() -> Result<(), ClientError> {
+        let plan = plan(json!({
+            "customers": [{"name": "Acme", "projects": [{"name": "Website"}]}]
+        }))?;
+        let client_context = ClientContext::new("acme".to_string());
+
+        let refused = plan.apply(&client_context, &ApplyOptions::new().with_dry_run(true));
+        assert!(matches!(
+            refused,
+            Err(ClientError::Message(message)) if message.contains("project 'Acme / Legacy'")
+        ));
+
+        let options = ApplyOptions::new()
+            .with_dry_run(true)
+            .with_destroy_protection(false);
+        let applied = plan.apply(&client_context, &options)?;
+        let archived: Vec<(ChangeAction, Option<i32>)> = applied
+            .iter()
+            .map(|change| (change.action, change.id))
+            .collect();
+        assert_eq!(
+            archived,
+            [
+                (ChangeAction::Archive, Some(30)),
+                (ChangeAction::Archive, Some(31)),
+                (ChangeAction::Archive, Some(21)),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_duplicates_and_unknown_references() {
+        let duplicate = plan(json!({"customers": [{"name": "Acme"}, {"name": "ACME"}]}));
+        assert!(matches!(
+            duplicate,
+            Err(ClientError::Message(message)) if message.contains("declared twice")
+        ));
+        let unknown = plan(json!({"customers": [{"name": "Acme", "projects": [
+            {"name": "Website", "tasks": [{"name": "Design", "type_of_work": "Travel"}]}
+        ]}]}));
+        assert!(matches!(
+            unknown,
+            Err(ClientError::Message(message)) if message.contains("unknown type of work 'Travel'")
+        ));
+    }
+
+    #[test]
+    fn rejects_unknown_task_statuses() {
+        let spec = |status: &str| {
+            serde_json::from_value::<TaskSpec>(json!({"name": "Design", "status": status}))
+        };
+        assert!(matches!(
+            spec("completed"),
+            Ok(TaskSpec {
+                status: Some(TaskStatus::Completed),
+                ..
+            })
+        ));
+        assert!(spec("blocked").is_err());
+    }
+}
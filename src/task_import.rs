@@ -0,0 +1,629 @@
+//! Bulk creation of tasks from a CSV file, one task per row.
+//!
+//! ```text
+//! customer,project,task,description,deadline,estimate,type of work,workflow status
+//! Acme,Website relaunch,Design review,,2024-06-30,4h,Consulting,New
+//! Acme,Website relaunch,Copywriting,Landing pages only,,1h 30m,Content,
+//! ```
+//!
+//! Every row is validated and every name resolved before anything is sent: a file with a
+//! single invalid row creates nothing. Rows naming a task that already exists in its project
+//! are skipped, so an import can be run again after a partial failure.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io::Read;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::client_context::{ClientContext, PAGE_SIZE};
+use crate::errors::ClientError;
+use crate::payload::{
+    Customer, EntityFields, Project, Task, TypeOfWork, WorkDuration, WorkflowStatus,
+};
+use crate::reference_cache::{ReferenceDataKind, find_by_name};
+use crate::routes::{GetTasksParameters, post_customer, post_project, post_task};
+
+/// Header names of the CSV columns, matched case-insensitively
+#[derive(Debug, Clone)]
+pub struct TaskImportColumns {
+    /// Customer name (required)
+    pub customer: String,
+    /// Project name (required)
+    pub project: String,
+    /// Task name (required)
+    pub task: String,
+    /// Task description
+    pub description: String,
+    /// Deadline, as `YYYY-MM-DD`
+    pub deadline: String,
+    /// Estimated time, as minutes or text such as `90`, `1h 30m` or `1:30`
+    pub estimate: String,
+    /// Type of work name
+    pub type_of_work: String,
+    /// Workflow status name
+    pub workflow_status: String,
+}
+
+impl Default for TaskImportColumns {
+    fn default() -> Self {
+        TaskImportColumns {
+            customer: "customer".to_string(),
+            project: "project".to_string(),
+            task: "task".to_string(),
+            description: "description".to_string(),
+            deadline: "deadline".to_string(),
+            estimate: "estimate".to_string(),
+            type_of_work: "type of work".to_string(),
+            workflow_status: "workflow status".to_string(),
+        }
+    }
+}
+
+/// Settings of a task import
+#[derive(Debug, Clone, Default)]
+pub struct TaskImport {
+    /// Header names of the CSV columns
+    pub columns: TaskImportColumns,
+    /// Create customers and projects that do not exist instead of rejecting their rows
+    pub create_missing: bool,
+    /// Validate the file without creating anything
+    pub dry_run: bool,
+}
+
+/// Outcome of one CSV row
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RowStatus {
+    /// The row failed validation
+    Invalid,
+    /// The row passed validation; nothing was sent
+    Valid,
+    /// The task already exists in its project
+    Skipped,
+    /// The task was created
+    Created,
+    /// The server refused the task, its project or its customer
+    Failed,
+}
+
+impl fmt::Display for RowStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            RowStatus::Invalid => "invalid",
+            RowStatus::Valid => "valid",
+            RowStatus::Skipped => "skipped",
+            RowStatus::Created => "created",
+            RowStatus::Failed => "failed",
+        };
+        write!(f, "{}", status)
+    }
+}
+
+/// Result of one CSV row
+#[derive(Debug, Clone, Serialize)]
+pub struct RowResult {
+    /// Line of the row in the CSV file
+    pub line: u64,
+    /// Customer name as written in the file
+    pub customer: String,
+    /// Project name as written in the file
+    pub project: String,
+    /// Task name as written in the file
+    pub task: String,
+    /// Outcome of the row
+    pub status: RowStatus,
+    /// ID of the created or existing task
+    pub task_id: Option<i32>,
+    /// Validation errors, server errors and notes about the row
+    pub messages: Vec<String>,
+}
+
+/// Per-row results of a task import
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    /// One result per CSV row, in file order
+    pub rows: Vec<RowResult>,
+    /// Customers created by the import
+    pub created_customers: Vec<String>,
+    /// Projects created by the import, as `customer / project`
+    pub created_projects: Vec<String>,
+}
+
+impl ImportReport {
+    /// Returns the number of rows with the given status.
+    pub fn count(&self, status: RowStatus) -> usize {
+        self.rows.iter().filter(|row| row.status == status).count()
+    }
+
+    /// Returns `true` when no row is invalid or failed.
+    pub fn is_success(&self) -> bool {
+        self.count(RowStatus::Invalid) == 0 && self.count(RowStatus::Failed) == 0
+    }
+}
+
+impl fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} rows: ", self.rows.len())?;
+        let counts: Vec<String> = [
+            RowStatus::Created,
+            RowStatus::Valid,
+            RowStatus::Skipped,
+            RowStatus::Failed,
+            RowStatus::Invalid,
+        ]
+        .into_iter()
+        .map(|status| (status, self.count(status)))
+        .filter(|(_, count)| *count > 0)
+        .map(|(status, count)| format!("{} {}", count, status))
+        .collect();
+        if counts.is_empty() {
+            write!(f, "nothing to import")
+        } else {
+            write!(f, "{}", counts.join(", "))
+        }
+    }
+}
+
+/// Customer or project a row belongs to
+#[derive(Debug, Clone, Copy)]
+enum Target {
+    Existing(i32),
+    Missing,
+}
+
+/// Row parsed from the file, with its task ready to send except for the project
+struct ParsedRow {
+    customer_key: String,
+    project_key: (String, String),
+    customer: Target,
+    project: Target,
+    task: Task,
+}
+
+/// Column indexes of the CSV header
+struct ColumnIndexes {
+    customer: usize,
+    project: usize,
+    task: usize,
+    description: Option<usize>,
+    deadline: Option<usize>,
+    estimate: Option<usize>,
+    type_of_work: Option<usize>,
+    workflow_status: Option<usize>,
+}
+
+impl TaskImport {
+    /// Creates an import with the default column names.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `columns` field and returns the modified import.
+    pub fn with_columns(mut self, columns: TaskImportColumns) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Sets the `create_missing` field and returns the modified import.
+    pub fn with_create_missing(mut self, create_missing: bool) -> Self {
+        self.create_missing = create_missing;
+        self
+    }
+
+    /// Sets the `dry_run` field and returns the modified import.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Validates every row of a CSV file, then creates its tasks unless a row is invalid or
+    /// this is a dry run.
+    ///
+    /// Customers, projects, types of work and workflow statuses are resolved by name,
+    /// ignoring case. A row that fails on the server does not stop the others, and a type of
+    /// work or workflow status that cannot be looked up makes its row invalid.
+    ///
+    /// # Arguments
+    /// * `client_context` - The client context used to resolve names and create tasks.
+    /// * `reader` - The CSV content, with a header row.
+    ///
+    /// # Returns
+    /// The per-row report, or an error if the file cannot be read or lacks a required column.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let file = std::fs::File::open("kickoff.csv")?;
+    /// let report = TaskImport::new()
+    ///     .with_create_missing(true)
+    ///     .run(&client_context, file)?;
+    /// println!("{}", report);
+    /// ```
+    pub fn run(
+        &self,
+        client_context: &ClientContext,
+        reader: impl Read,
+    ) -> Result<ImportReport, ClientError> {
+        let mut csv_reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(reader);
+        let headers = csv_reader
+            .headers()
+            .map_err(|e| ClientError::Other(Box::new(e)))?
+            .clone();
+        let indexes = self.column_indexes(&headers)?;
+
+        client_context.invalidate_reference_data(ReferenceDataKind::Customer);
+        client_context.invalidate_reference_data(ReferenceDataKind::Project);
+        let customers = client_context.reference_data::<Customer>()?;
+        let projects = client_context.reference_data::<Project>()?;
+
+        let mut report = ImportReport::default();
+        let mut parsed_rows: Vec<Option<ParsedRow>> = vec![];
+        let mut first_lines: HashMap<(String, String, String), u64> = HashMap::new();
+        for record in csv_reader.records() {
+            let record = record.map_err(|e| ClientError::Other(Box::new(e)))?;
+            let line = record.position().map(|p| p.line()).unwrap_or_default();
+            let field = |index: usize| record.get(index).unwrap_or_default().to_string();
+            let optional = |index: Option<usize>| {
+                index
+                    .and_then(|index| record.get(index))
+                    .filter(|value| !value.is_empty())
+            };
+
+            let mut row = RowResult {
+                line,
+                customer: field(indexes.customer),
+                project: field(indexes.project),
+                task: field(indexes.task),
+                status: RowStatus::Valid,
+                task_id: None,
+                messages: vec![],
+            };
+            let mut task = Task::new(row.task.clone());
+            let mut errors = vec![];
+
+            for (value, column) in [
+                (&row.customer, &self.columns.customer),
+                (&row.project, &self.columns.project),
+                (&row.task, &self.columns.task),
+            ] {
+                if value.is_empty() {
+                    errors.push(format!("'{}' is empty", column));
+                }
+            }
+
+            let customer_key = row.customer.to_ascii_lowercase();
+            let project_key = (customer_key.clone(), row.project.to_ascii_lowercase());
+            let key = (
+                customer_key.clone(),
+                project_key.1.clone(),
+                row.task.to_ascii_lowercase(),
+            );
+            if let Some(first_line) = first_lines.get(&key) {
+                errors.push(format!("Duplicate of line {}", first_line));
+            } else {
+                first_lines.insert(key, line);
+            }
+
+            let customer =
+                match find_by_name(customers.iter(), &row.customer, |c| (&c.name, c.archived))
+                    .first()
+                    .copied()
+                {
+                    Some(customer) if customer.archived => {
+                        errors.push(format!("Customer '{}' is archived", customer.name));
+                        Target::Existing(customer.id)
+                    }
+                    Some(customer) => Target::Existing(customer.id),
+                    None => Target::Missing,
+                };
+            let project = match customer {
+                Target::Existing(customer_id) => {
+                    let customer_projects = projects
+                        .iter()
+                        .filter(|project| project.customer_id == customer_id);
+                    match find_by_name(customer_projects, &row.project, |p| (&p.name, p.archived))
+                        .first()
+                        .copied()
+                    {
+                        Some(project) if project.archived => {
+                            errors.push(format!("Project '{}' is archived", project.name));
+                            Target::Existing(project.id)
+                        }
+                        Some(project) => Target::Existing(project.id),
+                        None => Target::Missing,
+                    }
+                }
+                Target::Missing => Target::Missing,
+            };
+            if !row.customer.is_empty() && !row.project.is_empty() {
+                match (customer, project, self.create_missing) {
+                    (Target::Missing, _, false) => {
+                        errors.push(format!("Customer '{}' does not exist", row.customer))
+                    }
+                    (_, Target::Missing, false) => {
+                        errors.push(format!("Project '{}' does not exist", row.project))
+                    }
+                    (Target::Missing, _, true) => row
+                        .messages
+                        .push(format!("New customer '{}'", row.customer)),
+                    (_, Target::Missing, true) => {
+                        row.messages.push(format!("New project '{}'", row.project))
+                    }
+                    _ => {}
+                }
+            }
+
+            if let Some(description) = optional(indexes.description) {
+                task = task.with_description(description.to_string());
+            }
+            if let Some(deadline) = optional(indexes.deadline) {
+                match NaiveDate::parse_from_str(deadline, "%Y-%m-%d") {
+                    Ok(deadline) => task = task.with_deadline(deadline),
+                    Err(_) => errors.push(format!(
+                        "Invalid deadline '{}', expected YYYY-MM-DD",
+                        deadline
+                    )),
+                }
+            }
+            if let Some(estimate) = optional(indexes.estimate) {
+                match WorkDuration::from_str(estimate) {
+                    Ok(estimate) if estimate >= WorkDuration::ZERO => {
+                        task = task.with_estimated_time(estimate)
+                    }
+                    _ => errors.push(format!("Invalid estimate '{}'", estimate)),
+                }
+            }
+            if let Some(name) = optional(indexes.type_of_work) {
+                match client_context.reference_by_name::<TypeOfWork>(name) {
+                    Ok(Some(type_of_work)) => task = task.with_type_of_work_id(type_of_work.id),
+                    Ok(None) => errors.push(format!("Type of work '{}' does not exist", name)),
+                    Err(e) => errors.push(format!("Cannot look up type of work '{}': {}", name, e)),
+                }
+            }
+            if let Some(name) = optional(indexes.workflow_status) {
+                match client_context.reference_by_name::<WorkflowStatus>(name) {
+                    Ok(Some(status)) => task = task.with_workflow_status_id(status.id),
+                    Ok(None) => errors.push(format!("Workflow status '{}' does not exist", name)),
+                    Err(e) => {
+                        errors.push(format!("Cannot look up workflow status '{}': {}", name, e))
+                    }
+                }
+            }
+
+            if errors.is_empty() {
+                parsed_rows.push(Some(ParsedRow {
+                    customer_key,
+                    project_key,
+                    customer,
+                    project,
+                    task,
+                }));
+            } else {
+                row.status = RowStatus::Invalid;
+                row.messages = errors;
+                parsed_rows.push(None);
+            }
+            report.rows.push(row);
+        }
+
+        mark_existing_tasks(client_context, &mut report, &parsed_rows)?;
+        if self.dry_run || report.count(RowStatus::Invalid) > 0 {
+            return Ok(report);
+        }
+        create_tasks(client_context, &mut report, parsed_rows);
+        Ok(report)
+    }
+
+    fn column_indexes(&self, headers: &csv::StringRecord) -> Result<ColumnIndexes, ClientError> {
+        let position = |name: &str| {
+            headers
+                .iter()
+                .position(|header| header.eq_ignore_ascii_case(name))
+        };
+        let required = |name: &str| {
+            position(name).ok_or_else(|| ClientError::Message(format!("Missing column '{}'", name)))
+        };
+        Ok(ColumnIndexes {
+            customer: required(&self.columns.customer)?,
+            project: required(&self.columns.project)?,
+            task: required(&self.columns.task)?,
+            description: position(&self.columns.description),
+            deadline: position(&self.columns.deadline),
+            estimate: position(&self.columns.estimate),
+            type_of_work: position(&self.columns.type_of_work),
+            workflow_status: position(&self.columns.workflow_status),
+        })
+    }
+}
+
+/// Marks the valid rows whose task already exists in its project as skipped.
+fn mark_existing_tasks(
+    client_context: &ClientContext,
+    report: &mut ImportReport,
+    parsed_rows: &[Option<ParsedRow>],
+) -> Result<(), ClientError> {
+    let mut project_ids: Vec<u32> = parsed_rows
+        .iter()
+        .flatten()
+        .filter_map(|row| match row.project {
+            Target::Existing(id) => u32::try_from(id).ok(),
+            Target::Missing => None,
+        })
+        .collect();
+    project_ids.sort_unstable();
+    project_ids.dedup();
+    if project_ids.is_empty() {
+        return Ok(());
+    }
+
+    let mut existing = vec![];
+    for chunk in project_ids.chunks(PAGE_SIZE as usize) {
+        let parameters = GetTasksParameters::new().with_project_ids(chunk.to_vec());
+        existing.extend(client_context.get_all_tasks(parameters)?);
+    }
+    for (row, parsed) in report.rows.iter_mut().zip(parsed_rows) {
+        let Some(ParsedRow {
+            project: Target::Existing(project_id),
+            task,
+            ..
+        }) = parsed
+        else {
+            continue;
+        };
+        if let Some(existing) = existing.iter().find(|existing| {
+            existing.project_id == Some(*project_id)
+                && existing.name.eq_ignore_ascii_case(&task.name)
+        }) {
+            row.status = RowStatus::Skipped;
+            row.task_id = existing.id;
+            row.messages.push("Task already exists".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Creates the missing customers and projects, then the tasks of the valid rows.
+fn create_tasks(
+    client_context: &ClientContext,
+    report: &mut ImportReport,
+    parsed_rows: Vec<Option<ParsedRow>>,
+) {
+    let mut customer_ids: BTreeMap<String, Result<i32, String>> = BTreeMap::new();
+    let mut project_ids: BTreeMap<(String, String), Result<i32, String>> = BTreeMap::new();
+
+    for (row, parsed) in report.rows.iter_mut().zip(parsed_rows) {
+        let Some(parsed) = parsed else { continue };
+        if row.status != RowStatus::Valid {
+            continue;
+        }
+
+        let customer_id = match parsed.customer {
+            Target::Existing(id) => Ok(id),
+            Target::Missing => customer_ids
+                .entry(parsed.customer_key)
+                .or_insert_with(|| {
+                    let created = create_customer(client_context, &row.customer);
+                    if created.is_ok() {
+                        report.created_customers.push(row.customer.clone());
+                    }
+                    created
+                })
+                .clone(),
+        };
+        let project_id = match (parsed.project, customer_id) {
+            (Target::Existing(id), _) => Ok(id),
+            (Target::Missing, Ok(customer_id)) => project_ids
+                .entry(parsed.project_key)
+                .or_insert_with(|| {
+                    let created = create_project(client_context, customer_id, &row.project);
+                    if created.is_ok() {
+                        report
+                            .created_projects
+                            .push(format!("{} / {}", row.customer, row.project));
+                    }
+                    created
+                })
+                .clone(),
+            (Target::Missing, Err(error)) => Err(error),
+        };
+
+        let result = project_id.and_then(|project_id| {
+            client_context
+                .call_route(&post_task(), Some(&parsed.task.with_project_id(project_id)))
+                .map_err(|e| e.to_string())
+        });
+        match result {
+            Ok(task) => {
+                row.status = RowStatus::Created;
+                row.task_id = task.id;
+            }
+            Err(error) => {
+                row.status = RowStatus::Failed;
+                row.messages.push(error);
+            }
+        }
+    }
+}
+
+fn create_customer(client_context: &ClientContext, name: &str) -> Result<i32, String> {
+    let fields = EntityFields::new()
+        .with_field("name", name)
+        .map_err(|e| e.to_string())?;
+    client_context
+        .call_route(&post_customer(), Some(&fields))
+        .map(|customer| customer.id)
+        .map_err(|e| format!("Cannot create customer '{}': {}", name, e))
+}
+
+fn create_project(
+    client_context: &ClientContext,
+    customer_id: i32,
+    name: &str,
+) -> Result<i32, String> {
+    let fields = EntityFields::new()
+        .with_field("customerId", customer_id)
+        .and_then(|fields| fields.with_field("name", name))
+        .map_err(|e| e.to_string())?;
+    client_context
+        .call_route(&post_project(), Some(&fields))
+        .map(|project| project.id)
+        .map_err(|e| format!("Cannot create project '{}': {}", name, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(line: &str) -> csv::StringRecord {
+        csv::StringRecord::from(line.split(',').collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn finds_columns_ignoring_case() -> Result<(), ClientError> {
+        let import = TaskImport::new();
+        let indexes = import.column_indexes(&headers("Task,Customer,PROJECT,Deadline"))?;
+        assert_eq!((indexes.customer, indexes.project, indexes.task), (1, 2, 0));
+        assert_eq!(indexes.deadline, Some(3));
+        assert_eq!(indexes.estimate, None);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_missing_required_columns() {
+        let import = TaskImport::new();
+        assert!(import.column_indexes(&headers("customer,task")).is_err());
+
+        let columns = TaskImportColumns {
+            project: "job".to_string(),
+            ..TaskImportColumns::default()
+        };
+        let import = TaskImport::new().with_columns(columns);
+        assert!(import.column_indexes(&headers("customer,job,task")).is_ok());
+    }
+
+    #[test]
+    fn summarizes_row_statuses() {
+        let row = |status| RowResult {
+            line: 2,
+            customer: "Acme".to_string(),
+            project: "Website".to_string(),
+            task: "Design".to_string(),
+            status,
+            task_id: None,
+            messages: vec![],
+        };
+        let mut report = ImportReport::default();
+        assert_eq!(report.to_string(), "0 rows: nothing to import");
+        report.rows = vec![
+            row(RowStatus::Created),
+            row(RowStatus::Created),
+            row(RowStatus::Invalid),
+        ];
+        assert_eq!(report.to_string(), "3 rows: 2 created, 1 invalid");
+        assert!(!report.is_success());
+    }
+}
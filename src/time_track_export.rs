@@ -0,0 +1,724 @@
+//! Export of tracked time for payroll and invoicing.
+//!
+//! Time-track records are joined with the names of their user, customer, project, task and
+//! type of work, then optionally grouped and sorted, and written as CSV (`csv` feature),
+//! NDJSON or XLSX (`xlsx` feature).
+//!
+//! ```ignore
+//! let export = TimeTrackExport::new()
+//!     .with_columns(vec![ExportColumn::User, ExportColumn::Project, ExportColumn::Hours])
+//!     .with_group_by(vec![ExportColumn::User, ExportColumn::Project]);
+//! let rows = export.rows(&client_context, GetTimeTrackParameters::new().with_dates(from..=to))?;
+//! export.write(&rows, ExportFormat::Csv, std::io::stdout())?;
+//! ```
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io::Write;
+
+use chrono::NaiveDate;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde_json::Value;
+
+use crate::client_context::{ClientContext, PAGE_SIZE};
+use crate::errors::ClientError;
+use crate::payload::{Customer, Project, Task, TypeOfWork, WorkDuration};
+use crate::routes::{GetTasksParameters, GetTimeTrackParameters, GetUsersParameters};
+
+/// Column of an export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExportColumn {
+    /// Day the time was tracked on
+    Date,
+    /// Identifier of the user
+    UserId,
+    /// Username of the user
+    User,
+    /// Full name of the user
+    UserName,
+    /// Identifier of the customer
+    CustomerId,
+    /// Name of the customer
+    Customer,
+    /// Identifier of the project
+    ProjectId,
+    /// Name of the project
+    Project,
+    /// Identifier of the task
+    TaskId,
+    /// Name of the task
+    Task,
+    /// Name of the type of work of the task
+    TypeOfWork,
+    /// Whether the type of work of the task is billable
+    Billable,
+    /// Tracked time in minutes
+    Minutes,
+    /// Tracked time in decimal hours
+    Hours,
+    /// Comment entered with the time
+    Comment,
+}
+
+impl ExportColumn {
+    /// Every column, in their default order.
+    pub const ALL: [ExportColumn; 15] = [
+        ExportColumn::Date,
+        ExportColumn::UserId,
+        ExportColumn::User,
+        ExportColumn::UserName,
+        ExportColumn::CustomerId,
+        ExportColumn::Customer,
+        ExportColumn::ProjectId,
+        ExportColumn::Project,
+        ExportColumn::TaskId,
+        ExportColumn::Task,
+        ExportColumn::TypeOfWork,
+        ExportColumn::Billable,
+        ExportColumn::Minutes,
+        ExportColumn::Hours,
+        ExportColumn::Comment,
+    ];
+
+    /// Returns the header of the column, also used as NDJSON key.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportColumn::Date => "date",
+            ExportColumn::UserId => "user_id",
+            ExportColumn::User => "user",
+            ExportColumn::UserName => "user_name",
+            ExportColumn::CustomerId => "customer_id",
+            ExportColumn::Customer => "customer",
+            ExportColumn::ProjectId => "project_id",
+            ExportColumn::Project => "project",
+            ExportColumn::TaskId => "task_id",
+            ExportColumn::Task => "task",
+            ExportColumn::TypeOfWork => "type_of_work",
+            ExportColumn::Billable => "billable",
+            ExportColumn::Minutes => "minutes",
+            ExportColumn::Hours => "hours",
+            ExportColumn::Comment => "comment",
+        }
+    }
+
+    /// Returns `true` for the columns summed when rows are grouped.
+    pub fn is_time(&self) -> bool {
+        matches!(self, ExportColumn::Minutes | ExportColumn::Hours)
+    }
+}
+
+impl fmt::Display for ExportColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// File format of an export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Comma-separated values with a header row; needs the `csv` feature
+    Csv,
+    /// One JSON object per line
+    Ndjson,
+    /// Excel workbook with one sheet; needs the `xlsx` feature
+    Xlsx,
+}
+
+/// Value of one cell of an export
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportValue {
+    /// No value, such as the comment of a record without one
+    Empty,
+    /// Text
+    Text(String),
+    /// Identifier or number of minutes
+    Integer(i64),
+    /// Decimal number of hours
+    Decimal(f64),
+    /// Day
+    Date(NaiveDate),
+    /// Flag
+    Bool(bool),
+}
+
+impl ExportValue {
+    fn compare(&self, other: &ExportValue) -> Ordering {
+        match (self, other) {
+            (ExportValue::Text(a), ExportValue::Text(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+            (ExportValue::Integer(a), ExportValue::Integer(b)) => a.cmp(b),
+            (ExportValue::Decimal(a), ExportValue::Decimal(b)) => a.total_cmp(b),
+            (ExportValue::Date(a), ExportValue::Date(b)) => a.cmp(b),
+            (ExportValue::Bool(a), ExportValue::Bool(b)) => a.cmp(b),
+            (ExportValue::Empty, ExportValue::Empty) => Ordering::Equal,
+            (ExportValue::Empty, _) => Ordering::Greater,
+            (_, ExportValue::Empty) => Ordering::Less,
+            _ => Ordering::Equal,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        match self {
+            ExportValue::Empty => Value::Null,
+            ExportValue::Text(text) => Value::from(text.as_str()),
+            ExportValue::Integer(number) => Value::from(*number),
+            ExportValue::Decimal(number) => Value::from(*number),
+            ExportValue::Date(date) => Value::from(date.format("%Y-%m-%d").to_string()),
+            ExportValue::Bool(flag) => Value::from(*flag),
+        }
+    }
+}
+
+impl fmt::Display for ExportValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportValue::Empty => Ok(()),
+            ExportValue::Text(text) => write!(f, "{}", text),
+            ExportValue::Integer(number) => write!(f, "{}", number),
+            ExportValue::Decimal(number) => write!(f, "{:.2}", number),
+            ExportValue::Date(date) => write!(f, "{}", date.format("%Y-%m-%d")),
+            ExportValue::Bool(flag) => write!(f, "{}", flag),
+        }
+    }
+}
+
+/// Time-track record joined with the names of the entities it refers to
+#[derive(Debug, Clone)]
+pub struct ExportRow {
+    /// Day the time was tracked on
+    pub date: NaiveDate,
+    /// Identifier of the user
+    pub user_id: i32,
+    /// Username of the user
+    pub user: Option<String>,
+    /// Full name of the user
+    pub user_name: Option<String>,
+    /// Identifier of the customer
+    pub customer_id: Option<i32>,
+    /// Name of the customer
+    pub customer: Option<String>,
+    /// Identifier of the project
+    pub project_id: Option<i32>,
+    /// Name of the project
+    pub project: Option<String>,
+    /// Identifier of the task
+    pub task_id: i32,
+    /// Name of the task
+    pub task: Option<String>,
+    /// Name of the type of work of the task
+    pub type_of_work: Option<String>,
+    /// Whether the type of work of the task is billable
+    pub billable: Option<bool>,
+    /// Hourly rate of the type of work of the task
+    pub rate: Option<f64>,
+    /// Tracked time, summed over the group when rows are grouped
+    pub time: WorkDuration,
+    /// Comment entered with the time; dropped when rows are grouped
+    pub comment: Option<String>,
+}
+
+impl ExportRow {
+    /// Returns the value of a column.
+    pub fn value(&self, column: ExportColumn) -> ExportValue {
+        let text = |value: &Option<String>| match value {
+            Some(text) => ExportValue::Text(text.clone()),
+            None => ExportValue::Empty,
+        };
+        let id = |value: Option<i32>| match value {
+            Some(id) => ExportValue::Integer(i64::from(id)),
+            None => ExportValue::Empty,
+        };
+        match column {
+            ExportColumn::Date => ExportValue::Date(self.date),
+            ExportColumn::UserId => id(Some(self.user_id)),
+            ExportColumn::User => text(&self.user),
+            ExportColumn::UserName => text(&self.user_name),
+            ExportColumn::CustomerId => id(self.customer_id),
+            ExportColumn::Customer => text(&self.customer),
+            ExportColumn::ProjectId => id(self.project_id),
+            ExportColumn::Project => text(&self.project),
+            ExportColumn::TaskId => id(Some(self.task_id)),
+            ExportColumn::Task => text(&self.task),
+            ExportColumn::TypeOfWork => text(&self.type_of_work),
+            ExportColumn::Billable => match self.billable {
+                Some(billable) => ExportValue::Bool(billable),
+                None => ExportValue::Empty,
+            },
+            ExportColumn::Minutes => ExportValue::Integer(self.time.minutes()),
+            ExportColumn::Hours => ExportValue::Decimal(self.time.hours()),
+            ExportColumn::Comment => text(&self.comment),
+        }
+    }
+}
+
+/// Settings of a time-track export
+#[derive(Debug, Clone)]
+pub struct TimeTrackExport {
+    /// Columns written, in order
+    pub columns: Vec<ExportColumn>,
+    /// Columns the rows are sorted by, most significant first
+    pub sort_by: Vec<ExportColumn>,
+    /// Columns the rows are grouped by; empty to export one row per record
+    pub group_by: Vec<ExportColumn>,
+}
+
+impl Default for TimeTrackExport {
+    fn default() -> Self {
+        TimeTrackExport {
+            columns: vec![
+                ExportColumn::Date,
+                ExportColumn::User,
+                ExportColumn::Customer,
+                ExportColumn::Project,
+                ExportColumn::Task,
+                ExportColumn::TypeOfWork,
+                ExportColumn::Billable,
+                ExportColumn::Hours,
+                ExportColumn::Comment,
+            ],
+            sort_by: vec![
+                ExportColumn::Date,
+                ExportColumn::User,
+                ExportColumn::Customer,
+                ExportColumn::Project,
+                ExportColumn::Task,
+            ],
+            group_by: vec![],
+        }
+    }
+}
+
+impl TimeTrackExport {
+    /// Creates an export with the default columns and sort order, one row per record.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `columns` field and returns the modified export.
+    pub fn with_columns(mut self, columns: Vec<ExportColumn>) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Sets the `sort_by` field and returns the modified export.
+    ///
+    /// When rows are grouped, only the grouped and time columns are sorted by, followed by the
+    /// `group_by` columns.
+    pub fn with_sort_by(mut self, sort_by: Vec<ExportColumn>) -> Self {
+        self.sort_by = sort_by;
+        self
+    }
+
+    /// Sets the `group_by` field and returns the modified export.
+    ///
+    /// Rows sharing the values of these columns are merged and their time summed. Columns
+    /// that are neither grouped nor time columns are left out of the output.
+    pub fn with_group_by(mut self, group_by: Vec<ExportColumn>) -> Self {
+        self.group_by = group_by;
+        self
+    }
+
+    /// Returns the columns written, after grouping is taken into account.
+    pub fn output_columns(&self) -> Vec<ExportColumn> {
+        if self.group_by.is_empty() {
+            return self.columns.clone();
+        }
+        self.columns
+            .iter()
+            .copied()
+            .filter(|column| column.is_time() || self.group_by.contains(column))
+            .collect()
+    }
+
+    /// Fetches the time-track matching `parameters` and turns it into grouped, sorted rows.
+    ///
+    /// # Arguments
+    /// * `client_context` - The client context used to fetch the records and names.
+    /// * `parameters` - The time-track filters; `with_dates` should be set.
+    ///
+    /// # Returns
+    /// The rows, ready for `write`.
+    pub fn rows(
+        &self,
+        client_context: &ClientContext,
+        parameters: GetTimeTrackParameters,
+    ) -> Result<Vec<ExportRow>, ClientError> {
+        let time_track = client_context.get_complete_time_track(parameters)?;
+
+        let mut task_ids: Vec<u32> = time_track
+            .entries()
+            .filter_map(|entry| u32::try_from(entry.record.task_id).ok())
+            .collect();
+        task_ids.sort_unstable();
+        task_ids.dedup();
+        let mut tasks: HashMap<i32, Task> = HashMap::new();
+        for chunk in task_ids.chunks(PAGE_SIZE as usize) {
+            let parameters = GetTasksParameters::new().with_task_ids(chunk.to_vec());
+            for task in client_context.get_all_tasks(parameters)? {
+                if let Some(id) = task.id {
+                    tasks.insert(id, task);
+                }
+            }
+        }
+        let users: HashMap<i32, _> = client_context
+            .get_all_users(GetUsersParameters::new())?
+            .into_iter()
+            .map(|user| (user.id, user))
+            .collect();
+        let customers = client_context.reference_data::<Customer>()?;
+        let projects = client_context.reference_data::<Project>()?;
+        let types_of_work = client_context.reference_data::<TypeOfWork>()?;
+
+        let rows = time_track
+            .entries()
+            .map(|entry| {
+                let task = tasks.get(&entry.record.task_id);
+                let user = users.get(&entry.user_id);
+                let customer_id = task.and_then(|task| task.customer_id);
+                let project_id = task.and_then(|task| task.project_id);
+                // Tasks without a type of work fall under the default one
+                let type_of_work = match task.and_then(|task| task.type_of_work_id) {
+                    Some(id) => types_of_work.iter().find(|item| item.id == id),
+                    None => types_of_work.iter().find(|item| item.default),
+                };
+                ExportRow {
+                    date: entry.date,
+                    user_id: entry.user_id,
+                    user: user.map(|user| user.username.clone()),
+                    user_name: user.map(|user| {
+                        user.full_name
+                            .clone()
+                            .unwrap_or_else(|| format!("{} {}", user.first_name, user.last_name))
+                    }),
+                    customer_id,
+                    customer: customer_id
+                        .and_then(|id| customers.iter().find(|customer| customer.id == id))
+                        .map(|customer| customer.name.clone())
+                        .or_else(|| task.and_then(|task| task.customer_name.clone())),
+                    project_id,
+                    project: project_id
+                        .and_then(|id| projects.iter().find(|project| project.id == id))
+                        .map(|project| project.name.clone())
+                        .or_else(|| task.and_then(|task| task.project_name.clone())),
+                    task_id: entry.record.task_id,
+                    task: task.map(|task| task.name.clone()),
+                    type_of_work: type_of_work.map(|item| item.name.clone()),
+                    billable: type_of_work.map(|item| item.billable),
+                    rate: type_of_work.and_then(|item| item.rate),
+                    time: entry.record.time,
+                    comment: entry.record.comment.clone(),
+                }
+            })
+            .collect();
+        Ok(self.arrange(rows))
+    }
+
+    /// Returns the columns the rows are sorted by, most significant first.
+    ///
+    /// Grouped rows are only sorted by columns they output: hidden columns such as the date
+    /// hold the value of an arbitrary row of the group.
+    pub fn sort_columns(&self) -> Vec<ExportColumn> {
+        if self.group_by.is_empty() {
+            return self.sort_by.clone();
+        }
+        let mut columns: Vec<ExportColumn> = self
+            .sort_by
+            .iter()
+            .copied()
+            .filter(|column| column.is_time() || self.group_by.contains(column))
+            .collect();
+        for column in &self.group_by {
+            if !columns.contains(column) {
+                columns.push(*column);
+            }
+        }
+        columns
+    }
+
+    /// Groups and sorts rows according to `group_by` and `sort_columns`.
+    pub fn arrange(&self, rows: Vec<ExportRow>) -> Vec<ExportRow> {
+        let mut rows = if self.group_by.is_empty() {
+            rows
+        } else {
+            let mut groups: BTreeMap<Vec<String>, ExportRow> = BTreeMap::new();
+            for row in rows {
+                let key = self
+                    .group_by
+                    .iter()
+                    .map(|column| row.value(*column).to_string())
+                    .collect();
+                match groups.get_mut(&key) {
+                    Some(group) => group.time += row.time,
+                    None => {
+                        groups.insert(
+                            key,
+                            ExportRow {
+                                comment: None,
+                                ..row
+                            },
+                        );
+                    }
+                }
+            }
+            groups.into_values().collect()
+        };
+        let sort_columns = self.sort_columns();
+        rows.sort_by(|a, b| {
+            sort_columns
+                .iter()
+                .map(|column| a.value(*column).compare(&b.value(*column)))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+        rows
+    }
+
+    /// Writes rows in a file format.
+    ///
+    /// # Returns
+    /// `Ok(())`, or an error if writing fails or the format's feature is not enabled.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let file = std::fs::File::create("march.xlsx")?;
+    /// export.write(&rows, ExportFormat::Xlsx, file)?;
+    /// ```
+    pub fn write(
+        &self,
+        rows: &[ExportRow],
+        format: ExportFormat,
+        writer: impl Write,
+    ) -> Result<(), ClientError> {
+        match format {
+            ExportFormat::Csv => self.write_csv(rows, writer),
+            ExportFormat::Ndjson => self.write_ndjson(rows, writer),
+            ExportFormat::Xlsx => self.write_xlsx(rows, writer),
+        }
+    }
+
+    #[cfg(feature = "csv")]
+    fn write_csv(&self, rows: &[ExportRow], writer: impl Write) -> Result<(), ClientError> {
+        let other = |e: csv::Error| ClientError::Other(Box::new(e));
+        let columns = self.output_columns();
+        let mut csv_writer = csv::Writer::from_writer(writer);
+        csv_writer
+            .write_record(columns.iter().map(ExportColumn::name))
+            .map_err(other)?;
+        for row in rows {
+            csv_writer
+                .write_record(columns.iter().map(|column| row.value(*column).to_string()))
+                .map_err(other)?;
+        }
+        csv_writer
+            .flush()
+            .map_err(|e| ClientError::Other(Box::new(e)))
+    }
+
+    #[cfg(not(feature = "csv"))]
+    fn write_csv(&self, _rows: &[ExportRow], _writer: impl Write) -> Result<(), ClientError> {
+        Err(ClientError::Message(
+            "CSV export needs the `csv` feature".to_string(),
+        ))
+    }
+
+    fn write_ndjson(&self, rows: &[ExportRow], mut writer: impl Write) -> Result<(), ClientError> {
+        let columns = self.output_columns();
+        for row in rows {
+            let line = NdjsonLine {
+                columns: &columns,
+                row,
+            };
+            serde_json::to_writer(&mut writer, &line).map_err(ClientError::SerdeJson)?;
+            writeln!(writer).map_err(|e| ClientError::Other(Box::new(e)))?;
+        }
+        writer.flush().map_err(|e| ClientError::Other(Box::new(e)))
+    }
+
+    #[cfg(feature = "xlsx")]
+    fn write_xlsx(&self, rows: &[ExportRow], mut writer: impl Write) -> Result<(), ClientError> {
+        use rust_xlsxwriter::{Format, Workbook};
+
+        let other = |e: rust_xlsxwriter::XlsxError| ClientError::Other(Box::new(e));
+        let columns = self.output_columns();
+        let header_format = Format::new().set_bold();
+        let date_format = Format::new().set_num_format("yyyy-mm-dd");
+        let hours_format = Format::new().set_num_format("0.00");
+
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name("Time-track").map_err(other)?;
+        for (col, column) in columns.iter().enumerate() {
+            let col = col as u16;
+            worksheet
+                .write_string_with_format(0, col, column.name(), &header_format)
+                .map_err(other)?;
+        }
+        for (index, row) in rows.iter().enumerate() {
+            let row_number = index as u32 + 1;
+            for (col, column) in columns.iter().enumerate() {
+                let col = col as u16;
+                match row.value(*column) {
+                    ExportValue::Empty => continue,
+                    ExportValue::Text(text) => worksheet.write_string(row_number, col, text),
+                    ExportValue::Integer(number) => {
+                        worksheet.write_number(row_number, col, number as f64)
+                    }
+                    ExportValue::Decimal(number) => {
+                        worksheet.write_number_with_format(row_number, col, number, &hours_format)
+                    }
+                    ExportValue::Date(date) => {
+                        worksheet.write_date_with_format(row_number, col, date, &date_format)
+                    }
+                    ExportValue::Bool(flag) => worksheet.write_boolean(row_number, col, flag),
+                }
+                .map_err(other)?;
+            }
+        }
+        worksheet.set_freeze_panes(1, 0).map_err(other)?;
+        worksheet.autofit();
+
+        let buffer = workbook.save_to_buffer().map_err(other)?;
+        writer
+            .write_all(&buffer)
+            .and_then(|()| writer.flush())
+            .map_err(|e| ClientError::Other(Box::new(e)))
+    }
+
+    #[cfg(not(feature = "xlsx"))]
+    fn write_xlsx(&self, _rows: &[ExportRow], _writer: impl Write) -> Result<(), ClientError> {
+        Err(ClientError::Message(
+            "XLSX export needs the `xlsx` feature".to_string(),
+        ))
+    }
+}
+
+/// Row written as a JSON object keyed by column name, in column order
+struct NdjsonLine<'a> {
+    columns: &'a [ExportColumn],
+    row: &'a ExportRow,
+}
+
+impl Serialize for NdjsonLine<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.columns.len()))?;
+        for column in self.columns {
+            map.serialize_entry(column.name(), &self.row.value(*column).to_json())?;
+        }
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap_or_default()
+    }
+
+    fn row(day: u32, user: &str, project: &str, minutes: i64, comment: &str) -> ExportRow {
+        ExportRow {
+            date: date(day),
+            user_id: 1,
+            user: Some(user.to_string()),
+            user_name: None,
+            customer_id: Some(1),
+            customer: Some("Acme".to_string()),
+            project_id: Some(1),
+            project: Some(project.to_string()),
+            task_id: 1,
+            task: Some("Design".to_string()),
+            type_of_work: None,
+            billable: None,
+            rate: None,
+            time: WorkDuration::from_minutes(minutes),
+            comment: Some(comment.to_string()),
+        }
+    }
+
+    fn names(rows: &[ExportRow], column: ExportColumn) -> Vec<String> {
+        rows.iter()
+            .map(|row| row.value(column).to_string())
+            .collect()
+    }
+
+    #[test]
+    fn sorts_records_by_sort_by() {
+        let rows = vec![
+            row(2, "bob", "Web", 30, ""),
+            row(1, "carol", "Web", 30, ""),
+            row(1, "Alice", "Web", 30, ""),
+        ];
+        let rows = TimeTrackExport::new().arrange(rows);
+        assert_eq!(names(&rows, ExportColumn::User), ["Alice", "carol", "bob"]);
+    }
+
+    #[test]
+    fn groups_and_sums_rows() {
+        let export =
+            TimeTrackExport::new().with_group_by(vec![ExportColumn::User, ExportColumn::Project]);
+        let rows = export.arrange(vec![
+            row(3, "bob", "Web", 30, "b"),
+            row(1, "alice", "Web", 15, "x"),
+            row(2, "alice", "App", 60, "y"),
+            row(4, "alice", "Web", 45, "z"),
+        ]);
+        assert_eq!(names(&rows, ExportColumn::User), ["alice", "alice", "bob"]);
+        assert_eq!(names(&rows, ExportColumn::Project), ["App", "Web", "Web"]);
+        assert_eq!(names(&rows, ExportColumn::Minutes), ["60", "60", "30"]);
+        assert!(rows.iter().all(|row| row.comment.is_none()));
+    }
+
+    #[test]
+    fn sorts_groups_by_output_columns_only() {
+        let export = TimeTrackExport::new()
+            .with_group_by(vec![ExportColumn::Project])
+            .with_sort_by(vec![ExportColumn::Date, ExportColumn::Minutes]);
+        assert_eq!(
+            export.sort_columns(),
+            [ExportColumn::Minutes, ExportColumn::Project]
+        );
+        let rows = export.arrange(vec![
+            row(1, "alice", "Web", 90, ""),
+            row(2, "alice", "App", 30, ""),
+            row(3, "alice", "Api", 30, ""),
+        ]);
+        assert_eq!(names(&rows, ExportColumn::Project), ["Api", "App", "Web"]);
+    }
+
+    #[test]
+    fn keeps_grouped_and_time_columns() {
+        let export = TimeTrackExport::new();
+        assert_eq!(export.output_columns(), export.columns);
+        let export = export.with_group_by(vec![ExportColumn::Project, ExportColumn::User]);
+        assert_eq!(
+            export.output_columns(),
+            [
+                ExportColumn::User,
+                ExportColumn::Project,
+                ExportColumn::Hours
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_ndjson_keys_in_column_order() -> Result<(), ClientError> {
+        let export = TimeTrackExport::new().with_columns(vec![
+            ExportColumn::Minutes,
+            ExportColumn::Date,
+            ExportColumn::Comment,
+            ExportColumn::Billable,
+        ]);
+        let mut output = vec![];
+        export.write(
+            &[row(1, "alice", "Web", 90, "Mockups")],
+            ExportFormat::Ndjson,
+            &mut output,
+        )?;
+        assert_eq!(
+            String::from_utf8_lossy(&output),
+            "{\"minutes\":90,\"date\":\"2024-03-01\",\"comment\":\"Mockups\",\"billable\":null}\n"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,1031 @@
+//! Import of time tracked in other tools into actiTIME time-track records.
+//!
+//! Three CSV layouts are read, see `TimeTrackSource`: Toggl and Harvest detailed exports, and
+//! a generic file with one entry per row:
+//!
+//! ```text
+//! user,date,customer,project,task,time,comment
+//! jdoe,2024-03-04,Acme,Website relaunch,Design review,1h 30m,Wireframes
+//! ```
+//!
+//! Names are resolved to ids with a `TimeTrackMapping`, falling back to matching by name.
+//! Entries of the same user, day and task are summed into one record, then every day is
+//! checked against the user's schedule, leave time and already tracked time before anything
+//! is sent. Records are submitted through batch requests.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::io::Read;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::client_context::{ClientContext, PAGE_SIZE};
+use crate::errors::ClientError;
+use crate::payload::{
+    BatchRequestItem, Customer, EntityFields, Project, Task, TimeTrackRecord, User, WorkDuration,
+};
+use crate::routes::{
+    GetLeaveTimeParameters, GetTasksParameters, GetTimeTrackParameters, GetUsersParameters,
+    PostBatchRequestsIncludeResponseBody, get_users_schedule, patch_time_track,
+    post_batch_requests,
+};
+
+/// Number of records submitted per batch request
+const BATCH_SIZE: usize = 100;
+
+/// Longest time a user can track on one day
+const MAX_DAY_TIME: WorkDuration = WorkDuration::from_hours(24);
+
+/// Layout of the CSV file to import
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimeTrackSource {
+    /// `user`, `date` (`YYYY-MM-DD`), `customer` (optional), `project`, `task`, `time` (such as
+    /// `90`, `1h 30m` or `1:30`) and `comment` (optional) columns
+    #[default]
+    Generic,
+    /// Toggl detailed report: `User`, `Email`, `Client`, `Project`, `Task`, `Description`,
+    /// `Start date` and `Duration` (`HH:MM:SS`) columns. `Description` stands in for an
+    /// empty `Task` and becomes the comment.
+    Toggl,
+    /// Harvest detailed time report: `Date`, `Client`, `Project`, `Task`, `Notes`, `Hours`
+    /// (decimal), `First Name` and `Last Name` columns
+    Harvest,
+}
+
+impl fmt::Display for TimeTrackSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let source = match self {
+            TimeTrackSource::Generic => "generic",
+            TimeTrackSource::Toggl => "toggl",
+            TimeTrackSource::Harvest => "harvest",
+        };
+        write!(f, "{}", source)
+    }
+}
+
+/// Rules translating the names used in the source tool to actiTIME ids
+///
+/// Keys are matched ignoring case. Names without a rule are matched against actiTIME names:
+/// users by username, full name or email, projects by name (within the customer when the
+/// source has one), tasks by name within their project.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TimeTrackMapping {
+    /// User names or emails of the source, mapped to `User.id`
+    #[serde(default)]
+    pub users: BTreeMap<String, i32>,
+    /// Project names of the source, mapped to `Project.id`
+    #[serde(default)]
+    pub projects: BTreeMap<String, i32>,
+    /// Task names, or `project / task` pairs, of the source, mapped to `Task.id`
+    #[serde(default)]
+    pub tasks: BTreeMap<String, i32>,
+}
+
+impl TimeTrackMapping {
+    /// Parses mapping rules written in TOML.
+    ///
+    /// ```toml
+    /// [users]
+    /// "jane@example.com" = 12
+    ///
+    /// [tasks]
+    /// "Website / Meetings" = 431
+    /// ```
+    #[cfg(feature = "config")]
+    pub fn from_toml(contents: &str) -> Result<Self, ClientError> {
+        toml::from_str(contents).map_err(|e| ClientError::Other(Box::new(e)))
+    }
+
+    /// Parses mapping rules written in YAML.
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(contents: &str) -> Result<Self, ClientError> {
+        serde_yaml::from_str(contents).map_err(|e| ClientError::Other(Box::new(e)))
+    }
+
+    /// Reads mapping rules from a `.toml`, `.yaml` or `.yml` file.
+    #[cfg(any(feature = "config", feature = "yaml"))]
+    pub fn from_path(path: &std::path::Path) -> Result<Self, ClientError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| ClientError::Message(format!("Cannot read {}: {}", path.display(), e)))?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            #[cfg(feature = "config")]
+            Some("toml") => Self::from_toml(&contents),
+            #[cfg(feature = "yaml")]
+            Some("yaml" | "yml") => Self::from_yaml(&contents),
+            _ => Err(ClientError::Message(format!(
+                "Unsupported mapping file {}",
+                path.display()
+            ))),
+        }
+    }
+
+    fn lookup(rules: &BTreeMap<String, i32>, name: &str) -> Option<i32> {
+        rules
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, id)| *id)
+    }
+}
+
+/// Settings of a time-track import
+#[derive(Debug, Clone, Default)]
+pub struct TimeTrackImport {
+    /// Layout of the CSV file
+    pub source: TimeTrackSource,
+    /// Rules translating source names to actiTIME ids
+    pub mapping: TimeTrackMapping,
+    /// Check the file and report what would be submitted without sending anything
+    pub dry_run: bool,
+    /// Treat schedule and leave warnings as errors
+    pub strict: bool,
+    /// Replace records that already hold a different time instead of reporting a conflict
+    pub overwrite: bool,
+}
+
+/// Outcome of one imported record
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordStatus {
+    /// The record passed validation and was not sent
+    Pending,
+    /// actiTIME already holds the same time
+    Unchanged,
+    /// actiTIME already holds a different time; not sent without `overwrite`
+    Conflict,
+    /// The record was submitted
+    Submitted,
+    /// The server refused the record
+    Failed,
+}
+
+impl fmt::Display for RecordStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            RecordStatus::Pending => "pending",
+            RecordStatus::Unchanged => "unchanged",
+            RecordStatus::Conflict => "conflict",
+            RecordStatus::Submitted => "submitted",
+            RecordStatus::Failed => "failed",
+        };
+        write!(f, "{}", status)
+    }
+}
+
+/// Source line that could not be read or resolved
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceError {
+    /// Line in the CSV file
+    pub line: u64,
+    /// What is wrong with the line
+    pub message: String,
+}
+
+/// Time of one user on one task and day, summed from the source entries
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedRecord {
+    /// Identifier of the task (`Task.id`)
+    pub task_id: i32,
+    /// Name of the task
+    pub task: String,
+    /// Imported time
+    pub time: WorkDuration,
+    /// Comments of the source entries, joined
+    pub comment: Option<String>,
+    /// Time actiTIME held for the task and day before the import
+    pub previous: Option<WorkDuration>,
+    /// Outcome of the record
+    pub status: RecordStatus,
+    /// Server error of a failed record
+    pub message: Option<String>,
+    /// Lines of the source entries
+    pub lines: Vec<u64>,
+}
+
+/// Imported records of one user on one day, with what the day looked like before the import
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedDay {
+    /// Identifier of the user (`User.id`)
+    pub user_id: i32,
+    /// Username of the user
+    pub user: String,
+    /// Day of the records
+    pub date: NaiveDate,
+    /// Scheduled working time
+    pub scheduled: WorkDuration,
+    /// Leave time
+    pub leave: WorkDuration,
+    /// Time tracked on every task before the import
+    pub tracked: WorkDuration,
+    /// Imported records, one per task
+    pub records: Vec<ImportedRecord>,
+    /// Findings that do not block the import
+    pub warnings: Vec<String>,
+    /// Findings that block the import
+    pub errors: Vec<String>,
+}
+
+impl ImportedDay {
+    /// Sums the time of the imported records.
+    pub fn imported(&self) -> WorkDuration {
+        self.records.iter().map(|record| record.time).sum()
+    }
+
+    /// Returns the time tracked on the day once the records are submitted.
+    pub fn tracked_after(&self) -> WorkDuration {
+        self.tracked
+            + self
+                .records
+                .iter()
+                .filter(|record| record.status != RecordStatus::Conflict)
+                .map(|record| record.time - record.previous.unwrap_or_default())
+                .sum()
+    }
+}
+
+/// Per user and day results of a time-track import
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TimeTrackImportReport {
+    /// Source lines that could not be read or resolved
+    pub errors: Vec<SourceError>,
+    /// Imported records grouped by user and day, in user then date order
+    pub days: Vec<ImportedDay>,
+}
+
+impl TimeTrackImportReport {
+    /// Returns the number of records with the given status.
+    pub fn count(&self, status: RecordStatus) -> usize {
+        self.records()
+            .filter(|record| record.status == status)
+            .count()
+    }
+
+    /// Returns `true` when a source line or a day blocks the import.
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty() || self.days.iter().any(|day| !day.errors.is_empty())
+    }
+
+    fn records(&self) -> impl Iterator<Item = &ImportedRecord> {
+        self.days.iter().flat_map(|day| day.records.iter())
+    }
+}
+
+impl fmt::Display for TimeTrackImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total: WorkDuration = self.days.iter().map(ImportedDay::imported).sum();
+        write!(
+            f,
+            "{} records on {} days, {}",
+            self.records().count(),
+            self.days.len(),
+            total
+        )?;
+        for status in [
+            RecordStatus::Submitted,
+            RecordStatus::Pending,
+            RecordStatus::Unchanged,
+            RecordStatus::Conflict,
+            RecordStatus::Failed,
+        ] {
+            let count = self.count(status);
+            if count > 0 {
+                write!(f, ", {} {}", count, status)?;
+            }
+        }
+        if self.has_errors() {
+            let day_errors: usize = self.days.iter().map(|day| day.errors.len()).sum();
+            write!(f, ", {} errors", self.errors.len() + day_errors)?;
+        }
+        Ok(())
+    }
+}
+
+/// Entry as read from the source file, before names are resolved
+struct SourceEntry {
+    line: u64,
+    user: String,
+    email: Option<String>,
+    date: NaiveDate,
+    customer: Option<String>,
+    project: String,
+    task: String,
+    time: WorkDuration,
+    comment: Option<String>,
+}
+
+/// Source entry with its names resolved
+struct ResolvedEntry {
+    line: u64,
+    user_id: i32,
+    user: String,
+    date: NaiveDate,
+    task_id: i32,
+    task: String,
+    time: WorkDuration,
+    comment: Option<String>,
+}
+
+impl TimeTrackImport {
+    /// Creates an import of the generic CSV layout without mapping rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `source` field and returns the modified import.
+    pub fn with_source(mut self, source: TimeTrackSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Sets the `mapping` field and returns the modified import.
+    pub fn with_mapping(mut self, mapping: TimeTrackMapping) -> Self {
+        self.mapping = mapping;
+        self
+    }
+
+    /// Sets the `dry_run` field and returns the modified import.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Sets the `strict` field and returns the modified import.
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Sets the `overwrite` field and returns the modified import.
+    pub fn with_overwrite(mut self, overwrite: bool) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// Reads a CSV export, resolves its names, checks every day and submits the records
+    /// unless something blocks the import or this is a dry run.
+    ///
+    /// # Arguments
+    /// * `client_context` - The client context used to resolve names and submit the records.
+    /// * `reader` - The CSV content, with a header row.
+    ///
+    /// # Returns
+    /// The per user and day report, or an error if the file cannot be read, lacks a column
+    /// of its layout, or the checks cannot be fetched.
+    ///
+    /// # Example
+    /// ``` ignore
+    /// let file = std::fs::File::open("toggl-march.csv")?;
+    /// let report = TimeTrackImport::new()
+    ///     .with_source(TimeTrackSource::Toggl)
+    ///     .with_mapping(TimeTrackMapping::from_path(Path::new("toggl.toml"))?)
+    ///     .with_dry_run(true)
+    ///     .run(&client_context, file)?;
+    /// for day in &report.days {
+    ///     println!("{} {}: {}", day.user, day.date, day.imported());
+    /// }
+    /// ```
+    pub fn run(
+        &self,
+        client_context: &ClientContext,
+        reader: impl Read,
+    ) -> Result<TimeTrackImportReport, ClientError> {
+        let mut report = TimeTrackImportReport::default();
+        let entries = self.read_entries(reader, &mut report)?;
+        let resolved = self.resolve(client_context, entries, &mut report)?;
+        report.errors.sort_by_key(|error| error.line);
+        report.days = self.check_days(client_context, resolved)?;
+
+        if self.dry_run || report.has_errors() {
+            return Ok(report);
+        }
+        submit(client_context, &mut report);
+        Ok(report)
+    }
+
+    fn read_entries(
+        &self,
+        reader: impl Read,
+        report: &mut TimeTrackImportReport,
+    ) -> Result<Vec<SourceEntry>, ClientError> {
+        let mut csv_reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(reader);
+        let headers = csv_reader
+            .headers()
+            .map_err(|e| ClientError::Other(Box::new(e)))?
+            .clone();
+        let position = |name: &str| {
+            headers
+                .iter()
+                .position(|header| header.eq_ignore_ascii_case(name))
+        };
+        let required = |name: &str| {
+            position(name).ok_or_else(|| {
+                ClientError::Message(format!("Missing column '{}' for {}", name, self.source))
+            })
+        };
+
+        match self.source {
+            TimeTrackSource::Generic => {
+                let columns = [
+                    required("user")?,
+                    required("date")?,
+                    required("project")?,
+                    required("task")?,
+                    required("time")?,
+                ];
+                let (customer, comment) = (position("customer"), position("comment"));
+                read_records(&mut csv_reader, report, |line, record| {
+                    let [user, date, project, task, time] = columns.map(|i| field(record, i));
+                    Ok(SourceEntry {
+                        line,
+                        user: user.to_string(),
+                        email: None,
+                        date: parse_date(date)?,
+                        customer: optional_field(record, customer),
+                        project: project.to_string(),
+                        task: task.to_string(),
+                        time: WorkDuration::from_str(time)
+                            .map_err(|_| format!("Invalid time '{}'", time))?,
+                        comment: optional_field(record, comment),
+                    })
+                })
+            }
+            TimeTrackSource::Toggl => {
+                let columns = [
+                    required("User")?,
+                    required("Project")?,
+                    required("Start date")?,
+                    required("Duration")?,
+                ];
+                let email = position("Email");
+                let client = position("Client");
+                let task = position("Task");
+                let description = position("Description");
+                read_records(&mut csv_reader, report, |line, record| {
+                    let [user, project, date, duration] = columns.map(|i| field(record, i));
+                    let description = optional_field(record, description);
+                    Ok(SourceEntry {
+                        line,
+                        user: user.to_string(),
+                        email: optional_field(record, email),
+                        date: parse_date(date)?,
+                        customer: optional_field(record, client),
+                        project: project.to_string(),
+                        task: optional_field(record, task)
+                            .or_else(|| description.clone())
+                            .unwrap_or_default(),
+                        time: parse_clock_duration(duration)
+                            .ok_or_else(|| format!("Invalid duration '{}'", duration))?,
+                        comment: description,
+                    })
+                })
+            }
+            TimeTrackSource::Harvest => {
+                let columns = [
+                    required("Date")?,
+                    required("Project")?,
+                    required("Task")?,
+                    required("Hours")?,
+                    required("First Name")?,
+                    required("Last Name")?,
+                ];
+                let (client, notes) = (position("Client"), position("Notes"));
+                read_records(&mut csv_reader, report, |line, record| {
+                    let [date, project, task, hours, first_name, last_name] =
+                        columns.map(|i| field(record, i));
+                    let time = hours
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(WorkDuration::try_from_hours_f64)
+                        .filter(|time| *time <= MAX_DAY_TIME)
+                        .ok_or_else(|| format!("Invalid hours '{}'", hours))?;
+                    Ok(SourceEntry {
+                        line,
+                        user: format!("{} {}", first_name, last_name),
+                        email: None,
+                        date: parse_date(date)?,
+                        customer: optional_field(record, client),
+                        project: project.to_string(),
+                        task: task.to_string(),
+                        time,
+                        comment: optional_field(record, notes),
+                    })
+                })
+            }
+        }
+    }
+
+    fn resolve(
+        &self,
+        client_context: &ClientContext,
+        entries: Vec<SourceEntry>,
+        report: &mut TimeTrackImportReport,
+    ) -> Result<Vec<ResolvedEntry>, ClientError> {
+        if entries.is_empty() {
+            return Ok(vec![]);
+        }
+        let mapping = &self.mapping;
+        let users = client_context.get_all_users(GetUsersParameters::new())?;
+        let customers = client_context.reference_data::<Customer>()?;
+        let projects = client_context.reference_data::<Project>()?;
+
+        let mut project_ids: Vec<Result<i32, String>> = vec![];
+        for entry in &entries {
+            let project_id = TimeTrackMapping::lookup(&mapping.projects, &entry.project)
+                .map(Ok)
+                .unwrap_or_else(|| {
+                    find_project(
+                        &customers,
+                        &projects,
+                        entry.customer.as_deref(),
+                        &entry.project,
+                    )
+                });
+            project_ids.push(project_id);
+        }
+
+        let project_filter: Vec<u32> = project_ids
+            .iter()
+            .flatten()
+            .filter_map(|id| u32::try_from(*id).ok())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let mapped_task_ids: Vec<u32> = mapping
+            .tasks
+            .values()
+            .filter_map(|id| u32::try_from(*id).ok())
+            .collect();
+        let mut tasks: Vec<Task> = vec![];
+        for chunk in project_filter.chunks(PAGE_SIZE as usize) {
+            let parameters = GetTasksParameters::new().with_project_ids(chunk.to_vec());
+            tasks.extend(client_context.get_all_tasks(parameters)?);
+        }
+        for chunk in mapped_task_ids.chunks(PAGE_SIZE as usize) {
+            let parameters = GetTasksParameters::new().with_task_ids(chunk.to_vec());
+            tasks.extend(client_context.get_all_tasks(parameters)?);
+        }
+
+        let mut resolved = vec![];
+        for (entry, project_id) in entries.into_iter().zip(project_ids) {
+            let user_id = resolve_user(mapping, &users, &entry);
+            let task = TimeTrackMapping::lookup(
+                &mapping.tasks,
+                &format!("{} / {}", entry.project, entry.task),
+            )
+            .or_else(|| TimeTrackMapping::lookup(&mapping.tasks, &entry.task))
+            .map(|task_id| {
+                tasks
+                    .iter()
+                    .find(|task| task.id == Some(task_id))
+                    .ok_or_else(|| format!("Mapped task {} does not exist", task_id))
+            })
+            .unwrap_or_else(|| {
+                let project_id = project_id?;
+                if entry.task.is_empty() {
+                    return Err(format!("No task for project '{}'", entry.project));
+                }
+                tasks
+                    .iter()
+                    .find(|task| {
+                        task.project_id == Some(project_id)
+                            && task.name.eq_ignore_ascii_case(&entry.task)
+                    })
+                    .ok_or_else(|| {
+                        format!(
+                            "Task '{}' does not exist in project '{}'",
+                            entry.task, entry.project
+                        )
+                    })
+            });
+
+            let mut messages = vec![];
+            if let Err(message) = &user_id {
+                messages.push(message.clone());
+            }
+            if let Err(message) = &task {
+                messages.push(message.clone());
+            }
+            if entry.time <= WorkDuration::ZERO {
+                messages.push(format!("Time must be positive, got {}", entry.time));
+            }
+            match (user_id, task) {
+                (Ok(user), Ok(task)) if messages.is_empty() => resolved.push(ResolvedEntry {
+                    line: entry.line,
+                    user_id: user.id,
+                    user: user.username.clone(),
+                    date: entry.date,
+                    task_id: task.id.unwrap_or_default(),
+                    task: task.name.clone(),
+                    time: entry.time,
+                    comment: entry.comment,
+                }),
+                _ => report.errors.push(SourceError {
+                    line: entry.line,
+                    message: messages.join("; "),
+                }),
+            }
+        }
+        Ok(resolved)
+    }
+
+    /// Groups the entries by user, day and task and compares each day with actiTIME.
+    fn check_days(
+        &self,
+        client_context: &ClientContext,
+        resolved: Vec<ResolvedEntry>,
+    ) -> Result<Vec<ImportedDay>, ClientError> {
+        let (Some(date_from), Some(date_to)) = (
+            resolved.iter().map(|entry| entry.date).min(),
+            resolved.iter().map(|entry| entry.date).max(),
+        ) else {
+            return Ok(vec![]);
+        };
+
+        let mut grouped: BTreeMap<(i32, NaiveDate), BTreeMap<i32, ImportedRecord>> =
+            BTreeMap::new();
+        let mut usernames = HashMap::new();
+        for entry in resolved {
+            usernames.insert(entry.user_id, entry.user.clone());
+            let record = grouped
+                .entry((entry.user_id, entry.date))
+                .or_default()
+                .entry(entry.task_id)
+                .or_insert_with(|| ImportedRecord {
+                    task_id: entry.task_id,
+                    task: entry.task.clone(),
+                    time: WorkDuration::ZERO,
+                    comment: None,
+                    previous: None,
+                    status: RecordStatus::Pending,
+                    message: None,
+                    lines: vec![],
+                });
+            record.time += entry.time;
+            record.lines.push(entry.line);
+            if let Some(comment) = entry.comment {
+                record.comment = Some(match record.comment.take() {
+                    Some(previous) if previous != comment => format!("{}; {}", previous, comment),
+                    Some(previous) => previous,
+                    None => comment,
+                });
+            }
+        }
+
+        let user_ids: Vec<i32> = grouped
+            .keys()
+            .map(|(user_id, _)| *user_id)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let time_track = client_context.get_complete_time_track(
+            GetTimeTrackParameters::new()
+                .with_dates(date_from..=date_to)
+                .with_user_ids(user_ids.clone()),
+        )?;
+        let leave_time = client_context.get_complete_leave_time(
+            GetLeaveTimeParameters::new()
+                .with_dates(date_from..=date_to)
+                .with_user_ids(user_ids.clone()),
+        )?;
+        let mut schedules = HashMap::new();
+        for user_id in &user_ids {
+            let route = get_users_schedule(*user_id, date_from..=date_to)?;
+            schedules.insert(*user_id, client_context.call_route(&route, None)?);
+        }
+        let mut days = vec![];
+        for ((user_id, date), records) in grouped {
+            let existing: Vec<&TimeTrackRecord> = time_track
+                .entries()
+                .filter(|entry| entry.user_id == user_id && entry.date == date)
+                .map(|entry| entry.record)
+                .collect();
+            let mut day = ImportedDay {
+                user_id,
+                user: usernames.get(&user_id).cloned().unwrap_or_default(),
+                date,
+                scheduled: schedules
+                    .get(&user_id)
+                    .and_then(|schedule| schedule.scheduled_time(date))
+                    .unwrap_or_default(),
+                leave: leave_time.total_for(user_id, date),
+                tracked: existing.iter().map(|record| record.time).sum(),
+                records: vec![],
+                warnings: vec![],
+                errors: vec![],
+            };
+
+            for mut record in records.into_values() {
+                record.previous = existing
+                    .iter()
+                    .find(|existing| existing.task_id == record.task_id)
+                    .map(|existing| existing.time)
+                    .filter(|time| *time > WorkDuration::ZERO);
+                record.status = match record.previous {
+                    Some(previous) if previous == record.time => RecordStatus::Unchanged,
+                    Some(_) if !self.overwrite => RecordStatus::Conflict,
+                    _ => RecordStatus::Pending,
+                };
+                day.records.push(record);
+            }
+
+            let mut warnings = vec![];
+            if day.scheduled == WorkDuration::ZERO {
+                warnings.push("Non-working day".to_string());
+            }
+            if day.leave > WorkDuration::ZERO {
+                warnings.push(format!("{} of leave", day.leave));
+            }
+            let tracked_after = day.tracked_after();
+            if day.scheduled > WorkDuration::ZERO && tracked_after + day.leave > day.scheduled {
+                warnings.push(format!(
+                    "{} tracked and {} of leave exceed the schedule of {}",
+                    tracked_after, day.leave, day.scheduled
+                ));
+            }
+            if tracked_after > MAX_DAY_TIME {
+                day.errors.push(format!(
+                    "{} tracked exceeds {}",
+                    tracked_after, MAX_DAY_TIME
+                ));
+            }
+            if self.strict {
+                day.errors.extend(warnings);
+            } else {
+                day.warnings = warnings;
+            }
+            days.push(day);
+        }
+        Ok(days)
+    }
+}
+
+/// Submits the pending records through batch requests and records each outcome.
+fn submit(client_context: &ClientContext, report: &mut TimeTrackImportReport) {
+    let mut pending: Vec<(NaiveDate, i32, &mut ImportedRecord)> = report
+        .days
+        .iter_mut()
+        .flat_map(|day| {
+            let (user_id, date) = (day.user_id, day.date);
+            day.records
+                .iter_mut()
+                .filter(|record| record.status == RecordStatus::Pending)
+                .map(move |record| (date, user_id, record))
+        })
+        .collect();
+
+    for chunk in pending.chunks_mut(BATCH_SIZE) {
+        let items: Result<Vec<BatchRequestItem<EntityFields, TimeTrackRecord>>, ClientError> =
+            chunk
+                .iter()
+                .map(|(date, user_id, record)| {
+                    let mut fields = EntityFields::new().with_field("time", record.time)?;
+                    if let Some(comment) = &record.comment {
+                        fields = fields.with_field("comment", comment)?;
+                    }
+                    Ok(
+                        BatchRequestItem::from(patch_time_track(*user_id, *date, record.task_id))
+                            .with_body(fields),
+                    )
+                })
+                .collect();
+        let route = post_batch_requests(Some(PostBatchRequestsIncludeResponseBody::Never));
+        let results = items.and_then(|items| client_context.call_route(&route, Some(&items)));
+        match results {
+            Ok(results) => {
+                for (index, (_, _, record)) in chunk.iter_mut().enumerate() {
+                    match results.get(index) {
+                        Some(result) if result.is_success() => {
+                            record.status = RecordStatus::Submitted
+                        }
+                        Some(result) => {
+                            record.status = RecordStatus::Failed;
+                            record.message = result.error_message();
+                        }
+                        None => {
+                            record.status = RecordStatus::Failed;
+                            record.message = Some("Missing from the batch response".to_string());
+                        }
+                    }
+                }
+            }
+            Err(error) => {
+                for (_, _, record) in chunk.iter_mut() {
+                    record.status = RecordStatus::Failed;
+                    record.message = Some(error.to_string());
+                }
+            }
+        }
+    }
+}
+
+/// Parses every record of the file, collecting unreadable lines into the report.
+fn read_records<R: Read>(
+    csv_reader: &mut csv::Reader<R>,
+    report: &mut TimeTrackImportReport,
+    parse: impl Fn(u64, &csv::StringRecord) -> Result<SourceEntry, String>,
+) -> Result<Vec<SourceEntry>, ClientError> {
+    let mut entries = vec![];
+    for record in csv_reader.records() {
+        let record = record.map_err(|e| ClientError::Other(Box::new(e)))?;
+        let line = record.position().map(|p| p.line()).unwrap_or_default();
+        match parse(line, &record) {
+            Ok(entry) => entries.push(entry),
+            Err(message) => report.errors.push(SourceError { line, message }),
+        }
+    }
+    Ok(entries)
+}
+
+fn field(record: &csv::StringRecord, index: usize) -> &str {
+    record.get(index).unwrap_or_default()
+}
+
+fn optional_field(record: &csv::StringRecord, index: Option<usize>) -> Option<String> {
+    index
+        .and_then(|index| record.get(index))
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date '{}', expected YYYY-MM-DD", date))
+}
+
+/// Parses an `HH:MM:SS` duration, rounded to the nearest minute. Every part is unsigned.
+fn parse_clock_duration(duration: &str) -> Option<WorkDuration> {
+    let mut parts = duration.split(':').map(|part| {
+        let part = part.trim();
+        if part.bytes().all(|byte| byte.is_ascii_digit()) {
+            part.parse::<i64>().ok()
+        } else {
+            None
+        }
+    });
+    let (hours, minutes, seconds) = (parts.next()??, parts.next()??, parts.next()??);
+    if parts.next().is_some() || minutes >= 60 || seconds >= 60 {
+        return None;
+    }
+    let rounded = hours
+        .checked_mul(60)?
+        .checked_add(minutes + i64::from(seconds >= 30))?;
+    Some(WorkDuration::from_minutes(rounded))
+}
+
+fn resolve_user<'a>(
+    mapping: &TimeTrackMapping,
+    users: &'a [User],
+    entry: &SourceEntry,
+) -> Result<&'a User, String> {
+    let names = std::iter::once(entry.user.as_str()).chain(entry.email.as_deref());
+    for name in names.clone() {
+        if let Some(user_id) = TimeTrackMapping::lookup(&mapping.users, name) {
+            return users
+                .iter()
+                .find(|user| user.id == user_id)
+                .ok_or_else(|| format!("Mapped user {} does not exist", user_id));
+        }
+    }
+    for name in names {
+        let matches: Vec<&User> = users
+            .iter()
+            .filter(|user| {
+                user.username.eq_ignore_ascii_case(name)
+                    || user.email.eq_ignore_ascii_case(name)
+                    || user
+                        .full_name
+                        .as_deref()
+                        .is_some_and(|full_name| full_name.eq_ignore_ascii_case(name))
+                    || format!("{} {}", user.first_name, user.last_name).eq_ignore_ascii_case(name)
+            })
+            .collect();
+        match matches.as_slice() {
+            [user] => return Ok(user),
+            [] => continue,
+            _ => return Err(format!("User '{}' matches several users", name)),
+        }
+    }
+    Err(format!("User '{}' does not exist", entry.user))
+}
+
+fn find_project(
+    customers: &[Customer],
+    projects: &[Project],
+    customer: Option<&str>,
+    project: &str,
+) -> Result<i32, String> {
+    let customer_id = match customer {
+        Some(name) => Some(
+            customers
+                .iter()
+                .find(|customer| customer.name.eq_ignore_ascii_case(name))
+                .map(|customer| customer.id)
+                .ok_or_else(|| format!("Customer '{}' does not exist", name))?,
+        ),
+        None => None,
+    };
+    let matches: Vec<&Project> = projects
+        .iter()
+        .filter(|candidate| {
+            candidate.name.eq_ignore_ascii_case(project)
+                && customer_id.is_none_or(|id| candidate.customer_id == id)
+        })
+        .collect();
+    match matches.as_slice() {
+        [project] => Ok(project.id),
+        [] => Err(format!("Project '{}' does not exist", project)),
+        _ => Err(format!(
+            "Project '{}' exists for several customers, add a customer or a mapping rule",
+            project
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(
+        source: TimeTrackSource,
+        csv: &str,
+    ) -> Result<(Vec<SourceEntry>, TimeTrackImportReport), ClientError> {
+        let mut report = TimeTrackImportReport::default();
+        let entries = TimeTrackImport::new()
+            .with_source(source)
+            .read_entries(csv.as_bytes(), &mut report)?;
+        Ok((entries, report))
+    }
+
+    #[test]
+    fn reads_generic_entries() -> Result<(), ClientError> {
+        let (entries, report) = read(
+            TimeTrackSource::Generic,
+            "user,date,project,task,time,comment\n\
+             jdoe,2024-03-04,Website,Design,1h 30m,Mockups\n\
+             jdoe,04/03/2024,Website,Design,1h,\n",
+        )?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].time, WorkDuration::from_minutes(90));
+        assert_eq!(entries[0].comment.as_deref(), Some("Mockups"));
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].line, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn reads_toggl_entries() -> Result<(), ClientError> {
+        let (entries, report) = read(
+            TimeTrackSource::Toggl,
+            "User,Email,Client,Project,Description,Start date,Duration\n\
+             Jane Doe,jane@example.com,Acme,Website,Design,2024-03-04,01:29:30\n\
+             Jane Doe,jane@example.com,Acme,Website,Design,2024-03-04,01:75:00\n",
+        )?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].time, WorkDuration::from_minutes(90));
+        assert_eq!(entries[0].task, "Design");
+        assert_eq!(entries[0].email.as_deref(), Some("jane@example.com"));
+        assert_eq!(report.errors.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_out_of_range_harvest_hours() -> Result<(), ClientError> {
+        let (entries, report) = read(
+            TimeTrackSource::Harvest,
+            "Date,Client,Project,Task,Notes,Hours,First Name,Last Name\n\
+             2024-03-04,Acme,Website,Design,,1.5,Jane,Doe\n\
+             2024-03-04,Acme,Website,Design,,NaN,Jane,Doe\n\
+             2024-03-04,Acme,Website,Design,,inf,Jane,Doe\n\
+             2024-03-04,Acme,Website,Design,,1e300,Jane,Doe\n\
+             2024-03-04,Acme,Website,Design,,25,Jane,Doe\n",
+        )?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].user, "Jane Doe");
+        assert_eq!(entries[0].time, WorkDuration::from_minutes(90));
+        assert_eq!(report.errors.len(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_missing_columns() {
+        assert!(read(TimeTrackSource::Harvest, "Date,Project,Task\n").is_err());
+    }
+
+    #[test]
+    fn parses_clock_durations() {
+        assert_eq!(
+            parse_clock_duration("2:00:29"),
+            Some(WorkDuration::from_minutes(120))
+        );
+        assert_eq!(parse_clock_duration("2:00"), None);
+        assert_eq!(parse_clock_duration("1:-5:00"), None);
+        assert_eq!(parse_clock_duration("-1:05:00"), None);
+        assert_eq!(parse_clock_duration("1:05:+10"), None);
+        assert_eq!(parse_clock_duration("1::00"), None);
+        assert_eq!(parse_clock_duration("999999999999999999:00:00"), None);
+    }
+}